@@ -0,0 +1,49 @@
+//! Times the per-event work of rendering a timestamp: reading the clock and
+//! converting it to the timer's offset, with the offset validated once up
+//! front or again for every event, and a full `format_time` call.
+//!
+//! Run with `cargo bench --bench offset`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use clia_local_time::LocalTime;
+use time::{OffsetDateTime, UtcOffset};
+use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
+
+const ITERATIONS: u32 = 10_000_000;
+
+fn main() {
+    let tz = black_box((8, 0, 0));
+    let offset = UtcOffset::from_hms(tz.0, tz.1, tz.2).unwrap_or(UtcOffset::UTC);
+
+    report("validated once", || {
+        black_box(OffsetDateTime::now_utc().to_offset(black_box(offset)));
+    });
+    report("validated per event", || {
+        let offset = UtcOffset::from_hms(tz.0, tz.1, tz.2).unwrap_or(UtcOffset::UTC);
+        black_box(OffsetDateTime::now_utc().to_offset(offset));
+    });
+
+    let timer = LocalTime::rfc_3339();
+    let mut s = String::with_capacity(64);
+    report("format_time", || {
+        s.clear();
+        timer.format_time(&mut Writer::new(&mut s)).unwrap();
+        black_box(&s);
+    });
+}
+
+/// Prints the mean time per call of `f` over `ITERATIONS` calls.
+fn report(name: &str, mut f: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let per_call = start.elapsed().as_nanos() / u128::from(ITERATIONS);
+    println!(
+        "{:<20} {:>5?}/event",
+        name,
+        Duration::from_nanos(per_call as u64)
+    );
+}
@@ -0,0 +1,93 @@
+//! A self-describing header for log files.
+
+use std::fmt;
+
+use time::{format_description::well_known::Rfc3339, UtcOffset};
+
+use crate::{clock::ClockSource, offset::OffsetProvider, LocalTime};
+
+/// The absolute UTC time together with a timer's settings, so a log file stays
+/// self-describing even when later lines use a short format.
+///
+/// Returned by [`LocalTime::anchor`] and logged by [`LocalTime::emit_anchor`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Anchor {
+    /// The current UTC time in RFC 3339.
+    pub utc: String,
+    /// The configured offset, e.g. `+08:00:00`.
+    pub offset: String,
+    /// The `Debug` rendering of the format in use.
+    pub format: String,
+}
+
+impl fmt::Display for Anchor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "utc={} offset={} format={}",
+            self.utc, self.offset, self.format
+        )
+    }
+}
+
+// === impl LocalTime ===
+
+impl<F: fmt::Debug, P: OffsetProvider, C: ClockSource> LocalTime<F, P, C> {
+    /// Returns the current UTC time, read as the timer's timestamps are,
+    /// along with this timer's offset and format.
+    pub fn anchor(&self) -> Anchor {
+        let config = self.config();
+        Anchor {
+            utc: self
+                .now()
+                .to_offset(UtcOffset::UTC)
+                .format(&Rfc3339)
+                .unwrap_or_default(),
+            offset: config.offset,
+            format: config.format,
+        }
+    }
+
+    /// Logs the [`anchor`](LocalTime::anchor) as a one-time `INFO` header
+    /// event with target `clia_local_time::anchor`.
+    ///
+    /// Call it right after installing the subscriber, so it becomes the first
+    /// line of every log file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::macros::format_description;
+    ///
+    /// let timer = LocalTime::with_timezone(format_description!("[hour]:[minute]:[second]"), (8, 0, 0));
+    /// let subscriber = tracing_subscriber::fmt()
+    ///     .with_timer(timer.clone())
+    ///     .finish();
+    /// tracing::subscriber::with_default(subscriber, || timer.emit_anchor());
+    /// ```
+    pub fn emit_anchor(&self) {
+        let anchor = self.anchor();
+        tracing::info!(
+            target: "clia_local_time::anchor",
+            utc = %anchor.utc,
+            offset = %anchor.offset,
+            format = %anchor.format,
+            "timestamp anchor"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{clock::Fixed, LocalTime};
+    use time::macros::datetime;
+
+    #[test]
+    fn test_anchor_reads_timer_clock() {
+        let timer = LocalTime::w3c((8, 0, 0))
+            .with_clock(Fixed(datetime!(2024-05-01 02:00 UTC)))
+            .with_skew(time::Duration::MINUTE);
+        assert_eq!(timer.anchor().utc, "2024-05-01T02:01:00Z");
+    }
+}
@@ -0,0 +1,168 @@
+//! Styling timestamps with ANSI escape codes on the console.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{self, IsTerminal};
+
+use time::formatting::Formattable;
+use tracing_subscriber::fmt::format::Writer;
+
+use crate::{clock::ClockSource, offset::OffsetProvider, LocalTime};
+
+/// An ANSI text style for timestamps, set with
+/// [`LocalTime::with_ansi_style`].
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::AnsiStyle;
+///
+/// assert_eq!(AnsiStyle::DIM.sgr(), "2");
+/// assert_eq!(AnsiStyle::new("1;34").sgr(), "1;34");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AnsiStyle {
+    sgr: Cow<'static, str>,
+}
+
+// === impl AnsiStyle ===
+
+impl AnsiStyle {
+    /// Dimmed text, as tracing-subscriber renders its own timestamps.
+    pub const DIM: Self = Self::from_static("2");
+    /// Bold text.
+    pub const BOLD: Self = Self::from_static("1");
+    /// Green text.
+    pub const GREEN: Self = Self::from_static("32");
+    /// Blue text.
+    pub const BLUE: Self = Self::from_static("34");
+    /// Cyan text.
+    pub const CYAN: Self = Self::from_static("36");
+    /// Gray text.
+    pub const GRAY: Self = Self::from_static("90");
+
+    /// Returns a style from the parameters of an SGR escape sequence, e.g.
+    /// `1;34` for bold blue or `38;5;245` for a 256-color gray.
+    pub fn new(sgr: impl Into<Cow<'static, str>>) -> Self {
+        Self { sgr: sgr.into() }
+    }
+
+    const fn from_static(sgr: &'static str) -> Self {
+        Self {
+            sgr: Cow::Borrowed(sgr),
+        }
+    }
+
+    /// The parameters of the SGR escape sequence.
+    pub fn sgr(&self) -> &str {
+        &self.sgr
+    }
+}
+
+// === impl LocalTime ===
+
+impl<F, P, C> LocalTime<F, P, C> {
+    /// Wraps the timestamp, including its decoration, in the ANSI escape
+    /// codes of `style` when the `fmt` layer writes ANSI escapes and stdout,
+    /// tracing-subscriber's default writer, is a terminal, which makes dense
+    /// console output easier to scan. The style replaces the dimming
+    /// tracing-subscriber applies to timestamps.
+    ///
+    /// Whether stdout is a terminal is checked here, once. For layers writing
+    /// elsewhere, use [`LocalTime::with_ansi_style_for`]. Layers built with
+    /// `with_ansi(false)`, e.g. because `NO_COLOR` is set, get plain
+    /// timestamps too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::{AnsiStyle, LocalTime};
+    ///
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(LocalTime::rfc_3339().with_ansi_style(AnsiStyle::CYAN));
+    /// # drop(collector);
+    /// ```
+    pub fn with_ansi_style(self, style: AnsiStyle) -> Self {
+        self.with_ansi_style_for(style, &io::stdout())
+    }
+
+    /// Like [`LocalTime::with_ansi_style`], but only styles timestamps if
+    /// `stream`, the one the layer writes to, is a terminal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::{AnsiStyle, LocalTime};
+    ///
+    /// let timer = LocalTime::rfc_3339().with_ansi_style_for(AnsiStyle::DIM, &std::io::stderr());
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_writer(std::io::stderr)
+    ///     .with_timer(timer);
+    /// # drop(collector);
+    /// ```
+    pub fn with_ansi_style_for(self, style: AnsiStyle, stream: &impl IsTerminal) -> Self {
+        Self {
+            ansi_style: stream.is_terminal().then_some(style),
+            ..self
+        }
+    }
+}
+
+impl<F: Formattable, P: OffsetProvider, C: ClockSource> LocalTime<F, P, C> {
+    /// Writes the current time, styled if `w` takes ANSI escapes.
+    pub(crate) fn write_styled(&self, w: &mut Writer<'_>) -> fmt::Result {
+        match &self.ansi_style {
+            Some(style) if w.has_ansi_escapes() => {
+                // Resets first, replacing the dimming of the `fmt` layer.
+                write!(w, "\x1b[0;{}m", style.sgr)?;
+                self.write_now(w)?;
+                w.write_str("\x1b[0m")
+            }
+            _ => self.write_now(w),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AnsiStyle;
+    use crate::{clock::Fixed, testing::Capture, LocalTime};
+    use time::macros::datetime;
+
+    #[test]
+    fn test_ansi_style() {
+        for ansi in [true, false] {
+            let capture = Capture::default();
+            let writer = capture.clone();
+            let timer =
+                LocalTime::w3c((8, 0, 0)).with_clock(Fixed(datetime!(2024-05-01 02:00 UTC)));
+            // As styled for a terminal.
+            let timer = LocalTime {
+                ansi_style: Some(AnsiStyle::new("1;34")),
+                ..timer
+            };
+            let subscriber = tracing_subscriber::fmt()
+                .with_ansi(ansi)
+                .with_timer(timer)
+                .with_writer(move || writer.clone())
+                .finish();
+            tracing::subscriber::with_default(subscriber, || tracing::info!("hello"));
+
+            let output = capture.output();
+            let expected = if ansi {
+                "\x1b[0;1;34m2024-05-01 10:00:00\x1b[0m"
+            } else {
+                "2024-05-01 10:00:00 "
+            };
+            assert!(output.contains(expected), "{:?}", output);
+            assert_eq!(output.contains('\x1b'), ansi);
+        }
+    }
+
+    #[test]
+    fn test_ansi_style_suppressed() {
+        let file = std::fs::File::open(std::env::current_exe().unwrap()).unwrap();
+        let timer = LocalTime::w3c((8, 0, 0)).with_ansi_style_for(AnsiStyle::BOLD, &file);
+        assert_eq!(timer.ansi_style, None);
+    }
+}
@@ -0,0 +1,274 @@
+//! A rolling log file that rotates at local hour, day, week or month
+//! boundaries.
+//!
+//! `tracing_appender::rolling` rotates at UTC boundaries and stamps file names
+//! with the UTC date, so in UTC+8 `app.log.2024-05-01` holds lines from 08:00
+//! on that day until 08:00 the next. [`RollingFile`] computes both in an
+//! [`OffsetProvider`], normally the one the log timestamps are formatted with.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use time::{macros::format_description, Duration, OffsetDateTime, UtcOffset};
+use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::offset::{FixedOffset, OffsetProvider};
+use crate::{Boundary, LocalTime};
+
+/// How often a [`RollingFile`] starts a new file: at every local
+/// [`Boundary`].
+///
+/// Files are named after the start of the period they hold:
+///
+/// - [`Hourly`](Boundary::Hourly): e.g. `app.log.2024-05-01-10`.
+/// - [`Daily`](Boundary::Daily): e.g. `app.log.2024-05-01`.
+/// - [`Weekly`](Boundary::Weekly): the day the week started, e.g.
+///   `app.log.2024-04-29` for a week starting on Monday.
+/// - [`Monthly`](Boundary::Monthly): e.g. `app.log.2024-05`.
+pub type Rotation = Boundary;
+
+/// A log file in `directory`, named `prefix.<date>`, starting a new file at
+/// every local [`Rotation`] boundary.
+///
+/// It implements [`io::Write`], so it can be wrapped in
+/// [`tracing_appender::non_blocking`], and [`MakeWriter`] for writing from
+/// the calling thread.
+#[derive(Debug)]
+pub struct RollingFile<P = FixedOffset> {
+    naming: Naming<P>,
+    state: Mutex<State>,
+}
+
+/// Where files go and when they change.
+#[derive(Debug)]
+struct Naming<P> {
+    directory: PathBuf,
+    prefix: String,
+    rotation: Rotation,
+    offset: P,
+}
+
+/// A handle writing to the current file, returned by
+/// [`RollingFile::make_writer`].
+#[derive(Debug)]
+pub struct RollingWriter<'a>(MutexGuard<'a, State>);
+
+#[derive(Debug)]
+struct State {
+    file: File,
+    /// The UTC instant the next file starts at.
+    next: OffsetDateTime,
+}
+
+// === impl RollingFile ===
+
+impl<P: OffsetProvider> RollingFile<P> {
+    /// Opens, or creates, the current file in `directory`, which is created
+    /// too if missing.
+    pub fn new(
+        directory: impl AsRef<Path>,
+        prefix: impl Into<String>,
+        rotation: Rotation,
+        offset: P,
+    ) -> io::Result<Self> {
+        let naming = Naming {
+            directory: directory.as_ref().to_path_buf(),
+            prefix: prefix.into(),
+            rotation,
+            offset,
+        };
+        let state = naming.open(OffsetDateTime::now_utc())?;
+        Ok(Self {
+            naming,
+            state: Mutex::new(state),
+        })
+    }
+
+    /// Returns the path of the file holding lines written at `utc`.
+    pub fn path_at(&self, utc: OffsetDateTime) -> PathBuf {
+        self.naming.path_at(utc)
+    }
+
+    /// Moves the writes to a background thread with
+    /// [`tracing_appender::non_blocking`].
+    pub fn non_blocking(self) -> (NonBlocking, WorkerGuard)
+    where
+        P: Send + 'static,
+    {
+        tracing_appender::non_blocking(self)
+    }
+
+    fn lock(&self) -> io::Result<MutexGuard<'_, State>> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = OffsetDateTime::now_utc();
+        if now >= state.next {
+            *state = self.naming.open(now)?;
+        }
+        Ok(state)
+    }
+}
+
+impl<P: OffsetProvider> Write for RollingFile<P> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.lock()?.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.lock()?.file.flush()
+    }
+}
+
+impl<'a, P: OffsetProvider + 'a> MakeWriter<'a> for RollingFile<P> {
+    type Writer = RollingWriter<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        // Keep writing to the old file if the new one can't be opened.
+        let state = self
+            .lock()
+            .unwrap_or_else(|_| self.state.lock().unwrap_or_else(|e| e.into_inner()));
+        RollingWriter(state)
+    }
+}
+
+// === impl Naming ===
+
+impl<P: OffsetProvider> Naming<P> {
+    fn path_at(&self, utc: OffsetDateTime) -> PathBuf {
+        let local = utc.to_offset(self.offset.offset_at(utc));
+        let stamp = match self.rotation {
+            Boundary::Hourly => local.format(format_description!("[year]-[month]-[day]-[hour]")),
+            Boundary::Daily => local.format(format_description!("[year]-[month]-[day]")),
+            Boundary::Weekly(weekday) => {
+                let days = (7 + local.weekday().number_days_from_monday()
+                    - weekday.number_days_from_monday())
+                    % 7;
+                (local - Duration::days(days.into()))
+                    .format(format_description!("[year]-[month]-[day]"))
+            }
+            Boundary::Monthly => local.format(format_description!("[year]-[month]")),
+        }
+        .unwrap_or_default();
+        self.directory.join(format!("{}.{}", self.prefix, stamp))
+    }
+
+    /// The UTC instant of the first boundary after `utc`.
+    fn next_boundary(&self, utc: OffsetDateTime) -> OffsetDateTime {
+        let local = utc.to_offset(self.offset.offset_at(utc));
+        self.rotation
+            .next_after(local, &self.offset)
+            .to_offset(UtcOffset::UTC)
+    }
+
+    fn open(&self, now: OffsetDateTime) -> io::Result<State> {
+        fs::create_dir_all(&self.directory)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path_at(now))?;
+        Ok(State {
+            file,
+            next: self.next_boundary(now),
+        })
+    }
+}
+
+// === impl RollingWriter ===
+
+impl Write for RollingWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.file.flush()
+    }
+}
+
+// === impl LocalTime ===
+
+impl<F, P: OffsetProvider + Clone, C> LocalTime<F, P, C> {
+    /// Returns a rolling file that rotates at local boundaries in this
+    /// timer's offset, so file names match the timestamps inside.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use clia_local_time::{appender::Rotation, LocalTime};
+    ///
+    /// let timer = LocalTime::rfc_3339();
+    /// let file = timer
+    ///     .rolling_file(Rotation::Daily, "/var/log/app", "app.log")
+    ///     .expect("log directory should be writable");
+    /// let (writer, _guard) = file.non_blocking();
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(timer)
+    ///     .with_writer(writer);
+    /// # drop(collector);
+    /// ```
+    pub fn rolling_file(
+        &self,
+        rotation: Rotation,
+        directory: impl AsRef<Path>,
+        prefix: impl Into<String>,
+    ) -> io::Result<RollingFile<P>> {
+        RollingFile::new(directory, prefix, rotation, self.offset.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Naming, RollingFile, Rotation};
+    use crate::offset::FixedOffset;
+    use std::io::Write;
+    use time::macros::{datetime, offset};
+    use time::Weekday;
+
+    #[test]
+    fn test_local_boundaries() {
+        let dir = std::env::temp_dir().join(format!("clia-local-time-{}", std::process::id()));
+        let mut file =
+            RollingFile::new(&dir, "app.log", Rotation::Daily, FixedOffset(offset!(+8))).unwrap();
+        let at = datetime!(2024-05-01 17:00 UTC);
+        assert_eq!(file.path_at(at), dir.join("app.log.2024-05-02"));
+        assert_eq!(
+            file.naming.next_boundary(at),
+            datetime!(2024-05-02 16:00 UTC)
+        );
+
+        file.write_all(b"line\n").unwrap();
+        let path = file.path_at(time::OffsetDateTime::now_utc());
+        assert_eq!(std::fs::read(&path).unwrap(), b"line\n");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let hourly = Naming {
+            rotation: Rotation::Hourly,
+            ..file.naming
+        };
+        assert_eq!(hourly.path_at(at), dir.join("app.log.2024-05-02-01"));
+        assert_eq!(hourly.next_boundary(at), datetime!(2024-05-01 18:00 UTC));
+
+        // 2024-05-02 is a Thursday in UTC+8.
+        let weekly = Naming {
+            rotation: Rotation::Weekly(Weekday::Monday),
+            ..hourly
+        };
+        assert_eq!(weekly.path_at(at), dir.join("app.log.2024-04-29"));
+        assert_eq!(weekly.next_boundary(at), datetime!(2024-05-05 16:00 UTC));
+        let weekly = Naming {
+            rotation: Rotation::Weekly(Weekday::Thursday),
+            ..weekly
+        };
+        assert_eq!(weekly.path_at(at), dir.join("app.log.2024-05-02"));
+        assert_eq!(weekly.next_boundary(at), datetime!(2024-05-08 16:00 UTC));
+
+        let monthly = Naming {
+            rotation: Rotation::Monthly,
+            ..weekly
+        };
+        assert_eq!(monthly.path_at(at), dir.join("app.log.2024-05"));
+        assert_eq!(monthly.next_boundary(at), datetime!(2024-05-31 16:00 UTC));
+    }
+}
@@ -0,0 +1,205 @@
+//! A backend trait over the datetime libraries this crate can format with.
+//!
+//! Downstream crates can take a [`TimezoneSpec`] as their timezone option and
+//! build the timer with [`DefaultBackend`], or any [`TimeBackend`], without
+//! naming `time`, chrono or jiff types in their own API. Each backend builds
+//! the same timer type whichever features are enabled, so enabling one, e.g.
+//! in another crate of the build, never changes the type another returns.
+//!
+//! # Examples
+//!
+//! ```
+//! use clia_local_time::backend::{DefaultBackend, TimeBackend};
+//! use clia_local_time::TimezoneSpec;
+//!
+//! let spec: TimezoneSpec = "+08:00".parse().expect("spec should be valid");
+//! let timer = DefaultBackend::rfc_3339(&spec).expect("spec should resolve");
+//! let collector = tracing_subscriber::fmt()
+//!     .with_timer(timer);
+//! # drop(collector);
+//! ```
+
+use time::format_description::OwnedFormatItem;
+use tracing_subscriber::fmt::time::FormatTime;
+
+use crate::{LocalTime, SpecOffset, TimeFormatSpec, TimezoneError, TimezoneSpec};
+
+/// Builds timers with one datetime library.
+pub trait TimeBackend {
+    /// The timer this backend builds.
+    type Timer: FormatTime + Send + Sync + 'static;
+
+    /// The custom formats this backend's timers render, e.g. a parsed `time`
+    /// format description.
+    type Format;
+
+    /// Returns a timer rendering RFC 3339 timestamps in the timezone `spec`
+    /// names.
+    fn rfc_3339(spec: &TimezoneSpec) -> Result<Self::Timer, TimezoneError>;
+
+    /// Returns a timer rendering `format` in the timezone `spec` names.
+    fn with_format(spec: &TimezoneSpec, format: Self::Format)
+        -> Result<Self::Timer, TimezoneError>;
+}
+
+/// The `time` backend, building [`LocalTime`]s with a [`SpecOffset`].
+///
+/// Named zones need the `jiff` feature. They and [`TimezoneSpec::System`]
+/// follow their daylight saving transitions.
+#[derive(Clone, Copy, Debug)]
+pub struct Time;
+
+/// The chrono backend, building [`ChronoLocalTime`](crate::ChronoLocalTime)s.
+///
+/// Timezones are resolved as by [`Time`].
+#[cfg(feature = "chrono")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+#[derive(Clone, Copy, Debug)]
+pub struct Chrono;
+
+/// The jiff backend, building [`JiffLocalTime`](crate::JiffLocalTime)s.
+///
+/// Named zones and the system zone follow their daylight saving transitions.
+#[cfg(feature = "jiff")]
+#[cfg_attr(docsrs, doc(cfg(feature = "jiff")))]
+#[derive(Clone, Copy, Debug)]
+pub struct Jiff;
+
+/// The backend downstream crates should default to: [`Time`], whatever
+/// features are enabled.
+pub type DefaultBackend = Time;
+
+/// The RFC 3339 format description, rendered by the specialized writer.
+fn rfc_3339_format() -> OwnedFormatItem {
+    TimeFormatSpec::Rfc3339
+        .format()
+        .expect("the RFC 3339 description should be valid")
+}
+
+// === impl Time ===
+
+impl TimeBackend for Time {
+    type Timer = LocalTime<OwnedFormatItem, SpecOffset>;
+    type Format = OwnedFormatItem;
+
+    fn rfc_3339(spec: &TimezoneSpec) -> Result<Self::Timer, TimezoneError> {
+        Ok(LocalTime {
+            fast_rfc3339: true,
+            ..Self::with_format(spec, rfc_3339_format())?
+        })
+    }
+
+    fn with_format(
+        spec: &TimezoneSpec,
+        format: Self::Format,
+    ) -> Result<Self::Timer, TimezoneError> {
+        LocalTime::with_spec(format, spec)
+    }
+}
+
+// === impl Chrono ===
+
+#[cfg(feature = "chrono")]
+impl TimeBackend for Chrono {
+    type Timer = crate::ChronoLocalTime;
+    /// Parsed [`strftime`] items, e.g. from `StrftimeItems::parse_to_owned`.
+    ///
+    /// [`strftime`]: https://docs.rs/chrono/0.4/chrono/format/strftime/index.html
+    type Format = Vec<::chrono::format::Item<'static>>;
+
+    fn rfc_3339(spec: &TimezoneSpec) -> Result<Self::Timer, TimezoneError> {
+        Ok(crate::ChronoLocalTime::rfc_3339(chrono_offset(spec)?))
+    }
+
+    fn with_format(
+        spec: &TimezoneSpec,
+        format: Self::Format,
+    ) -> Result<Self::Timer, TimezoneError> {
+        Ok(crate::ChronoLocalTime {
+            items: Some(format),
+            offset: chrono_offset(spec)?,
+        })
+    }
+}
+
+/// The offset `spec` resolves to now.
+#[cfg(feature = "chrono")]
+fn chrono_offset(spec: &TimezoneSpec) -> Result<::chrono::FixedOffset, TimezoneError> {
+    ::chrono::FixedOffset::east_opt(spec.resolve()?.whole_seconds())
+        .ok_or_else(|| TimezoneError::Invalid(spec.to_string()))
+}
+
+// === impl Jiff ===
+
+#[cfg(feature = "jiff")]
+impl TimeBackend for Jiff {
+    type Timer = crate::JiffLocalTime<OwnedFormatItem>;
+    type Format = OwnedFormatItem;
+
+    fn rfc_3339(spec: &TimezoneSpec) -> Result<Self::Timer, TimezoneError> {
+        let mut timer = Self::with_format(spec, rfc_3339_format())?;
+        timer.fast_rfc3339 = true;
+        Ok(timer)
+    }
+
+    fn with_format(
+        spec: &TimezoneSpec,
+        format: Self::Format,
+    ) -> Result<Self::Timer, TimezoneError> {
+        use ::jiff::tz::{Offset, TimeZone};
+
+        let zone = match spec {
+            TimezoneSpec::Fixed(offset) => {
+                let offset = Offset::from_seconds(offset.whole_seconds())
+                    .map_err(|_| TimezoneError::Invalid(spec.to_string()))?;
+                TimeZone::fixed(offset)
+            }
+            TimezoneSpec::Named(name) => {
+                TimeZone::get(name).map_err(|_| TimezoneError::UnknownZone(name.clone()))?
+            }
+            TimezoneSpec::System => {
+                TimeZone::try_system().map_err(|_| TimezoneError::IndeterminateSystemOffset)?
+            }
+        };
+        Ok(crate::JiffLocalTime::with_zone(zone, format))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Time, TimeBackend};
+    use crate::{testing::render, TimezoneError, TimezoneSpec};
+    use time::macros::{datetime, format_description, offset};
+
+    #[test]
+    fn test_time_backend() {
+        let spec = TimezoneSpec::Fixed(offset!(+8));
+        let at = datetime!(2024-05-01 02:00 UTC);
+        assert_eq!(
+            render(&Time::rfc_3339(&spec).unwrap(), at),
+            "2024-05-01T10:00:00+08:00"
+        );
+        let format = format_description!("[hour]:[minute]");
+        let timer = Time::with_format(&spec, format.into()).unwrap();
+        assert_eq!(render(&timer, at), "10:00");
+        #[cfg(feature = "chrono")]
+        {
+            let items = chrono::format::StrftimeItems::new("%H:%M")
+                .parse_to_owned()
+                .unwrap();
+            assert!(super::Chrono::with_format(&spec, items).is_ok());
+            assert!(super::Chrono::rfc_3339(&spec).is_ok());
+        }
+        #[cfg(not(feature = "jiff"))]
+        assert_eq!(
+            Time::rfc_3339(&TimezoneSpec::Named("Europe/Berlin".to_string())).unwrap_err(),
+            TimezoneError::UnknownZone("Europe/Berlin".to_string())
+        );
+        #[cfg(feature = "jiff")]
+        assert_eq!(
+            super::Jiff::rfc_3339(&TimezoneSpec::Named("Nowhere/Atlantis".to_string()))
+                .unwrap_err(),
+            TimezoneError::UnknownZone("Nowhere/Atlantis".to_string())
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Rendering the timestamp on a background thread.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, PoisonError, RwLock, Weak};
+use std::thread;
+use std::time::Duration;
+
+use time::formatting::Formattable;
+use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
+
+use crate::{
+    clock::{ClockSource, SystemClock},
+    offset::{FixedOffset, OffsetProvider},
+    LocalTime,
+};
+
+/// A timer whose timestamp is pre-rendered by a background thread every
+/// `interval`; formatting an event only copies the latest rendition.
+///
+/// The thread stops once every clone of the timer has been dropped (which
+/// happens when the subscriber is dropped), or when
+/// [`shutdown`](BackgroundTime::shutdown) is called. Timestamps can lag behind
+/// the actual time by up to `interval`.
+///
+/// Returned by [`LocalTime::background`].
+#[cfg_attr(docsrs, doc(cfg(feature = "background")))]
+#[derive(Clone, Debug)]
+pub struct BackgroundTime<F, P = FixedOffset, C = SystemClock> {
+    shared: Arc<Shared<F, P, C>>,
+}
+
+#[derive(Debug)]
+struct Shared<F, P, C> {
+    timer: LocalTime<F, P, C>,
+    rendered: RwLock<String>,
+    stop: AtomicBool,
+}
+
+// === impl BackgroundTime ===
+
+impl<F, P, C> BackgroundTime<F, P, C>
+where
+    F: Formattable + Send + Sync + 'static,
+    P: OffsetProvider + Send + Sync + 'static,
+    C: ClockSource + Send + Sync + 'static,
+{
+    fn spawn(timer: LocalTime<F, P, C>, interval: Duration) -> Self {
+        let shared = Arc::new(Shared {
+            timer,
+            rendered: RwLock::new(String::new()),
+            stop: AtomicBool::new(false),
+        });
+        shared.render();
+        let weak = Arc::downgrade(&shared);
+        thread::Builder::new()
+            .name("clia-local-time".into())
+            .spawn(move || run(weak, interval))
+            .expect("failed to spawn the timestamp thread");
+        Self { shared }
+    }
+}
+
+impl<F, P, C> BackgroundTime<F, P, C> {
+    /// Stops the background thread. Later events keep the last rendition.
+    pub fn shutdown(&self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+impl<F: Formattable, P: OffsetProvider, C: ClockSource> Shared<F, P, C> {
+    fn render(&self) {
+        let mut rendered = String::new();
+        if self
+            .timer
+            .format_decorated(self.timer.now(), &mut Writer::new(&mut rendered))
+            .is_ok()
+        {
+            *self
+                .rendered
+                .write()
+                .unwrap_or_else(PoisonError::into_inner) = rendered;
+        }
+    }
+}
+
+fn run<F, P, C>(shared: Weak<Shared<F, P, C>>, interval: Duration)
+where
+    F: Formattable,
+    P: OffsetProvider,
+    C: ClockSource,
+{
+    loop {
+        thread::sleep(interval);
+        match shared.upgrade() {
+            Some(shared) if !shared.stop.load(Ordering::Relaxed) => shared.render(),
+            _ => return,
+        }
+    }
+}
+
+impl<F, P, C> FormatTime for BackgroundTime<F, P, C> {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        let rendered = self
+            .shared
+            .rendered
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        w.write_str(&rendered)
+    }
+}
+
+// === impl LocalTime ===
+
+impl<F, P, C> LocalTime<F, P, C>
+where
+    F: Formattable + Send + Sync + 'static,
+    P: OffsetProvider + Send + Sync + 'static,
+    C: ClockSource + Send + Sync + 'static,
+{
+    /// Spawns a thread that renders the timestamp every `interval`, so that
+    /// formatting an event only copies a string, like high-performance loggers
+    /// do.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the thread can't be spawned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use clia_local_time::LocalTime;
+    ///
+    /// let timer = LocalTime::rfc_3339().background(Duration::from_micros(500));
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(timer);
+    /// # drop(collector);
+    /// ```
+    pub fn background(self, interval: Duration) -> BackgroundTime<F, P, C> {
+        BackgroundTime::spawn(self, interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{testing::render_now, LocalTime};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_background_stops() {
+        let timer = LocalTime::rfc_3339().background(Duration::from_millis(1));
+        assert!(!render_now(&timer).is_empty());
+
+        let weak = Arc::downgrade(&timer.shared);
+        drop(timer);
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(weak.upgrade().is_none());
+    }
+}
@@ -0,0 +1,167 @@
+//! Annotating retry log lines with the next scheduled attempt.
+
+use std::fmt;
+use std::time::Duration;
+
+use time::{formatting::Formattable, OffsetDateTime};
+use tracing_subscriber::fmt::format::Writer;
+
+use crate::clock::{ClockSource, SystemClock};
+use crate::duration::{format_duration, DurationStyle};
+use crate::offset::{FixedOffset, OffsetProvider};
+use crate::LocalTime;
+
+/// Exponential backoff state: the delay before each retry grows by `factor`,
+/// starting at `initial` and capped at `max`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use clia_local_time::Backoff;
+///
+/// let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(5));
+/// assert_eq!(backoff.advance(), Duration::from_secs(1));
+/// assert_eq!(backoff.advance(), Duration::from_secs(2));
+/// assert_eq!(backoff.advance(), Duration::from_secs(4));
+/// assert_eq!(backoff.advance(), Duration::from_secs(5));
+/// assert_eq!(backoff.attempt(), 4);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    factor: u32,
+    attempt: u32,
+}
+
+// === impl Backoff ===
+
+impl Backoff {
+    /// Returns a backoff that doubles the delay on every attempt.
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max,
+            factor: 2,
+            attempt: 0,
+        }
+    }
+
+    /// Sets the factor the delay grows by on every attempt.
+    pub fn with_factor(self, factor: u32) -> Self {
+        Self { factor, ..self }
+    }
+
+    /// The number of attempts scheduled so far.
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    /// The delay before the next attempt.
+    pub fn delay(&self) -> Duration {
+        let growth = self.factor.saturating_pow(self.attempt);
+        self.initial.saturating_mul(growth).min(self.max)
+    }
+
+    /// Schedules the next attempt, returning the delay before it.
+    pub fn advance(&mut self) -> Duration {
+        let delay = self.delay();
+        self.attempt = self.attempt.saturating_add(1);
+        delay
+    }
+
+    /// Starts over from the initial delay, e.g. after a success.
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+/// A retry annotation, rendered with a timer's format and zone, e.g.
+/// `10:00:00 (retry 3 in 4.0s at 10:00:04)`.
+///
+/// Returned by [`LocalTime::retry_note`].
+#[derive(Debug)]
+pub struct RetryNote<'a, F, P = FixedOffset, C = SystemClock> {
+    timer: &'a LocalTime<F, P, C>,
+    now: OffsetDateTime,
+    attempt: u32,
+    delay: Duration,
+}
+
+impl<F, P, C> fmt::Display for RetryNote<'_, F, P, C>
+where
+    F: Formattable,
+    P: OffsetProvider,
+    C: ClockSource,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut w = Writer::new(f);
+        self.timer.format_timestamp(self.now, &mut w)?;
+        write!(
+            w,
+            " (retry {} in {} at ",
+            self.attempt,
+            format_duration(self.delay, DurationStyle::Compact)
+        )?;
+        // The offset may change before the attempt is due.
+        let due = self.now + self.delay;
+        let due = due.to_offset(self.timer.offset.offset_at(due));
+        self.timer.format_timestamp(due, &mut w)?;
+        w.write_char(')')
+    }
+}
+
+// === impl LocalTime ===
+
+impl<F: Formattable, P: OffsetProvider, C: ClockSource> LocalTime<F, P, C> {
+    /// Returns a note with the current time and when the next attempt of
+    /// `backoff` is due, for use in retry log lines.
+    ///
+    /// The note describes the attempt that [`Backoff::advance`] schedules
+    /// next, so call it before advancing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use clia_local_time::{Backoff, LocalTime};
+    ///
+    /// let timer = LocalTime::w3c((8, 0, 0));
+    /// let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(60));
+    /// let note = timer.retry_note(&backoff).to_string();
+    /// backoff.advance();
+    /// # drop(note);
+    /// ```
+    pub fn retry_note(&self, backoff: &Backoff) -> RetryNote<'_, F, P, C> {
+        RetryNote {
+            timer: self,
+            now: self.now(),
+            attempt: backoff.attempt() + 1,
+            delay: backoff.delay(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Backoff, RetryNote};
+    use crate::LocalTime;
+    use std::time::Duration;
+    use time::macros::{datetime, format_description};
+
+    #[test]
+    fn test_retry_note() {
+        let timer = LocalTime::new(format_description!("[hour]:[minute]:[second]"));
+        let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(60));
+        backoff.advance();
+        backoff.advance();
+        let note = RetryNote {
+            timer: &timer,
+            now: datetime!(2024-05-01 10:00:00 UTC),
+            attempt: backoff.attempt() + 1,
+            delay: backoff.delay(),
+        };
+        assert_eq!(note.to_string(), "10:00:00 (retry 3 in 4.0s at 10:00:04)");
+    }
+}
@@ -0,0 +1,122 @@
+//! Reusing the rendered timestamp while the second stays the same.
+
+use std::fmt;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::RwLock;
+
+use time::{formatting::Formattable, OffsetDateTime};
+use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
+
+use crate::{
+    clock::{ClockSource, SystemClock},
+    offset::{FixedOffset, OffsetProvider},
+    LocalTime,
+};
+
+/// A [`LocalTime`] that renders its timestamp at most once per second.
+///
+/// Only use this with second-resolution formats, e.g. `[hour]:[minute]:[second]`
+/// or [`LocalTime::w3c`]: within a second every event reuses the same rendered
+/// string, so subsecond components would be frozen.
+///
+/// Returned by [`LocalTime::cached`].
+#[derive(Debug)]
+pub struct CachedTime<F, P = FixedOffset, C = SystemClock> {
+    timer: LocalTime<F, P, C>,
+    /// The epoch second `cache` was rendered for, checked before locking.
+    second: AtomicI64,
+    cache: RwLock<(i64, String)>,
+}
+
+// === impl CachedTime ===
+
+impl<F: Formattable, P: OffsetProvider, C: ClockSource> CachedTime<F, P, C> {
+    fn format_cached(&self, now: OffsetDateTime, w: &mut Writer<'_>) -> fmt::Result {
+        let second = now.unix_timestamp();
+        if self.second.load(Ordering::Acquire) == second {
+            if let Ok(cache) = self.cache.read() {
+                if cache.0 == second {
+                    return w.write_str(&cache.1);
+                }
+            }
+        }
+
+        let mut rendered = String::new();
+        self.timer
+            .format_decorated(now, &mut Writer::new(&mut rendered))?;
+        w.write_str(&rendered)?;
+        // Another thread may be rendering too; never block on the cache.
+        if let Ok(mut cache) = self.cache.try_write() {
+            if second > cache.0 {
+                *cache = (second, rendered);
+                self.second.store(second, Ordering::Release);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<F: Formattable, P: OffsetProvider, C: ClockSource> FormatTime for CachedTime<F, P, C> {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        let now = self.timer.now();
+        self.format_cached(now, w)
+    }
+}
+
+// === impl LocalTime ===
+
+impl<F, P, C> LocalTime<F, P, C> {
+    /// Renders the timestamp once per second and reuses it for every other
+    /// event within that second, which saves formatting work in services
+    /// logging thousands of events per second.
+    ///
+    /// See [`CachedTime`] for the formats this is suitable for.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    ///
+    /// let timer = LocalTime::w3c((8, 0, 0)).cached();
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(timer);
+    /// # drop(collector);
+    /// ```
+    pub fn cached(self) -> CachedTime<F, P, C> {
+        CachedTime {
+            timer: self,
+            second: AtomicI64::new(i64::MIN),
+            cache: RwLock::new((i64::MIN, String::new())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LocalTime;
+    use time::macros::datetime;
+    use tracing_subscriber::fmt::format::Writer;
+
+    #[test]
+    fn test_cache_per_second() {
+        let timer = LocalTime::w3c((0, 0, 0)).cached();
+        let render = |now| {
+            let mut s = String::new();
+            timer.format_cached(now, &mut Writer::new(&mut s)).unwrap();
+            s
+        };
+        assert_eq!(
+            render(datetime!(2024-05-01 10:00:00.1 UTC)),
+            "2024-05-01 10:00:00"
+        );
+        assert_eq!(
+            render(datetime!(2024-05-01 10:00:00.9 UTC)),
+            "2024-05-01 10:00:00"
+        );
+        assert_eq!(
+            render(datetime!(2024-05-01 10:00:01 UTC)),
+            "2024-05-01 10:00:01"
+        );
+        assert_eq!(timer.cache.read().unwrap().1, "2024-05-01 10:00:01");
+    }
+}
@@ -0,0 +1,195 @@
+//! Timestamps in the ordinal, ISO week and Julian calendars used by
+//! reporting, astronomy and satellite operations logs.
+
+use std::fmt;
+
+use time::{format_description::StaticFormatDescription, macros::format_description, UtcOffset};
+use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
+
+use crate::clock::{ClockSource, SystemClock};
+use crate::LocalTime;
+
+/// Formats the current time as a Julian Date with five truncated decimals, e.g.
+/// `2460431.91666`, the number of days since noon UTC on 4713-11-24 BC.
+///
+/// The configured offset shifts the day like it shifts a calendar date; use
+/// UTC for conventional Julian Dates. Returned by [`LocalTime::julian_date`].
+#[derive(Clone, Copy, Debug)]
+pub struct JulianDate<C = SystemClock> {
+    offset: UtcOffset,
+    clock: C,
+}
+
+// === impl JulianDate ===
+
+impl JulianDate {
+    /// Returns a formatter for Julian Dates in `offset`.
+    pub fn new(offset: UtcOffset) -> Self {
+        Self {
+            offset,
+            clock: SystemClock::new(),
+        }
+    }
+}
+
+impl<C> JulianDate<C> {
+    /// Reads the time from `clock` instead of the system clock.
+    pub fn with_clock<C2: ClockSource>(self, clock: C2) -> JulianDate<C2> {
+        JulianDate {
+            offset: self.offset,
+            clock,
+        }
+    }
+}
+
+impl<C: ClockSource> FormatTime for JulianDate<C> {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        let now = self.clock.now_utc().to_offset(self.offset);
+        // Julian days start at noon.
+        let seconds = i64::from(now.to_julian_day()) * 86_400
+            + i64::from(now.hour()) * 3600
+            + i64::from(now.minute()) * 60
+            + i64::from(now.second())
+            - 43_200;
+        let days = seconds.div_euclid(86_400);
+        let fraction = seconds.rem_euclid(86_400) * 100_000 / 86_400;
+        write!(w, "{}.{:05}", days, fraction)
+    }
+}
+
+// === impl LocalTime ===
+
+impl LocalTime<StaticFormatDescription> {
+    /// Returns a formatter for ISO 8601 ordinal dates, the year and the day
+    /// of the year, e.g. `2024-122T10:00:00+08:00`.
+    ///
+    /// Timezone format: (tz_hours, tz_minutes, tz_seconds)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::macros::datetime;
+    ///
+    /// let mut s = String::new();
+    /// LocalTime::ordinal_date((8, 0, 0))
+    ///     .format_datetime(datetime!(2024-05-01 02:00 UTC), &mut s)
+    ///     .unwrap();
+    /// assert_eq!(s, "2024-122T10:00:00+08:00");
+    /// ```
+    pub fn ordinal_date(tz_hms: (i8, i8, i8)) -> Self {
+        Self::with_timezone(
+            format_description!(
+                "[year]-[ordinal]T[hour]:[minute]:[second][offset_hour sign:mandatory]:[offset_minute]"
+            ),
+            tz_hms,
+        )
+    }
+
+    /// Returns a formatter for ordinal dates with a space-separated time and
+    /// no offset, e.g. `2024-122 10:00:00`, as some aerospace logging
+    /// standards require. See [`LocalTime::ordinal_date`] for the ISO 8601
+    /// form.
+    ///
+    /// Timezone format: (tz_hours, tz_minutes, tz_seconds)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::macros::datetime;
+    ///
+    /// let mut s = String::new();
+    /// LocalTime::day_of_year((8, 0, 0))
+    ///     .format_datetime(datetime!(2024-12-31 18:00 UTC), &mut s)
+    ///     .unwrap();
+    /// assert_eq!(s, "2025-001 02:00:00");
+    /// ```
+    pub fn day_of_year(tz_hms: (i8, i8, i8)) -> Self {
+        Self::with_timezone(
+            format_description!("[year]-[ordinal] [hour]:[minute]:[second]"),
+            tz_hms,
+        )
+    }
+
+    /// Returns a formatter for ISO 8601 week dates, the week-numbering year,
+    /// the week and the weekday from Monday as `1`, e.g.
+    /// `2024-W18-3 10:00:00+08:00`.
+    ///
+    /// Timezone format: (tz_hours, tz_minutes, tz_seconds)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::macros::datetime;
+    ///
+    /// let mut s = String::new();
+    /// LocalTime::iso_week_date((8, 0, 0))
+    ///     .format_datetime(datetime!(2024-05-01 02:00 UTC), &mut s)
+    ///     .unwrap();
+    /// assert_eq!(s, "2024-W18-3 10:00:00+08:00");
+    /// ```
+    pub fn iso_week_date(tz_hms: (i8, i8, i8)) -> Self {
+        Self::with_timezone(
+            format_description!(
+                "[year base:iso_week]-W[week_number repr:iso]-[weekday repr:monday] \
+                 [hour]:[minute]:[second][offset_hour sign:mandatory]:[offset_minute]"
+            ),
+            tz_hms,
+        )
+    }
+
+    /// Returns a formatter for Julian Dates, e.g. `2460431.91666`.
+    ///
+    /// Timezone format: (tz_hours, tz_minutes, tz_seconds)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    ///
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(LocalTime::julian_date((0, 0, 0)));
+    /// # drop(collector);
+    /// ```
+    pub fn julian_date(tz_hms: (i8, i8, i8)) -> JulianDate {
+        JulianDate::new(UtcOffset::from_hms(tz_hms.0, tz_hms.1, tz_hms.2).unwrap_or(UtcOffset::UTC))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JulianDate;
+    use crate::clock::Fixed;
+    use crate::testing::{render, render_now};
+    use crate::LocalTime;
+    use time::macros::{datetime, offset};
+
+    #[test]
+    fn test_julian_date() {
+        let render = |at| render_now(&JulianDate::new(offset!(UTC)).with_clock(Fixed(at)));
+        assert_eq!(render(datetime!(2000-01-01 12:00 UTC)), "2451545.00000");
+        assert_eq!(render(datetime!(2000-01-01 0:00 UTC)), "2451544.50000");
+        assert_eq!(render(datetime!(2024-05-01 10:00 UTC)), "2460431.91666");
+    }
+
+    #[test]
+    fn test_iso_week_date() {
+        // The week-numbering year differs from the calendar year here.
+        let timer = LocalTime::iso_week_date((0, 0, 0));
+        assert_eq!(
+            render(&timer, datetime!(2024-12-30 0:00 UTC)),
+            "2025-W01-1 00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_day_of_year() {
+        let timer = LocalTime::day_of_year((-5, 0, 0));
+        assert_eq!(
+            render(&timer, datetime!(2025-01-01 03:00 UTC)),
+            "2024-366 22:00:00"
+        );
+    }
+}
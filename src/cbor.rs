@@ -0,0 +1,239 @@
+//! A [`Layer`] that writes events as [CBOR] records, for binary logs.
+//!
+//! Each event is encoded as one CBOR map, and records follow one another as
+//! a [CBOR sequence]:
+//!
+//! | Key      | Value                                                    |
+//! |----------|----------------------------------------------------------|
+//! | `ts`     | tag 1 (epoch-based date/time) wrapping float seconds     |
+//! | `tz`     | the offset the timestamp was taken in, e.g. `+08:00`     |
+//! | `level`  | the event's level as text, e.g. `INFO`                   |
+//! | `target` | the event's target as text                               |
+//! | `fields` | a map of the event's fields, including `message`         |
+//!
+//! [CBOR]: https://www.rfc-editor.org/rfc/rfc8949
+//! [CBOR sequence]: https://www.rfc-editor.org/rfc/rfc8742
+
+use std::fmt;
+use std::io::Write;
+
+use time::{macros::format_description, OffsetDateTime};
+use tracing_core::{
+    field::{Field, Visit},
+    Event, Subscriber,
+};
+use tracing_subscriber::{
+    fmt::MakeWriter,
+    layer::{Context, Layer},
+};
+
+use crate::{
+    clock::{ClockSource, SystemClock},
+    offset::{FixedOffset, OffsetProvider},
+    LocalTime,
+};
+
+/// A [`Layer`] that writes every event as a CBOR record.
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::{cbor::CborLayer, LocalTime};
+/// use tracing_subscriber::layer::SubscriberExt;
+///
+/// let timer = LocalTime::rfc_3339();
+/// let subscriber = tracing_subscriber::registry()
+///     .with(CborLayer::new(timer, std::io::stderr));
+/// # drop(subscriber);
+/// ```
+#[derive(Debug)]
+pub struct CborLayer<W, F, P = FixedOffset, C = SystemClock> {
+    make_writer: W,
+    timer: LocalTime<F, P, C>,
+}
+
+// === impl CborLayer ===
+
+impl<W, F, P, C> CborLayer<W, F, P, C>
+where
+    W: for<'writer> MakeWriter<'writer> + 'static,
+{
+    /// Returns a layer that timestamps events with `timer`, and writes them
+    /// to `make_writer`.
+    ///
+    /// Only the instant `timer` reads is used: its clock, offset provider,
+    /// skew, time scale and monotonicity guard apply, its format doesn't.
+    pub fn new(timer: LocalTime<F, P, C>, make_writer: W) -> Self {
+        Self { make_writer, timer }
+    }
+}
+
+impl<S, W, F, P, C> Layer<S> for CborLayer<W, F, P, C>
+where
+    S: Subscriber,
+    W: for<'writer> MakeWriter<'writer> + 'static,
+    F: 'static,
+    P: OffsetProvider + 'static,
+    C: ClockSource + 'static,
+{
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let now = self.timer.now();
+        let zone = now
+            .offset()
+            .format(format_description!(
+                "[offset_hour sign:mandatory]:[offset_minute]"
+            ))
+            .unwrap_or_default();
+        let mut fields = FieldEncoder::default();
+        event.record(&mut fields);
+
+        let meta = event.metadata();
+        let mut buf = Vec::with_capacity(64 + fields.buf.len());
+        head(&mut buf, MAJOR_MAP, 5);
+        text(&mut buf, "ts");
+        epoch(&mut buf, now);
+        text(&mut buf, "tz");
+        text(&mut buf, &zone);
+        text(&mut buf, "level");
+        text(&mut buf, meta.level().as_str());
+        text(&mut buf, "target");
+        text(&mut buf, meta.target());
+        text(&mut buf, "fields");
+        head(&mut buf, MAJOR_MAP, fields.len);
+        buf.extend_from_slice(&fields.buf);
+
+        let _ = self.make_writer.make_writer_for(meta).write_all(&buf);
+    }
+}
+
+/// Encodes recorded fields as CBOR map entries.
+#[derive(Default)]
+struct FieldEncoder {
+    buf: Vec<u8>,
+    len: u64,
+}
+
+impl FieldEncoder {
+    fn key(&mut self, field: &Field) {
+        self.len += 1;
+        text(&mut self.buf, field.name());
+    }
+}
+
+impl Visit for FieldEncoder {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.key(field);
+        float(&mut self.buf, value);
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.key(field);
+        match u64::try_from(value) {
+            Ok(value) => head(&mut self.buf, MAJOR_UNSIGNED, value),
+            // -1 - value, without overflowing at i64::MIN.
+            Err(_) => head(&mut self.buf, MAJOR_NEGATIVE, !(value as u64)),
+        }
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.key(field);
+        head(&mut self.buf, MAJOR_UNSIGNED, value);
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.key(field);
+        self.buf.push(if value { TRUE } else { FALSE });
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.key(field);
+        text(&mut self.buf, value);
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.key(field);
+        text(&mut self.buf, &format!("{:?}", value));
+    }
+}
+
+const MAJOR_UNSIGNED: u8 = 0;
+const MAJOR_NEGATIVE: u8 = 1;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_TAG: u8 = 6;
+const TAG_EPOCH: u64 = 1;
+const FALSE: u8 = 0xf4;
+const TRUE: u8 = 0xf5;
+const FLOAT64: u8 = 0xfb;
+
+fn head(buf: &mut Vec<u8>, major: u8, n: u64) {
+    let major = major << 5;
+    if n < 24 {
+        buf.push(major | n as u8);
+    } else if let Ok(n) = u8::try_from(n) {
+        buf.extend_from_slice(&[major | 24, n]);
+    } else if let Ok(n) = u16::try_from(n) {
+        buf.push(major | 25);
+        buf.extend_from_slice(&n.to_be_bytes());
+    } else if let Ok(n) = u32::try_from(n) {
+        buf.push(major | 26);
+        buf.extend_from_slice(&n.to_be_bytes());
+    } else {
+        buf.push(major | 27);
+        buf.extend_from_slice(&n.to_be_bytes());
+    }
+}
+
+fn text(buf: &mut Vec<u8>, s: &str) {
+    head(buf, MAJOR_TEXT, s.len() as u64);
+    buf.extend_from_slice(s.as_bytes());
+}
+
+fn float(buf: &mut Vec<u8>, value: f64) {
+    buf.push(FLOAT64);
+    buf.extend_from_slice(&value.to_be_bytes());
+}
+
+fn epoch(buf: &mut Vec<u8>, now: OffsetDateTime) {
+    head(buf, MAJOR_TAG, TAG_EPOCH);
+    float(buf, now.unix_timestamp_nanos() as f64 / 1e9);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{epoch, head, text, CborLayer, MAJOR_NEGATIVE};
+    use crate::{clock::Fixed, offset::FixedOffset, testing::Capture, LocalTime};
+    use time::macros::{datetime, offset};
+    use time::{format_description::well_known::Rfc3339, Duration};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_encoding() {
+        let mut buf = Vec::new();
+        epoch(&mut buf, datetime!(1970-01-01 00:00:01.5 UTC));
+        assert_eq!(buf, [0xc1, 0xfb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0]);
+
+        let mut buf = Vec::new();
+        head(&mut buf, MAJOR_NEGATIVE, !(-500i64 as u64));
+        assert_eq!(buf, [0x39, 0x01, 0xf3]);
+    }
+
+    #[test]
+    fn test_layer_uses_timer() {
+        let timer = LocalTime::with_provider(Rfc3339, FixedOffset(offset!(+8)))
+            .with_clock(Fixed(datetime!(2024-05-01 02:00 UTC)))
+            .with_skew(Duration::seconds(90));
+        let capture = Capture::default();
+        let writer = capture.clone();
+        let subscriber =
+            tracing_subscriber::registry().with(CborLayer::new(timer, move || writer.clone()));
+        tracing::subscriber::with_default(subscriber, || tracing::info!("hello"));
+
+        let record = capture.bytes();
+        let (mut ts, mut tz) = (Vec::new(), Vec::new());
+        epoch(&mut ts, datetime!(2024-05-01 02:01:30 UTC));
+        text(&mut tz, "+08:00");
+        let contains = |part: &[u8]| record.windows(part.len()).any(|w| w == part);
+        assert!(contains(&ts) && contains(&tz), "{:x?}", record);
+    }
+}
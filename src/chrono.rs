@@ -0,0 +1,158 @@
+//! A [`chrono`]-based timer for projects already standardized on chrono.
+//!
+//! [`ChronoLocalTime`] mirrors [`LocalTime`](crate::LocalTime), but takes a
+//! chrono [`strftime`] format and a [`FixedOffset`]. It formats with chrono
+//! alone: built with `default-features = false, features = ["chrono"]`, the
+//! crate doesn't depend on `time`, at the cost of everything else, which is
+//! built on its types. With `std`, chrono offsets convert to the crate's
+//! [`FixedOffset`](crate::offset::FixedOffset) and `TimezoneSpec`.
+//!
+//! [`strftime`]: https://docs.rs/chrono/0.4/chrono/format/strftime/index.html
+
+use std::fmt;
+
+use ::chrono::format::{Item, ParseError, StrftimeItems};
+use ::chrono::{FixedOffset, SecondsFormat, Utc};
+#[cfg(feature = "std")]
+use time::UtcOffset;
+use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
+
+#[cfg(feature = "std")]
+use crate::TimezoneSpec;
+
+/// Formats the current time in a [`FixedOffset`] using a chrono [`strftime`]
+/// format.
+///
+/// [`strftime`]: https://docs.rs/chrono/0.4/chrono/format/strftime/index.html
+#[derive(Clone, Debug)]
+pub struct ChronoLocalTime {
+    pub(crate) items: Option<Vec<Item<'static>>>,
+    pub(crate) offset: FixedOffset,
+}
+
+// === impl ChronoLocalTime ===
+
+impl ChronoLocalTime {
+    /// Returns a formatter that formats the current time in the provided offset
+    /// in the [RFC 3339] format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::FixedOffset;
+    /// use clia_local_time::ChronoLocalTime;
+    ///
+    /// let offset = FixedOffset::east_opt(8 * 3600).unwrap();
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(ChronoLocalTime::rfc_3339(offset));
+    /// # drop(collector);
+    /// ```
+    ///
+    /// [RFC 3339]: https://datatracker.ietf.org/doc/html/rfc3339
+    pub fn rfc_3339(offset: FixedOffset) -> Self {
+        Self {
+            items: None,
+            offset,
+        }
+    }
+
+    /// Returns a formatter that formats the current time in the provided offset
+    /// using a chrono [`strftime`] format.
+    ///
+    /// The format is parsed up front, so an invalid format is reported here
+    /// rather than as a failed log line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::FixedOffset;
+    /// use clia_local_time::ChronoLocalTime;
+    ///
+    /// let offset = FixedOffset::east_opt(8 * 3600).unwrap();
+    /// let timer = ChronoLocalTime::with_format("%Y-%m-%d %H:%M:%S%.3f", offset)
+    ///     .expect("format string should be valid!");
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(timer);
+    /// # drop(collector);
+    /// ```
+    ///
+    /// [`strftime`]: https://docs.rs/chrono/0.4/chrono/format/strftime/index.html
+    pub fn with_format(format: &str, offset: FixedOffset) -> Result<Self, ParseError> {
+        Ok(Self {
+            items: Some(StrftimeItems::new(format).parse_to_owned()?),
+            offset,
+        })
+    }
+}
+
+// === impl FixedOffset ===
+
+/// Converts a chrono offset for [`LocalTime`](crate::LocalTime), which
+/// always succeeds as chrono's offsets stay within a day.
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::{offset::FixedOffset, LocalTime};
+/// use time::format_description::well_known::Rfc3339;
+///
+/// let offset = chrono::FixedOffset::east_opt(8 * 3600).unwrap();
+/// let collector = tracing_subscriber::fmt()
+///     .with_timer(LocalTime::with_provider(Rfc3339, FixedOffset::from(offset)));
+/// # drop(collector);
+/// ```
+#[cfg(feature = "std")]
+impl From<FixedOffset> for crate::offset::FixedOffset {
+    fn from(offset: FixedOffset) -> Self {
+        crate::offset::FixedOffset(utc_offset(offset))
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<FixedOffset> for TimezoneSpec {
+    fn from(offset: FixedOffset) -> Self {
+        TimezoneSpec::Fixed(utc_offset(offset))
+    }
+}
+
+#[cfg(feature = "std")]
+fn utc_offset(offset: FixedOffset) -> UtcOffset {
+    UtcOffset::from_whole_seconds(offset.local_minus_utc())
+        .expect("chrono offsets are less than a day")
+}
+
+impl FormatTime for ChronoLocalTime {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        let now = Utc::now().with_timezone(&self.offset);
+        match &self.items {
+            Some(items) => write!(w, "{}", now.format_with_items(items.iter())),
+            None => w.write_str(&now.to_rfc3339_opts(SecondsFormat::AutoSi, false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChronoLocalTime;
+    use ::chrono::FixedOffset;
+
+    #[test]
+    fn test_invalid_format() {
+        let offset = FixedOffset::east_opt(8 * 3600).unwrap();
+        assert!(ChronoLocalTime::with_format("%Y-%m-%d", offset).is_ok());
+        assert!(ChronoLocalTime::with_format("%Q", offset).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_from_chrono_offset() {
+        use crate::TimezoneSpec;
+
+        let offset = FixedOffset::west_opt(3 * 3600 + 30 * 60 + 15).unwrap();
+        let expected = time::UtcOffset::from_hms(-3, -30, -15).unwrap();
+        assert_eq!(crate::offset::FixedOffset::from(offset).0, expected);
+        assert_eq!(TimezoneSpec::from(offset), TimezoneSpec::Fixed(expected));
+        let max = FixedOffset::east_opt(86_399).unwrap();
+        assert_eq!(crate::offset::FixedOffset::from(max).0.whole_hours(), 23);
+    }
+}
@@ -0,0 +1,151 @@
+//! [`clap`] value parsers for the timezone and format specs, so command-line
+//! tools get `--log-timezone +08:00 --log-time-format rfc3339` flags with
+//! validation.
+//!
+//! [`TimezoneSpec`] and [`TimeFormatSpec`] implement [`ValueParserFactory`],
+//! so `clap::value_parser!` and derived arguments pick the parsers below
+//! without further configuration. Invalid values are reported as
+//! [`ErrorKind::ValueValidation`](::clap::error::ErrorKind::ValueValidation)
+//! errors carrying the spec's own message.
+//!
+//! # Examples
+//!
+//! ```
+//! use clap::{value_parser, Arg, Command};
+//! use clia_local_time::{TimeFormatSpec, TimezoneSpec};
+//!
+//! let matches = Command::new("app")
+//!     .arg(
+//!         Arg::new("log-timezone")
+//!             .long("log-timezone")
+//!             .value_parser(value_parser!(TimezoneSpec)),
+//!     )
+//!     .arg(
+//!         Arg::new("log-time-format")
+//!             .long("log-time-format")
+//!             .value_parser(value_parser!(TimeFormatSpec)),
+//!     )
+//!     .get_matches_from(["app", "--log-timezone", "+08:00", "--log-time-format", "rfc3339"]);
+//! let spec = matches.get_one::<TimezoneSpec>("log-timezone").unwrap();
+//! let format = matches.get_one::<TimeFormatSpec>("log-time-format").unwrap();
+//! let timer = format
+//!     .clone()
+//!     .into_timer(spec.resolve().expect("spec should resolve"))
+//!     .expect("format should be valid");
+//! # drop(timer);
+//! ```
+
+use std::ffi::OsStr;
+
+use ::clap::builder::{StringValueParser, TypedValueParser, ValueParserFactory};
+use ::clap::{Arg, Command, Error};
+
+use crate::{TimeFormatSpec, TimezoneSpec};
+
+/// Parses a [`TimezoneSpec`], e.g. `+08:00`, `Asia/Shanghai` or `system`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TimezoneSpecParser;
+
+/// Parses a [`TimeFormatSpec`], e.g. `rfc3339` or `[hour]:[minute]`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TimeFormatSpecParser;
+
+// === impl TimezoneSpecParser ===
+
+impl TypedValueParser for TimezoneSpecParser {
+    type Value = TimezoneSpec;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, Error> {
+        StringValueParser::new()
+            .try_map(|s| s.parse::<TimezoneSpec>())
+            .parse_ref(cmd, arg, value)
+    }
+}
+
+impl ValueParserFactory for TimezoneSpec {
+    type Parser = TimezoneSpecParser;
+
+    fn value_parser() -> Self::Parser {
+        TimezoneSpecParser
+    }
+}
+
+// === impl TimeFormatSpecParser ===
+
+impl TypedValueParser for TimeFormatSpecParser {
+    type Value = TimeFormatSpec;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, Error> {
+        StringValueParser::new()
+            .try_map(|s| s.parse::<TimeFormatSpec>())
+            .parse_ref(cmd, arg, value)
+    }
+}
+
+impl ValueParserFactory for TimeFormatSpec {
+    type Parser = TimeFormatSpecParser;
+
+    fn value_parser() -> Self::Parser {
+        TimeFormatSpecParser
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{TimeFormatSpec, TimezoneSpec};
+    use ::clap::{error::ErrorKind, value_parser, Arg, Command};
+    use time::macros::offset;
+
+    fn command() -> Command {
+        Command::new("app")
+            .arg(
+                Arg::new("tz")
+                    .long("log-timezone")
+                    .value_parser(value_parser!(TimezoneSpec)),
+            )
+            .arg(
+                Arg::new("format")
+                    .long("log-time-format")
+                    .value_parser(value_parser!(TimeFormatSpec)),
+            )
+    }
+
+    #[test]
+    fn test_value_parsers() {
+        let matches = command()
+            .try_get_matches_from([
+                "app",
+                "--log-timezone",
+                "+08:00",
+                "--log-time-format",
+                "unix",
+            ])
+            .unwrap();
+        assert_eq!(
+            matches.get_one::<TimezoneSpec>("tz"),
+            Some(&TimezoneSpec::Fixed(offset!(+8)))
+        );
+        assert_eq!(
+            matches.get_one::<TimeFormatSpec>("format"),
+            Some(&TimeFormatSpec::UnixSeconds)
+        );
+
+        for args in [
+            ["app", "--log-timezone", "+8:00"],
+            ["app", "--log-time-format", "[hour"],
+        ] {
+            let error = command().try_get_matches_from(args).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::ValueValidation);
+        }
+    }
+}
@@ -0,0 +1,446 @@
+//! The clocks a [`LocalTime`] can read the current time from.
+//!
+//! Every [`LocalTime`] consults a [`ClockSource`], the [`SystemClock`] unless
+//! replaced with [`LocalTime::with_clock`], e.g. to get deterministic
+//! timestamps in captured test output.
+
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use time::OffsetDateTime;
+
+use crate::LocalTime;
+
+/// A source of the current time.
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::{clock::ClockSource, LocalTime};
+/// use time::{macros::datetime, OffsetDateTime};
+///
+/// struct Frozen;
+///
+/// impl ClockSource for Frozen {
+///     fn now_utc(&self) -> OffsetDateTime {
+///         datetime!(2024-05-01 10:00 UTC)
+///     }
+/// }
+///
+/// let timer = LocalTime::w3c((8, 0, 0)).with_clock(Frozen);
+/// assert_eq!(timer.now(), datetime!(2024-05-01 18:00 +8));
+/// ```
+pub trait ClockSource {
+    /// Returns the current time in UTC.
+    fn now_utc(&self) -> OffsetDateTime;
+
+    /// The kind of clock, rendered by [`LocalTime::with_source_tag`].
+    fn kind(&self) -> ClockKind {
+        ClockKind::Wall
+    }
+}
+
+/// The system wall clock, optionally read through its cheaper coarse variant.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SystemClock {
+    coarse: bool,
+}
+
+/// A clock stopped at one instant, so tests can assert exact timestamps in
+/// captured log output.
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::{clock::Fixed, LocalTime};
+/// use time::macros::datetime;
+///
+/// let timer = LocalTime::rfc_3339().with_clock(Fixed(datetime!(2024-05-01 10:00 UTC)));
+/// let mut s = String::new();
+/// timer.format_datetime(timer.now(), &mut s).unwrap();
+/// assert_eq!(s, "2024-05-01T10:00:00Z");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Fixed(pub OffsetDateTime);
+
+/// A clock that starts at an instant and advances by a fixed step every time
+/// it is read, i.e. once per formatted event, so tests can exercise rollover
+/// such as day changes and subsecond carries deterministically.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use clia_local_time::{clock::{ClockSource, Stepping}, LocalTime};
+/// use time::macros::datetime;
+///
+/// let clock = Stepping::new(datetime!(2024-05-01 23:59:59.5 UTC), Duration::from_millis(500));
+/// assert_eq!(clock.now_utc(), datetime!(2024-05-01 23:59:59.5 UTC));
+/// assert_eq!(clock.now_utc(), datetime!(2024-05-02 00:00 UTC));
+///
+/// let timer = LocalTime::rfc_3339().with_clock(clock);
+/// # drop(timer);
+/// ```
+#[derive(Debug)]
+pub struct Stepping {
+    next: Mutex<OffsetDateTime>,
+    step: Duration,
+}
+
+/// A clock anchored to the wall clock when it is created, then advanced by
+/// the monotonic clock, so timestamps never go backwards or jump with
+/// adjustments of the system clock.
+///
+/// Drift from the wall clock accumulates over time; create a new clock to
+/// re-anchor.
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::{clock::MonotonicClock, LocalTime};
+///
+/// let collector = tracing_subscriber::fmt()
+///     .with_timer(LocalTime::rfc_3339().with_clock(MonotonicClock::new()));
+/// # drop(collector);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct MonotonicClock {
+    anchor: OffsetDateTime,
+    start: Instant,
+}
+
+/// The kind of clock a timestamp was read from.
+///
+/// Rendered as a one-character marker by [`LocalTime::with_source_tag`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ClockKind {
+    /// The system wall clock, marked `W`.
+    Wall,
+    /// A [`MonotonicClock`], marked `M`.
+    Monotonic,
+    /// A PTP hardware clock, read by a `PhcClock`, marked `P`.
+    Phc,
+    /// The coarse-grained wall clock used by [`LocalTime::coarse`], marked `C`.
+    Coarse,
+    /// A [`ReplayClock`], marked `R`.
+    Replay,
+    /// The system clock corrected by an NTP server, marked `N`.
+    Disciplined,
+    /// The CPU's time-stamp counter, read by a `TscClock`, marked `T`.
+    Tsc,
+    /// The last instant read, reused by the [degradation ladder](crate::ladder)
+    /// when no clock can be read, marked `L`.
+    Cached,
+    /// A sequence number standing in for the time on the degradation ladder,
+    /// marked `S`.
+    Sequence,
+}
+
+// === impl ClockKind ===
+
+impl ClockKind {
+    /// The one-character marker for this clock.
+    pub fn marker(self) -> char {
+        match self {
+            ClockKind::Wall => 'W',
+            ClockKind::Monotonic => 'M',
+            ClockKind::Phc => 'P',
+            ClockKind::Coarse => 'C',
+            ClockKind::Replay => 'R',
+            ClockKind::Disciplined => 'N',
+            ClockKind::Tsc => 'T',
+            ClockKind::Cached => 'L',
+            ClockKind::Sequence => 'S',
+        }
+    }
+}
+
+// === impl SystemClock ===
+
+impl SystemClock {
+    /// Returns the precise system clock.
+    pub const fn new() -> Self {
+        Self { coarse: false }
+    }
+
+    /// Returns the coarse-grained system clock, see [`LocalTime::coarse`].
+    pub const fn coarse() -> Self {
+        Self { coarse: true }
+    }
+
+    /// Whether the coarse-grained clock was requested.
+    pub fn is_coarse(&self) -> bool {
+        self.coarse
+    }
+}
+
+impl ClockSource for SystemClock {
+    fn now_utc(&self) -> OffsetDateTime {
+        if self.coarse {
+            coarse_now_utc()
+        } else {
+            OffsetDateTime::now_utc()
+        }
+    }
+
+    fn kind(&self) -> ClockKind {
+        if self.coarse && COARSE_AVAILABLE {
+            ClockKind::Coarse
+        } else {
+            ClockKind::Wall
+        }
+    }
+}
+
+// === impl Fixed ===
+
+impl ClockSource for Fixed {
+    fn now_utc(&self) -> OffsetDateTime {
+        self.0
+    }
+}
+
+// === impl Stepping ===
+
+impl Stepping {
+    /// Returns a clock that reads `start` first, then advances by `step`
+    /// after every read.
+    pub fn new(start: OffsetDateTime, step: Duration) -> Self {
+        Self {
+            next: Mutex::new(start),
+            step,
+        }
+    }
+}
+
+impl ClockSource for Stepping {
+    fn now_utc(&self) -> OffsetDateTime {
+        let mut next = self.next.lock().unwrap_or_else(PoisonError::into_inner);
+        let now = *next;
+        *next += self.step;
+        now
+    }
+}
+
+// === impl MonotonicClock ===
+
+impl MonotonicClock {
+    /// Returns a clock anchored to the current wall-clock time.
+    pub fn new() -> Self {
+        Self {
+            anchor: OffsetDateTime::now_utc(),
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for MonotonicClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClockSource for MonotonicClock {
+    fn now_utc(&self) -> OffsetDateTime {
+        self.anchor + self.start.elapsed()
+    }
+
+    fn kind(&self) -> ClockKind {
+        ClockKind::Monotonic
+    }
+}
+
+/// A clock driven by an external cursor, for re-emitting historical events
+/// with the timestamps they originally had, e.g. in a log replay viewer.
+///
+/// Clones share the same cursor, so the viewer can keep one handle to
+/// [`seek`](ReplayClock::seek) and [`advance`](ReplayClock::advance) while a
+/// timer reads it.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use clia_local_time::{clock::ReplayClock, LocalTime};
+/// use time::macros::datetime;
+///
+/// let clock = ReplayClock::new(datetime!(2024-05-01 10:00:00 UTC));
+/// let collector = tracing_subscriber::fmt()
+///     .with_timer(LocalTime::rfc_3339().with_replay_clock(clock.clone()));
+///
+/// clock.advance(Duration::from_millis(250));
+/// assert_eq!(clock.now(), datetime!(2024-05-01 10:00:00.25 UTC));
+/// # drop(collector);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ReplayClock {
+    cursor: Arc<Mutex<OffsetDateTime>>,
+}
+
+// === impl ReplayClock ===
+
+impl ReplayClock {
+    /// Returns a clock positioned at `start`.
+    pub fn new(start: OffsetDateTime) -> Self {
+        Self {
+            cursor: Arc::new(Mutex::new(start)),
+        }
+    }
+
+    /// The instant the cursor is at.
+    pub fn now(&self) -> OffsetDateTime {
+        *self.cursor.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Moves the cursor to `instant`, forwards or backwards.
+    pub fn seek(&self, instant: OffsetDateTime) {
+        *self.cursor.lock().unwrap_or_else(PoisonError::into_inner) = instant;
+    }
+
+    /// Moves the cursor forwards by `by`.
+    pub fn advance(&self, by: Duration) {
+        *self.cursor.lock().unwrap_or_else(PoisonError::into_inner) += by;
+    }
+}
+
+impl ClockSource for ReplayClock {
+    fn now_utc(&self) -> OffsetDateTime {
+        self.now()
+    }
+
+    fn kind(&self) -> ClockKind {
+        ClockKind::Replay
+    }
+}
+
+/// Whether [`coarse_now_utc`] reads an actual coarse clock.
+pub(crate) const COARSE_AVAILABLE: bool = cfg!(all(feature = "coarse", target_os = "linux"));
+
+/// Reads `CLOCK_REALTIME_COARSE`, falling back to the normal clock if the
+/// read fails.
+pub(crate) fn coarse_now_utc() -> OffsetDateTime {
+    try_coarse_now_utc().unwrap_or_else(OffsetDateTime::now_utc)
+}
+
+/// Reads `CLOCK_REALTIME_COARSE`, if available.
+#[cfg(all(feature = "coarse", target_os = "linux"))]
+pub(crate) fn try_coarse_now_utc() -> Option<OffsetDateTime> {
+    clock_gettime(libc::CLOCK_REALTIME_COARSE)
+}
+
+#[cfg(not(all(feature = "coarse", target_os = "linux")))]
+pub(crate) fn try_coarse_now_utc() -> Option<OffsetDateTime> {
+    None
+}
+
+/// Reads the system clock, failing instead of panicking if the syscall is
+/// blocked, or the clock is before the Unix epoch or out of range.
+#[cfg(unix)]
+pub(crate) fn try_now_utc() -> Option<OffsetDateTime> {
+    clock_gettime(libc::CLOCK_REALTIME).filter(|now| *now >= OffsetDateTime::UNIX_EPOCH)
+}
+
+/// Reads the system clock, failing instead of panicking if it is before the
+/// Unix epoch or out of range. Reading it can't fail on Windows.
+#[cfg(not(any(unix, all(target_family = "wasm", target_os = "unknown"))))]
+pub(crate) fn try_now_utc() -> Option<OffsetDateTime> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+    OffsetDateTime::from_unix_timestamp_nanos(since_epoch.as_nanos() as i128).ok()
+}
+
+/// Reads the clock `id`, checking the result rather than panicking as
+/// `SystemTime::now` does.
+#[cfg(unix)]
+pub(crate) fn clock_gettime(id: libc::clockid_t) -> Option<OffsetDateTime> {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: `ts` is a valid, writable `timespec`.
+    if unsafe { libc::clock_gettime(id, &mut ts) } != 0 {
+        return None;
+    }
+    let nanos = i128::from(ts.tv_sec) * 1_000_000_000 + i128::from(ts.tv_nsec);
+    OffsetDateTime::from_unix_timestamp_nanos(nanos).ok()
+}
+
+/// In browsers `SystemTime::now` panics, while `time` reads `Date.now()` with
+/// the `wasm` feature.
+#[cfg(all(target_family = "wasm", target_os = "unknown"))]
+pub(crate) fn try_now_utc() -> Option<OffsetDateTime> {
+    Some(OffsetDateTime::now_utc())
+}
+
+// === impl LocalTime ===
+
+impl<F, P, C> LocalTime<F, P, C> {
+    /// Reads the time from `clock` instead of the system clock.
+    ///
+    /// This is [`LocalTime::with_clock`] with a [`ReplayClock`], and keeps
+    /// the timer's skew, time scale and other settings.
+    pub fn with_replay_clock(self, clock: ReplayClock) -> LocalTime<F, P, ReplayClock> {
+        self.with_clock(clock)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClockSource, MonotonicClock, ReplayClock, Stepping};
+    use crate::{testing::render_now, LocalTime};
+    use std::time::Duration;
+    use time::macros::datetime;
+
+    #[test]
+    fn test_coarse_now() {
+        let coarse = super::coarse_now_utc();
+        let precise = time::OffsetDateTime::now_utc();
+        assert!((precise - coarse).abs() < time::Duration::milliseconds(100));
+    }
+
+    #[test]
+    fn test_stepping() {
+        let clock = Stepping::new(
+            datetime!(2024-12-31 23:59:59.999 UTC),
+            Duration::from_millis(1),
+        );
+        let timer = LocalTime::w3c((0, 0, 0)).with_clock(clock);
+        let lines = [render_now(&timer), render_now(&timer)];
+        assert_eq!(lines, ["2024-12-31 23:59:59", "2025-01-01 00:00:00"]);
+    }
+
+    #[test]
+    fn test_monotonic_clock() {
+        let clock = MonotonicClock::new();
+        let first = clock.now_utc();
+        assert!((time::OffsetDateTime::now_utc() - first).abs() < time::Duration::SECOND);
+        assert!(clock.now_utc() >= first);
+
+        let timer = LocalTime::w3c((0, 0, 0))
+            .with_source_tag()
+            .with_clock(clock);
+        assert!(render_now(&timer).ends_with(" M"));
+    }
+
+    #[test]
+    fn test_replay() {
+        let clock = ReplayClock::new(datetime!(2024-05-01 10:00:00 UTC));
+        let timer = LocalTime::w3c((8, 0, 0)).with_replay_clock(clock.clone());
+        assert_eq!(render_now(&timer), "2024-05-01 18:00:00");
+        clock.seek(datetime!(2023-01-01 00:00:00 UTC));
+        assert_eq!(render_now(&timer), "2023-01-01 08:00:00");
+
+        let timer = LocalTime::w3c((8, 0, 0))
+            .with_source_tag()
+            .with_clock(clock.clone());
+        assert_eq!(render_now(&timer), "2023-01-01 08:00:00 R");
+
+        // The replay clock keeps the timer's other settings.
+        let timer = LocalTime::w3c((8, 0, 0))
+            .with_skew(time::Duration::hours(1))
+            .with_replay_clock(clock);
+        assert_eq!(render_now(&timer), "2023-01-01 09:00:00");
+    }
+}
@@ -0,0 +1,540 @@
+//! Snapshots of a timer's configuration, for auditing timestamp policies,
+//! and, with the `serde` feature, [`TimerConfig`] for building timers from
+//! configuration files. With the `config` feature,
+//! [`LocalTime::from_config_file`] reads one from a TOML file.
+
+use std::fmt;
+#[cfg(feature = "config")]
+use std::{io, path::Path};
+
+#[cfg(feature = "serde")]
+use time::{
+    error::InvalidFormatDescription,
+    format_description::{
+        modifier::SubsecondDigits, well_known::Rfc3339, Component, OwnedFormatItem,
+    },
+};
+use time::{macros::format_description, OffsetDateTime};
+
+use crate::clock::{ClockKind, ClockSource};
+use crate::offset::OffsetProvider;
+use crate::LocalTime;
+#[cfg(feature = "serde")]
+use crate::{
+    FormatSpecError, Placeholder, SpecOffset, TimeFormatSpec, TimezoneError, TimezoneSpec,
+};
+
+/// A snapshot of the settings a [`LocalTime`] formats with.
+///
+/// Returned by [`LocalTime::config`]. With the `serde` feature enabled it can
+/// be serialized, e.g. to report each service's timestamp policy, and snapshots
+/// can be compared with [`Config::diff`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Config {
+    /// The UTC offset in effect when the snapshot was taken, e.g.
+    /// `+08:00:00`.
+    pub offset: String,
+    /// The `Debug` rendering of the format.
+    pub format: String,
+    /// Text written before the timestamp.
+    pub prefix: String,
+    /// Text written after the timestamp.
+    pub suffix: String,
+    /// The column width the timestamp is padded or truncated to.
+    pub width: Option<usize>,
+    /// The adjustment added to every timestamp, e.g. `-1m30s`.
+    pub skew: String,
+    /// The time scale timestamps are rendered in, e.g. `Tai`.
+    pub time_scale: String,
+    /// Whether the coarse clock is used.
+    pub coarse: bool,
+    /// Whether timestamps are tagged with their clock's marker.
+    pub source_tag: bool,
+    /// The scope timestamps are kept monotonic in, if any, e.g. `Global`.
+    pub monotonic: Option<String>,
+    /// Whether the offset is appended because the format lacks one.
+    pub auto_offset: bool,
+    /// Whether formatting failures are passed to a hook.
+    pub on_error: bool,
+    /// Whether rendered and failed timestamps are counted.
+    pub stats: bool,
+    /// The interval timestamps are rounded down to, if any, e.g. `10s`.
+    pub round_to: Option<String>,
+    /// Whether repeated timestamps are numbered.
+    pub repeat_counter: bool,
+    /// The ANSI style timestamps are wrapped in, if any, as SGR parameters,
+    /// e.g. `2`.
+    pub ansi_style: Option<String>,
+    /// Whether a zero offset is rendered as `+00:00` rather than `Z`.
+    pub numeric_utc_offset: bool,
+    /// Whether subseconds follow a comma rather than a dot.
+    pub decimal_comma: bool,
+    /// Whether clock failures fall back along the degradation ladder.
+    pub degradation: bool,
+}
+
+/// A setting that differs between two [`Config`]s.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Difference {
+    /// The name of the setting, e.g. `offset`.
+    pub field: &'static str,
+    /// The value in the snapshot [`Config::diff`] was called on.
+    pub left: String,
+    /// The value in the other snapshot.
+    pub right: String,
+}
+
+/// Timer settings read from a configuration file, converted into a
+/// [`LocalTime`] with [`TimerConfig::build`].
+///
+/// Every field is optional. For example, in TOML:
+///
+/// ```toml
+/// [timer]
+/// offset = "+08:00"
+/// precision = 3
+/// ```
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+#[non_exhaustive]
+pub struct TimerConfig {
+    /// The timezone, in any form [`TimezoneSpec`] parses: e.g. `+08:00`,
+    /// `Asia/Shanghai` or `system`. Defaults to UTC.
+    pub offset: String,
+    /// A format description in the syntax of [`time::format_description::parse`].
+    /// Defaults to RFC 3339.
+    pub format: Option<String>,
+    /// The number of subsecond digits, `0` to `9`. For a custom `format` it
+    /// replaces the digits of every `[subsecond]` component and must be at
+    /// least `1`.
+    pub precision: Option<u8>,
+    /// What replaces a timestamp the format can't render: `epoch` for the
+    /// Unix seconds, or any other text, e.g. `<time-error>`. Unset drops the
+    /// line.
+    pub fallback: Option<String>,
+    /// A format, in any form [`TimeFormatSpec`] parses, tried before the
+    /// `fallback` when the format can't render a timestamp.
+    pub fallback_format: Option<String>,
+}
+
+/// The error returned by [`TimerConfig::build`].
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TimerConfigError {
+    /// The timezone can't be parsed or resolved.
+    Timezone(TimezoneError),
+    /// The format description is invalid.
+    Format(InvalidFormatDescription),
+    /// The precision is out of range.
+    Precision(u8),
+    /// The fallback format is invalid.
+    FallbackFormat(FormatSpecError),
+}
+
+/// The error returned by [`LocalTime::from_config_file`].
+#[cfg(feature = "config")]
+#[cfg_attr(docsrs, doc(cfg(feature = "config")))]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ConfigFileError {
+    /// The file can't be read.
+    Io(io::Error),
+    /// The file isn't valid TOML, or the `[timer]` table has unknown or
+    /// mistyped settings.
+    Toml(toml::de::Error),
+    /// The settings can't be turned into a timer.
+    Build(TimerConfigError),
+}
+
+// === impl Config ===
+
+impl Config {
+    /// Returns every setting whose value differs between `self` and `other`,
+    /// or an empty list if both timers format alike.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    ///
+    /// let a = LocalTime::w3c((8, 0, 0)).config();
+    /// let b = LocalTime::w3c((0, 0, 0)).config();
+    /// let diff = a.diff(&b);
+    /// assert_eq!(diff.len(), 1);
+    /// assert_eq!(diff[0].to_string(), "offset: +08:00:00 != +00:00:00");
+    /// ```
+    pub fn diff(&self, other: &Config) -> Vec<Difference> {
+        self.entries()
+            .into_iter()
+            .zip(other.entries())
+            .filter(|((_, left), (_, right))| left != right)
+            .map(|((field, left), (_, right))| Difference { field, left, right })
+            .collect()
+    }
+
+    /// Every setting's name and its rendering for [`Difference`].
+    fn entries(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("offset", self.offset.clone()),
+            ("format", self.format.clone()),
+            ("prefix", self.prefix.clone()),
+            ("suffix", self.suffix.clone()),
+            ("width", option_to_string(self.width)),
+            ("skew", self.skew.clone()),
+            ("time_scale", self.time_scale.clone()),
+            ("coarse", self.coarse.to_string()),
+            ("source_tag", self.source_tag.to_string()),
+            ("monotonic", option_to_string(self.monotonic.as_ref())),
+            ("auto_offset", self.auto_offset.to_string()),
+            ("on_error", self.on_error.to_string()),
+            ("stats", self.stats.to_string()),
+            ("round_to", option_to_string(self.round_to.as_ref())),
+            ("repeat_counter", self.repeat_counter.to_string()),
+            ("ansi_style", option_to_string(self.ansi_style.as_ref())),
+            ("numeric_utc_offset", self.numeric_utc_offset.to_string()),
+            ("decimal_comma", self.decimal_comma.to_string()),
+            ("degradation", self.degradation.to_string()),
+        ]
+    }
+}
+
+fn option_to_string<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "none".to_string(), |value| value.to_string())
+}
+
+// === impl TimerConfig ===
+
+#[cfg(feature = "serde")]
+impl Default for TimerConfig {
+    fn default() -> Self {
+        Self {
+            offset: "+00:00".to_string(),
+            format: None,
+            precision: None,
+            fallback: None,
+            fallback_format: None,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TimerConfig {
+    /// Returns a timer with these settings.
+    ///
+    /// `offset` is read as a [`TimezoneSpec`]; `system` and named zones
+    /// follow daylight saving transitions, see [`SpecOffset`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::config::TimerConfig;
+    ///
+    /// let mut config = TimerConfig::default();
+    /// config.offset = "+08:00".to_string();
+    /// config.precision = Some(3);
+    /// let timer = config.build().expect("config should be valid");
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(timer);
+    /// # drop(collector);
+    /// ```
+    pub fn build(&self) -> Result<LocalTime<OwnedFormatItem, SpecOffset>, TimerConfigError> {
+        let spec: TimezoneSpec = self.offset.parse().map_err(TimerConfigError::Timezone)?;
+        let digits = match self.precision {
+            None | Some(0) => None,
+            Some(n) => Some(subsecond_digits(n).ok_or(TimerConfigError::Precision(n))?),
+        };
+        let format = match &self.format {
+            Some(format) => {
+                let mut format = time::format_description::parse_owned::<1>(format)
+                    .map_err(TimerConfigError::Format)?;
+                match digits {
+                    Some(digits) => set_subsecond_digits(&mut format, digits),
+                    None if self.precision == Some(0) => {
+                        return Err(TimerConfigError::Precision(0))
+                    }
+                    None => {}
+                }
+                format
+            }
+            None => {
+                let subsecond = if digits.is_some() { ".[subsecond]" } else { "" };
+                let mut format = time::format_description::parse_owned::<1>(&format!(
+                    "[year]-[month]-[day]T[hour]:[minute]:[second]{}\
+                     [offset_hour sign:mandatory]:[offset_minute]",
+                    subsecond
+                ))
+                .map_err(TimerConfigError::Format)?;
+                if let Some(digits) = digits {
+                    set_subsecond_digits(&mut format, digits);
+                }
+                format
+            }
+        };
+        let mut timer = LocalTime::with_spec(format, &spec).map_err(TimerConfigError::Timezone)?;
+        if let Some(fallback) = &self.fallback {
+            timer = timer.with_placeholder(if fallback == "epoch" {
+                Placeholder::EpochSeconds
+            } else {
+                Placeholder::Text(fallback.clone())
+            });
+        }
+        if let Some(fallback_format) = &self.fallback_format {
+            let spec: TimeFormatSpec = fallback_format
+                .parse()
+                .map_err(TimerConfigError::FallbackFormat)?;
+            timer = match spec {
+                TimeFormatSpec::Rfc3339 => timer.with_fallback_format(Rfc3339),
+                spec => timer
+                    .with_fallback_format(spec.format().map_err(TimerConfigError::FallbackFormat)?),
+            };
+        }
+        Ok(timer)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn subsecond_digits(n: u8) -> Option<SubsecondDigits> {
+    Some(match n {
+        1 => SubsecondDigits::One,
+        2 => SubsecondDigits::Two,
+        3 => SubsecondDigits::Three,
+        4 => SubsecondDigits::Four,
+        5 => SubsecondDigits::Five,
+        6 => SubsecondDigits::Six,
+        7 => SubsecondDigits::Seven,
+        8 => SubsecondDigits::Eight,
+        9 => SubsecondDigits::Nine,
+        _ => return None,
+    })
+}
+
+#[cfg(feature = "serde")]
+fn set_subsecond_digits(item: &mut OwnedFormatItem, digits: SubsecondDigits) {
+    match item {
+        OwnedFormatItem::Component(Component::Subsecond(modifier)) => {
+            *modifier = modifier.with_digits(digits);
+        }
+        OwnedFormatItem::Compound(items) | OwnedFormatItem::First(items) => {
+            for item in items.iter_mut() {
+                set_subsecond_digits(item, digits);
+            }
+        }
+        OwnedFormatItem::Optional(item) => set_subsecond_digits(item, digits),
+        _ => {}
+    }
+}
+
+// === impl TimerConfigError ===
+
+#[cfg(feature = "serde")]
+impl fmt::Display for TimerConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimerConfigError::Timezone(e) => write!(f, "invalid timezone: {}", e),
+            TimerConfigError::Format(e) => write!(f, "invalid format description: {}", e),
+            TimerConfigError::Precision(n) => write!(f, "invalid subsecond precision {}", n),
+            TimerConfigError::FallbackFormat(e) => write!(f, "invalid fallback format: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::error::Error for TimerConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TimerConfigError::Timezone(e) => Some(e),
+            TimerConfigError::Format(e) => Some(e),
+            TimerConfigError::FallbackFormat(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+// === impl ConfigFileError ===
+
+#[cfg(feature = "config")]
+impl fmt::Display for ConfigFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigFileError::Io(e) => write!(f, "can't read the timer config: {}", e),
+            ConfigFileError::Toml(e) => write!(f, "invalid timer config: {}", e),
+            ConfigFileError::Build(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "config")]
+impl std::error::Error for ConfigFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigFileError::Io(e) => Some(e),
+            ConfigFileError::Toml(e) => Some(e),
+            ConfigFileError::Build(e) => Some(e),
+        }
+    }
+}
+
+// === impl Difference ===
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} != {}", self.field, self.left, self.right)
+    }
+}
+
+// === impl LocalTime ===
+
+#[cfg(feature = "config")]
+#[cfg_attr(docsrs, doc(cfg(feature = "config")))]
+impl LocalTime<OwnedFormatItem, SpecOffset> {
+    /// Returns a timer with the settings in the `[timer]` table of the TOML
+    /// file at `path`, so timestamps can be changed without a redeploy.
+    ///
+    /// The table holds the fields of [`TimerConfig`]; other tables in the
+    /// file are ignored, and a missing `[timer]` table means the defaults.
+    ///
+    /// ```toml
+    /// [timer]
+    /// offset = "+08:00"
+    /// precision = 3
+    /// fallback = "<time-error>"
+    /// ```
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use clia_local_time::LocalTime;
+    ///
+    /// let timer = LocalTime::from_config_file("log.toml").expect("config should be valid");
+    /// tracing_subscriber::fmt().with_timer(timer).init();
+    /// ```
+    pub fn from_config_file(path: impl AsRef<Path>) -> Result<Self, ConfigFileError> {
+        let text = std::fs::read_to_string(path).map_err(ConfigFileError::Io)?;
+        timer_config(&text)?.build().map_err(ConfigFileError::Build)
+    }
+}
+
+/// Reads the `[timer]` table of a TOML document.
+#[cfg(feature = "config")]
+fn timer_config(text: &str) -> Result<TimerConfig, ConfigFileError> {
+    #[derive(serde::Deserialize)]
+    struct File {
+        #[serde(default)]
+        timer: TimerConfig,
+    }
+
+    let file: File = toml::from_str(text).map_err(ConfigFileError::Toml)?;
+    Ok(file.timer)
+}
+
+impl<F: fmt::Debug, P: OffsetProvider, C: ClockSource> LocalTime<F, P, C> {
+    /// Returns a snapshot of this timer's settings.
+    pub fn config(&self) -> Config {
+        let offset = self
+            .offset
+            .offset_at(OffsetDateTime::now_utc())
+            .format(format_description!(
+                "[offset_hour sign:mandatory]:[offset_minute]:[offset_second]"
+            ))
+            .unwrap_or_default();
+        Config {
+            offset,
+            format: format!("{:?}", self.format),
+            prefix: self.prefix.clone(),
+            suffix: self.suffix.clone(),
+            width: self.width,
+            skew: self.skew.to_string(),
+            time_scale: format!("{:?}", self.scale),
+            coarse: self.clock.kind() == ClockKind::Coarse,
+            source_tag: self.source_tag,
+            monotonic: self
+                .monotonic
+                .as_ref()
+                .map(|monotonic| format!("{:?}", monotonic.scope)),
+            auto_offset: self.auto_offset,
+            on_error: self.on_error.is_some(),
+            stats: self.stats.is_some(),
+            round_to: self.round_to.map(|interval| interval.to_string()),
+            repeat_counter: self.repeats.is_some(),
+            ansi_style: self
+                .ansi_style
+                .as_ref()
+                .map(|style| style.sgr().to_string()),
+            numeric_utc_offset: self.numeric_utc,
+            decimal_comma: self.decimal_comma,
+            degradation: self.ladder.is_some(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::{TimerConfig, TimerConfigError};
+    use crate::offset::localize;
+    use time::macros::datetime;
+
+    fn render(config: TimerConfig) -> String {
+        let timer = config.build().unwrap();
+        localize(datetime!(2024-05-01 02:00:00.123456 UTC), &timer.offset)
+            .format(&timer.format)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_build() {
+        assert_eq!(render(TimerConfig::default()), "2024-05-01T02:00:00+00:00");
+        let config = TimerConfig {
+            offset: "+08:00".to_string(),
+            precision: Some(3),
+            ..TimerConfig::default()
+        };
+        assert_eq!(render(config), "2024-05-01T10:00:00.123+08:00");
+        let config = TimerConfig {
+            format: Some("[hour]:[minute]:[second].[subsecond]".to_string()),
+            precision: Some(2),
+            ..TimerConfig::default()
+        };
+        assert_eq!(render(config), "02:00:00.12");
+
+        let config = TimerConfig {
+            precision: Some(10),
+            ..TimerConfig::default()
+        };
+        assert_eq!(config.build().unwrap_err(), TimerConfigError::Precision(10));
+    }
+
+    #[test]
+    fn test_fallback() {
+        let config = TimerConfig {
+            fallback: Some("epoch".to_string()),
+            fallback_format: Some("rfc2822".to_string()),
+            ..TimerConfig::default()
+        };
+        assert!(config.build().unwrap().config().on_error);
+        assert!(!TimerConfig::default().build().unwrap().config().on_error);
+        let config = TimerConfig {
+            fallback_format: Some("hh:mm".to_string()),
+            ..TimerConfig::default()
+        };
+        assert!(matches!(
+            config.build(),
+            Err(TimerConfigError::FallbackFormat(_))
+        ));
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_config_file() {
+        let config =
+            super::timer_config("[log]\nlevel = \"info\"\n[timer]\nprecision = 3\n").unwrap();
+        assert_eq!(config.precision, Some(3));
+        assert_eq!(super::timer_config("").unwrap(), TimerConfig::default());
+        assert!(super::timer_config("[timer]\nprecison = 3\n").is_err());
+    }
+}
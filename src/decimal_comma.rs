@@ -0,0 +1,82 @@
+//! Writing subseconds after a decimal comma, as ISO 8601 permits.
+
+use crate::LocalTime;
+
+// === impl LocalTime ===
+
+impl<F, P, C> LocalTime<F, P, C> {
+    /// Separates the subseconds with a comma instead of a dot, e.g.
+    /// `2024-05-01T10:00:00,123+08:00`, as some SIEM tools expect.
+    ///
+    /// The separator is the one following `[hour]:[minute]:[second]` in the
+    /// output, as in the built-in formats; other dots, e.g. in
+    /// `[day].[month].[year]`, are left alone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::macros::datetime;
+    ///
+    /// let timer = LocalTime::rfc_3339_millis((8, 0, 0)).with_decimal_comma();
+    /// let mut s = String::new();
+    /// timer.format_datetime(datetime!(2024-05-01 02:00:00.123 UTC), &mut s).unwrap();
+    /// assert_eq!(s, "2024-05-01T10:00:00,123+08:00");
+    /// ```
+    pub fn with_decimal_comma(self) -> Self {
+        Self {
+            decimal_comma: true,
+            ..self
+        }
+    }
+}
+
+/// The index of the decimal separator `sep` following the seconds of a
+/// `HH:MM:SS` time in `timestamp`, if any.
+pub(crate) fn find_separator(timestamp: &str, sep: u8) -> Option<usize> {
+    let b = timestamp.as_bytes();
+    (3..b.len().saturating_sub(1)).find(|&i| {
+        b[i] == sep
+            && b[i - 3] == b':'
+            && b[i - 2].is_ascii_digit()
+            && b[i - 1].is_ascii_digit()
+            && b[i + 1].is_ascii_digit()
+    })
+}
+
+/// Replaces the decimal separator `from` of `timestamp` with `to`.
+pub(crate) fn replace_separator(timestamp: &mut String, from: u8, to: &str) {
+    if let Some(i) = find_separator(timestamp, from) {
+        timestamp.replace_range(i..i + 1, to);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::replace_separator;
+    use crate::{testing::render, LocalTime};
+    use time::macros::{datetime, format_description};
+
+    #[test]
+    fn test_decimal_comma() {
+        let mut s = "01.05.2024 10:00:00.5 +08:00".to_string();
+        replace_separator(&mut s, b'.', ",");
+        assert_eq!(s, "01.05.2024 10:00:00,5 +08:00");
+        let mut s = "10:00:00".to_string();
+        replace_separator(&mut s, b'.', ",");
+        assert_eq!(s, "10:00:00");
+
+        let now = datetime!(2024-05-01 02:00:00.25 UTC);
+        let timer = LocalTime::rfc_3339().with_decimal_comma();
+        assert_eq!(render(&timer, now), "2024-05-01T02:00:00,25Z");
+
+        let timer = LocalTime::with_timezone(
+            format_description!(
+                "[day].[month].[year] [hour]:[minute]:[second].[subsecond digits:2]"
+            ),
+            (0, 0, 0),
+        )
+        .with_decimal_comma();
+        assert_eq!(render(&timer, now), "01.05.2024 02:00:00,25");
+    }
+}
@@ -0,0 +1,156 @@
+//! Elapsed-time rendering that sits well next to the timestamps.
+
+use std::fmt;
+use std::time::Duration;
+
+/// The style used by [`format_duration`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DurationStyle {
+    /// Compact unit-suffixed form, e.g. `1h02m03.4s`, `3.4s` or `340ms`.
+    #[default]
+    Compact,
+    /// Clock-like form with millisecond precision, e.g. `01:02:03.400`.
+    Colon,
+    /// Spelled-out units, e.g. `1 hour 2 minutes 3 seconds`.
+    Humanized,
+}
+
+/// A [`Duration`] that renders itself in a given [`DurationStyle`].
+///
+/// Returned by [`format_duration`]; use it with `write!` or `to_string()`.
+#[derive(Clone, Copy, Debug)]
+pub struct FormattedDuration {
+    duration: Duration,
+    style: DurationStyle,
+}
+
+/// Renders `duration` in the provided `style`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use clia_local_time::{format_duration, DurationStyle};
+///
+/// let d = Duration::from_millis(3_723_400);
+/// assert_eq!(format_duration(d, DurationStyle::Compact).to_string(), "1h02m03.4s");
+/// assert_eq!(format_duration(d, DurationStyle::Colon).to_string(), "01:02:03.400");
+/// assert_eq!(
+///     format_duration(d, DurationStyle::Humanized).to_string(),
+///     "1 hour 2 minutes 3 seconds"
+/// );
+/// ```
+pub fn format_duration(duration: Duration, style: DurationStyle) -> FormattedDuration {
+    FormattedDuration { duration, style }
+}
+
+// === impl FormattedDuration ===
+
+impl FormattedDuration {
+    fn fmt_compact(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.duration.as_secs();
+        let nanos = self.duration.subsec_nanos();
+        let tenths = nanos / 100_000_000;
+        if secs >= 3600 {
+            write!(
+                f,
+                "{}h{:02}m{:02}.{}s",
+                secs / 3600,
+                secs / 60 % 60,
+                secs % 60,
+                tenths
+            )
+        } else if secs >= 60 {
+            write!(f, "{}m{:02}.{}s", secs / 60, secs % 60, tenths)
+        } else if secs >= 1 {
+            write!(f, "{}.{}s", secs, tenths)
+        } else if nanos >= 1_000_000 {
+            write!(f, "{}ms", nanos / 1_000_000)
+        } else if nanos >= 1_000 {
+            write!(f, "{}µs", nanos / 1_000)
+        } else {
+            write!(f, "{}ns", nanos)
+        }
+    }
+
+    fn fmt_colon(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.duration.as_secs();
+        write!(
+            f,
+            "{:02}:{:02}:{:02}.{:03}",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60,
+            self.duration.subsec_millis()
+        )
+    }
+
+    fn fmt_humanized(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.duration.as_secs();
+        if secs == 0 {
+            return write_unit(f, u64::from(self.duration.subsec_millis()), "millisecond");
+        }
+        let units = [
+            (secs / 86_400, "day"),
+            (secs / 3600 % 24, "hour"),
+            (secs / 60 % 60, "minute"),
+            (secs % 60, "second"),
+        ];
+        let mut first = true;
+        for (value, unit) in units.iter().filter(|(value, _)| *value > 0) {
+            if !first {
+                f.write_str(" ")?;
+            }
+            write_unit(f, *value, unit)?;
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for FormattedDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.style {
+            DurationStyle::Compact => self.fmt_compact(f),
+            DurationStyle::Colon => self.fmt_colon(f),
+            DurationStyle::Humanized => self.fmt_humanized(f),
+        }
+    }
+}
+
+fn write_unit(f: &mut fmt::Formatter<'_>, value: u64, unit: &str) -> fmt::Result {
+    let plural = if value == 1 { "" } else { "s" };
+    write!(f, "{} {}{}", value, unit, plural)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_duration, DurationStyle};
+    use std::time::Duration;
+
+    #[test]
+    fn test_small_durations() {
+        let render = |d, style| format_duration(d, style).to_string();
+        assert_eq!(
+            render(Duration::from_millis(340), DurationStyle::Compact),
+            "340ms"
+        );
+        assert_eq!(
+            render(Duration::from_micros(12), DurationStyle::Compact),
+            "12µs"
+        );
+        assert_eq!(
+            render(Duration::from_secs(62), DurationStyle::Compact),
+            "1m02.0s"
+        );
+        assert_eq!(render(Duration::ZERO, DurationStyle::Colon), "00:00:00.000");
+        assert_eq!(
+            render(Duration::from_millis(1), DurationStyle::Humanized),
+            "1 millisecond"
+        );
+        assert_eq!(
+            render(Duration::from_secs(90_000), DurationStyle::Humanized),
+            "1 day 1 hour"
+        );
+    }
+}
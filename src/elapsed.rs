@@ -0,0 +1,253 @@
+//! Timers that annotate the wall-clock time with elapsed durations.
+
+use std::cell::Cell;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
+
+use crate::LocalTime;
+
+/// Formats the time of an inner timer followed by the process uptime, e.g.
+/// `2024-05-01 10:00:00.123 (+342.117s)`.
+///
+/// This combines [`LocalTime`] with what tracing-subscriber's `Uptime` timer
+/// prints, which helps correlating log lines with profiler traces. Returned
+/// by [`LocalTime::with_uptime`].
+#[derive(Clone, Copy, Debug)]
+pub struct WithUptime<T> {
+    timer: T,
+    start: Instant,
+}
+
+// === impl WithUptime ===
+
+impl<T> WithUptime<T> {
+    /// Wraps `timer`, counting the uptime from `start`.
+    pub fn since(timer: T, start: Instant) -> Self {
+        Self { timer, start }
+    }
+}
+
+impl<T: FormatTime> FormatTime for WithUptime<T> {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        self.timer.format_time(w)?;
+        let uptime = self.start.elapsed();
+        write!(w, " (+{}.{:03}s)", uptime.as_secs(), uptime.subsec_millis())
+    }
+}
+
+/// Which events [`WithDelta`] measures the gap to, and
+/// [`LocalTime::monotonic`] keeps timestamps ordered across.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DeltaScope {
+    /// The previous event formatted by this timer, on any thread.
+    #[default]
+    Global,
+    /// The previous event formatted on the current thread.
+    ///
+    /// [`WithDelta`] keeps the last event in a thread-local shared by every
+    /// `PerThread` timer, while [`LocalTime::monotonic`] keeps one instant
+    /// per timer, since each timer may read a different clock.
+    PerThread,
+}
+
+/// Formats the time of an inner timer followed by the time elapsed since the
+/// previous event, e.g. `10:00:00.123 (+1.2ms)`.
+///
+/// The first event shows the time elapsed since the timer was created.
+/// Returned by [`LocalTime::with_delta`].
+#[derive(Debug)]
+pub struct WithDelta<T> {
+    timer: T,
+    scope: DeltaScope,
+    start: Instant,
+    /// Nanoseconds between `start` and the previous event.
+    last: AtomicU64,
+}
+
+/// What a [`Relative`] timer measures from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RelativeTo {
+    /// The creation of the timer, normally at process start.
+    Start,
+    /// The previous event within the scope.
+    Previous(DeltaScope),
+}
+
+/// Formats a short human delta instead of the time, e.g. `2s ago` or
+/// `5m ago`.
+///
+/// This suits interactive `cargo run` output; keep a [`LocalTime`] on the
+/// layers writing files.
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::{DeltaScope, Relative, RelativeTo};
+///
+/// let console = tracing_subscriber::fmt()
+///     .with_timer(Relative::new(RelativeTo::Previous(DeltaScope::Global)));
+/// # drop(console);
+/// ```
+#[derive(Debug)]
+pub struct Relative {
+    origin: RelativeTo,
+    delta: WithDelta<()>,
+}
+
+thread_local! {
+    static LAST_EVENT: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+// === impl WithDelta ===
+
+impl<T> WithDelta<T> {
+    /// Wraps `timer`, measuring gaps within `scope`.
+    pub fn new(timer: T, scope: DeltaScope) -> Self {
+        Self {
+            timer,
+            scope,
+            start: Instant::now(),
+            last: AtomicU64::new(0),
+        }
+    }
+
+    fn delta(&self) -> Duration {
+        match self.scope {
+            DeltaScope::Global => {
+                let now = self.start.elapsed().as_nanos() as u64;
+                let prev = self.last.swap(now, Ordering::Relaxed);
+                Duration::from_nanos(now.saturating_sub(prev))
+            }
+            DeltaScope::PerThread => {
+                let now = Instant::now();
+                let prev = LAST_EVENT.with(|last| last.replace(Some(now)));
+                // Ignore events formatted before this timer existed.
+                let prev = prev.map_or(self.start, |prev| prev.max(self.start));
+                now.saturating_duration_since(prev)
+            }
+        }
+    }
+}
+
+impl<T: FormatTime> FormatTime for WithDelta<T> {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        self.timer.format_time(w)?;
+        write!(w, " (+{:.1?})", self.delta())
+    }
+}
+
+// === impl Relative ===
+
+impl Relative {
+    /// Returns a timer measuring from `origin`.
+    pub fn new(origin: RelativeTo) -> Self {
+        let scope = match origin {
+            RelativeTo::Start => DeltaScope::Global,
+            RelativeTo::Previous(scope) => scope,
+        };
+        Self {
+            origin,
+            delta: WithDelta::new((), scope),
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        match self.origin {
+            RelativeTo::Start => self.delta.start.elapsed(),
+            RelativeTo::Previous(_) => self.delta.delta(),
+        }
+    }
+}
+
+impl FormatTime for Relative {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        write!(w, "{} ago", Short(self.elapsed()))
+    }
+}
+
+/// A duration in its largest whole unit, e.g. `5m` for 5m59s.
+struct Short(Duration);
+
+impl fmt::Display for Short {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.0.as_secs();
+        match secs {
+            0 => write!(f, "{}ms", self.0.subsec_millis()),
+            1..=59 => write!(f, "{}s", secs),
+            60..=3599 => write!(f, "{}m", secs / 60),
+            3600..=86_399 => write!(f, "{}h", secs / 3600),
+            _ => write!(f, "{}d", secs / 86_400),
+        }
+    }
+}
+
+// === impl LocalTime ===
+
+impl<F, P, C> LocalTime<F, P, C> {
+    /// Appends the time elapsed since this call to every timestamp, e.g.
+    /// `2024-05-01 10:00:00.123 (+342.117s)`.
+    ///
+    /// Call this early in `main` so the uptime counts from process start.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    ///
+    /// let timer = LocalTime::rfc_3339().with_uptime();
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(timer);
+    /// # drop(collector);
+    /// ```
+    pub fn with_uptime(self) -> WithUptime<Self> {
+        WithUptime::since(self, Instant::now())
+    }
+
+    /// Appends the time elapsed since the previous event to every timestamp,
+    /// e.g. `10:00:00.123 (+1.2ms)`, to make latency gaps stand out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::{DeltaScope, LocalTime};
+    ///
+    /// let timer = LocalTime::rfc_3339().with_delta(DeltaScope::PerThread);
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(timer);
+    /// # drop(collector);
+    /// ```
+    pub fn with_delta(self, scope: DeltaScope) -> WithDelta<Self> {
+        WithDelta::new(self, scope)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeltaScope, Short, WithDelta};
+    use std::time::Duration;
+
+    #[test]
+    fn test_delta() {
+        for scope in [DeltaScope::Global, DeltaScope::PerThread] {
+            let timer = WithDelta::new((), scope);
+            timer.delta();
+            std::thread::sleep(Duration::from_millis(5));
+            let delta = timer.delta();
+            assert!(delta >= Duration::from_millis(5), "{:?}", delta);
+            assert!(timer.delta() < delta);
+        }
+    }
+
+    #[test]
+    fn test_short() {
+        let short = |secs| Short(Duration::from_secs(secs)).to_string();
+        assert_eq!(Short(Duration::from_millis(120)).to_string(), "120ms");
+        assert_eq!(short(2), "2s");
+        assert_eq!(short(359), "5m");
+        assert_eq!(short(7200), "2h");
+        assert_eq!(short(200_000), "2d");
+    }
+}
@@ -0,0 +1,189 @@
+//! Numeric epoch timestamps for the JSON formatter.
+//!
+//! tracing-subscriber's JSON formatter always quotes whatever the timer
+//! writes. [`JsonEpochTime`] writes a bare epoch number, and wrapping the
+//! formatter in [`UnquotedTimestamp`] drops the quotes around it, so
+//! pipelines like Elasticsearch or Loki index it as a number.
+
+use std::fmt;
+
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::{
+    format::{FormatEvent, FormatFields, Writer},
+    time::FormatTime,
+    FmtContext,
+};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::clock::{ClockSource, SystemClock};
+
+/// The unit a [`JsonEpochTime`] counts in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EpochUnit {
+    /// Whole milliseconds, e.g. `1714528800123`.
+    #[default]
+    Millis,
+    /// Seconds with six decimals, e.g. `1714528800.123456`; more digits would
+    /// exceed what a JSON double holds.
+    Seconds,
+}
+
+/// Formats the current time as a number of milliseconds or seconds since the
+/// Unix epoch.
+///
+/// The JSON formatter needs tracing-subscriber's `json` feature.
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::{JsonEpochTime, UnquotedTimestamp};
+///
+/// let format = tracing_subscriber::fmt::format()
+///     .json()
+///     .with_timer(JsonEpochTime::millis());
+/// let collector = tracing_subscriber::fmt()
+///     .json()
+///     .event_format(UnquotedTimestamp(format));
+/// # drop(collector);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct JsonEpochTime<C = SystemClock> {
+    unit: EpochUnit,
+    clock: C,
+}
+
+/// Wraps a JSON event formatter, writing a leading numeric `timestamp` field
+/// as a bare number instead of a string.
+///
+/// Lines whose `timestamp` isn't numeric are written unchanged.
+#[derive(Clone, Copy, Debug)]
+pub struct UnquotedTimestamp<E>(pub E);
+
+// === impl JsonEpochTime ===
+
+impl JsonEpochTime {
+    /// Returns a timer counting in `unit`.
+    pub fn new(unit: EpochUnit) -> Self {
+        Self {
+            unit,
+            clock: SystemClock::new(),
+        }
+    }
+
+    /// Returns a timer counting whole milliseconds.
+    pub fn millis() -> Self {
+        Self::new(EpochUnit::Millis)
+    }
+
+    /// Returns a timer counting seconds with six decimals.
+    pub fn seconds() -> Self {
+        Self::new(EpochUnit::Seconds)
+    }
+}
+
+impl<C> JsonEpochTime<C> {
+    /// Reads the time from `clock` instead of the system clock.
+    pub fn with_clock<C2: ClockSource>(self, clock: C2) -> JsonEpochTime<C2> {
+        JsonEpochTime {
+            unit: self.unit,
+            clock,
+        }
+    }
+}
+
+impl<C: ClockSource> FormatTime for JsonEpochTime<C> {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        let nanos = self.clock.now_utc().unix_timestamp_nanos();
+        let sign = if nanos < 0 { "-" } else { "" };
+        let nanos = nanos.unsigned_abs();
+        match self.unit {
+            EpochUnit::Millis => write!(w, "{}{}", sign, nanos / 1_000_000),
+            EpochUnit::Seconds => write!(
+                w,
+                "{}{}.{:06}",
+                sign,
+                nanos / 1_000_000_000,
+                nanos % 1_000_000_000 / 1_000
+            ),
+        }
+    }
+}
+
+// === impl UnquotedTimestamp ===
+
+impl<S, N, E> FormatEvent<S, N> for UnquotedTimestamp<E>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+    E: FormatEvent<S, N>,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let mut line = String::new();
+        self.0.format_event(ctx, Writer::new(&mut line), event)?;
+        writer.write_str(&unquote_timestamp(&line))
+    }
+}
+
+/// Removes the quotes around the value of a leading `"timestamp"` field if
+/// it's a number.
+fn unquote_timestamp(line: &str) -> std::borrow::Cow<'_, str> {
+    const KEY: &str = "{\"timestamp\":\"";
+    let Some(rest) = line.strip_prefix(KEY) else {
+        return line.into();
+    };
+    let Some(end) = rest.find('"') else {
+        return line.into();
+    };
+    let value = &rest[..end];
+    let numeric = value.parse::<f64>().is_ok()
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_digit() || b == b'-' || b == b'.');
+    if !numeric {
+        return line.into();
+    }
+    format!("{{\"timestamp\":{}{}", value, &rest[end + 1..]).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JsonEpochTime, UnquotedTimestamp};
+    use crate::{clock::Fixed, testing::Capture};
+    use time::macros::datetime;
+
+    #[test]
+    fn test_unquoted_timestamp() {
+        let at = datetime!(2024-05-01 02:00:00.123456789 UTC);
+        for (timer, expected) in [
+            (JsonEpochTime::millis(), "{\"timestamp\":1714528800123,"),
+            (
+                JsonEpochTime::seconds(),
+                "{\"timestamp\":1714528800.123456,",
+            ),
+        ] {
+            let capture = Capture::default();
+            let writer = capture.clone();
+            let format = tracing_subscriber::fmt::format()
+                .json()
+                .with_timer(timer.with_clock(Fixed(at)));
+            let subscriber = tracing_subscriber::fmt()
+                .json()
+                .event_format(UnquotedTimestamp(format))
+                .with_writer(move || writer.clone())
+                .finish();
+            tracing::subscriber::with_default(subscriber, || tracing::info!("hello"));
+            let line = capture.output();
+            assert!(line.starts_with(expected), "{}", line);
+        }
+        assert_eq!(
+            super::unquote_timestamp("{\"timestamp\":\"x\"}"),
+            "{\"timestamp\":\"x\"}"
+        );
+    }
+}
@@ -0,0 +1,336 @@
+//! Handling timestamps the format can't render.
+//!
+//! Formatting fails, e.g., for an RFC 3339 timer in an offset with seconds,
+//! or a year past 9999. tracing-subscriber then drops the whole line. With
+//! [`LocalTime::on_error`] the failure is reported instead, and a substitute
+//! can be written in place of the timestamp, such as a fixed
+//! [`Placeholder`] with [`LocalTime::with_placeholder`] or a simpler format
+//! with [`LocalTime::with_fallback_format`], and [`LocalTime::with_stats`]
+//! counts how often that happens.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use time::{error, formatting::Formattable, OffsetDateTime};
+
+use crate::{try_format_datetime, LocalTime};
+
+type Hook =
+    dyn Fn(&error::Format, OffsetDateTime, &mut dyn fmt::Write) -> fmt::Result + Send + Sync;
+
+pub(crate) struct OnError {
+    hook: Box<Hook>,
+}
+
+/// What [`LocalTime::with_placeholder`] writes in place of a timestamp the
+/// format can't render.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Placeholder {
+    /// Fixed text, `<time-error>` by default.
+    Text(String),
+    /// The Unix timestamp in seconds, e.g. `1714528800`, which can still be
+    /// correlated with other logs.
+    EpochSeconds,
+}
+
+/// How many timestamps a timer rendered, and how many it failed to render.
+///
+/// Returned by [`LocalTime::stats`]. Clones of a timer share their counters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FormatStats {
+    /// Timestamps the format rendered.
+    pub formatted_ok: u64,
+    /// Timestamps the format failed to render.
+    pub format_errors: u64,
+    /// Failures the [`on_error`](LocalTime::on_error) hook wrote a substitute
+    /// for.
+    pub fallbacks_used: u64,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Counters {
+    formatted_ok: AtomicU64,
+    format_errors: AtomicU64,
+    fallbacks_used: AtomicU64,
+}
+
+// === impl OnError ===
+
+impl OnError {
+    pub(crate) fn call(
+        &self,
+        error: &error::Format,
+        now: OffsetDateTime,
+        w: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        (self.hook)(error, now, w)
+    }
+}
+
+impl fmt::Debug for OnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OnError").finish_non_exhaustive()
+    }
+}
+
+// === impl Placeholder ===
+
+impl Placeholder {
+    fn write(&self, now: OffsetDateTime, w: &mut dyn fmt::Write) -> fmt::Result {
+        match self {
+            Placeholder::Text(text) => w.write_str(text),
+            Placeholder::EpochSeconds => write!(w, "{}", now.unix_timestamp()),
+        }
+    }
+}
+
+impl Default for Placeholder {
+    fn default() -> Self {
+        Placeholder::Text("<time-error>".to_string())
+    }
+}
+
+// === impl Counters ===
+
+impl Counters {
+    pub(crate) fn record(&self, ok: bool) {
+        let counter = if ok {
+            &self.formatted_ok
+        } else {
+            &self.format_errors
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_fallback(&self) {
+        self.fallbacks_used.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> FormatStats {
+        FormatStats {
+            formatted_ok: self.formatted_ok.load(Ordering::Relaxed),
+            format_errors: self.format_errors.load(Ordering::Relaxed),
+            fallbacks_used: self.fallbacks_used.load(Ordering::Relaxed),
+        }
+    }
+}
+
+// === impl LocalTime ===
+
+impl<F, P, C> LocalTime<F, P, C> {
+    /// Calls `hook` with the error and the instant whenever the format fails
+    /// to render a timestamp.
+    ///
+    /// Whatever `hook` writes replaces the timestamp, and returning `Ok`
+    /// keeps the line. Returning `Err` drops the line, as happens without a
+    /// hook. Timestamps are rendered into a buffer first, so no partial
+    /// output precedes the substitute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use time::{format_description::well_known::Rfc3339, macros::datetime};
+    ///
+    /// static FAILURES: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// // RFC 3339 can't express offset seconds.
+    /// let timer = LocalTime::with_timezone(Rfc3339, (5, 53, 28)).on_error(|_error, now, w| {
+    ///     FAILURES.fetch_add(1, Ordering::Relaxed);
+    ///     write!(w, "@{}", now.unix_timestamp())
+    /// });
+    /// let mut s = String::new();
+    /// timer.format_datetime(datetime!(2024-05-01 02:00 UTC), &mut s).unwrap();
+    /// assert_eq!(s, "@1714528800");
+    /// assert_eq!(FAILURES.load(Ordering::Relaxed), 1);
+    /// ```
+    pub fn on_error(
+        self,
+        hook: impl Fn(&error::Format, OffsetDateTime, &mut dyn fmt::Write) -> fmt::Result
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        Self {
+            on_error: Some(Arc::new(OnError {
+                hook: Box::new(hook),
+            })),
+            ..self
+        }
+    }
+
+    /// Writes `placeholder` in place of timestamps the format can't render,
+    /// instead of dropping the line. This replaces any
+    /// [`on_error`](LocalTime::on_error) hook.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::{LocalTime, Placeholder};
+    /// use time::{format_description::well_known::Rfc3339, macros::datetime};
+    ///
+    /// let timer = LocalTime::with_timezone(Rfc3339, (5, 53, 28))
+    ///     .with_placeholder(Placeholder::default());
+    /// let mut s = String::new();
+    /// timer.format_datetime(datetime!(2024-05-01 02:00 UTC), &mut s).unwrap();
+    /// assert_eq!(s, "<time-error>");
+    /// ```
+    pub fn with_placeholder(self, placeholder: Placeholder) -> Self {
+        self.on_error(move |_error, now, w| placeholder.write(now, w))
+    }
+
+    /// Renders timestamps the format can't render in `fallback` instead,
+    /// e.g. plain RFC 3339 behind an exotic custom format.
+    ///
+    /// If `fallback` fails too, the [`on_error`](LocalTime::on_error) hook or
+    /// [placeholder](LocalTime::with_placeholder) set before this is used, so
+    /// they can be chained.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::{LocalTime, Placeholder};
+    /// use time::format_description::well_known::Rfc3339;
+    /// use time::macros::{datetime, format_description};
+    ///
+    /// // RFC 3339 can't express offset seconds.
+    /// let timer = LocalTime::with_timezone(Rfc3339, (5, 53, 28))
+    ///     .with_placeholder(Placeholder::EpochSeconds)
+    ///     .with_fallback_format(format_description!(
+    ///         "[year]-[month]-[day]T[hour]:[minute]:[second]\
+    ///          [offset_hour sign:mandatory]:[offset_minute]:[offset_second]"
+    ///     ));
+    /// let mut s = String::new();
+    /// timer.format_datetime(datetime!(2024-05-01 02:00 UTC), &mut s).unwrap();
+    /// assert_eq!(s, "2024-05-01T07:53:28+05:53:28");
+    /// ```
+    pub fn with_fallback_format(self, fallback: impl Formattable + Send + Sync + 'static) -> Self {
+        let previous = self.on_error.clone();
+        self.on_error(move |error, now, w| {
+            let mut s = String::new();
+            match (try_format_datetime(now, &mut s, &fallback), &previous) {
+                (Ok(()), _) => w.write_str(&s),
+                (Err(_), Some(previous)) => previous.call(error, now, w),
+                (Err(_), None) => Err(fmt::Error),
+            }
+        })
+    }
+
+    /// Counts rendered and failed timestamps, so operators can alert when
+    /// formatting starts failing. Read the counts with
+    /// [`stats`](LocalTime::stats).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::macros::datetime;
+    ///
+    /// let timer = LocalTime::rfc_3339().with_stats();
+    /// timer.format_datetime(datetime!(2024-05-01 02:00 UTC), &mut String::new()).unwrap();
+    /// assert_eq!(timer.stats().unwrap().formatted_ok, 1);
+    /// ```
+    pub fn with_stats(self) -> Self {
+        Self {
+            stats: Some(Arc::new(Counters::default())),
+            ..self
+        }
+    }
+
+    /// The counts since [`with_stats`](LocalTime::with_stats) was called, if
+    /// it was.
+    pub fn stats(&self) -> Option<FormatStats> {
+        self.stats.as_ref().map(|stats| stats.snapshot())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Placeholder;
+    use crate::{testing::render, LocalTime};
+    use std::sync::{Arc, Mutex};
+    use time::format_description::well_known::Rfc3339;
+    use time::macros::datetime;
+
+    #[test]
+    fn test_on_error() {
+        let at = datetime!(2024-05-01 02:00 UTC);
+        let timer = LocalTime::with_timezone(Rfc3339, (5, 53, 28)).decorated("[", "]");
+        assert!(timer.format_datetime(at, &mut String::new()).is_err());
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let timer = timer.on_error({
+            let seen = seen.clone();
+            move |error, _now, w| {
+                seen.lock().unwrap().push(error.to_string());
+                w.write_str("?")
+            }
+        });
+        assert_eq!(render(&timer, at), "[?]");
+        assert_eq!(seen.lock().unwrap().len(), 1);
+
+        // Successful timestamps don't reach the hook.
+        let timer = LocalTime::rfc_3339().on_error(|_, _, _| Err(std::fmt::Error));
+        assert_eq!(render(&timer, at), "2024-05-01T02:00:00Z");
+    }
+
+    #[test]
+    fn test_placeholder() {
+        let timer = LocalTime::with_timezone(Rfc3339, (5, 53, 28))
+            .decorated("", " ")
+            .with_placeholder(Placeholder::EpochSeconds);
+        assert_eq!(
+            render(&timer, datetime!(2024-05-01 02:00 UTC)),
+            "1714528800 "
+        );
+    }
+
+    #[test]
+    fn test_fallback_format() {
+        let at = datetime!(2024-05-01 02:00 UTC);
+        let render = |timer: LocalTime<Rfc3339>| {
+            let mut s = String::new();
+            timer.format_datetime(at, &mut s).map(|()| s)
+        };
+        let timer = LocalTime::with_timezone(Rfc3339, (5, 53, 28));
+        let fallback = time::macros::format_description!("[hour]:[minute]:[second]");
+        assert_eq!(
+            render(timer.clone().with_fallback_format(fallback)).unwrap(),
+            "07:53:28"
+        );
+        // The fallback fails too, so the placeholder set before it is used.
+        assert!(render(timer.clone().with_fallback_format(Rfc3339)).is_err());
+        let timer = timer
+            .with_placeholder(Placeholder::default())
+            .with_fallback_format(Rfc3339);
+        assert_eq!(render(timer).unwrap(), "<time-error>");
+    }
+
+    #[test]
+    fn test_stats() {
+        let at = datetime!(2024-05-01 02:00 UTC);
+        let timer = LocalTime::with_timezone(Rfc3339, (5, 53, 28)).with_stats();
+        let copy = timer.clone();
+        assert!(timer.format_datetime(at, &mut String::new()).is_err());
+        let timer = timer.on_error(|_, _, w| w.write_str("?"));
+        timer.format_datetime(at, &mut String::new()).unwrap();
+        let stats = copy.stats().unwrap();
+        assert_eq!(
+            (
+                stats.formatted_ok,
+                stats.format_errors,
+                stats.fallbacks_used
+            ),
+            (0, 2, 1)
+        );
+
+        let timer = LocalTime::rfc_3339().with_stats();
+        timer.format_datetime(at, &mut String::new()).unwrap();
+        assert_eq!(timer.stats().unwrap().formatted_ok, 1);
+        assert_eq!(LocalTime::rfc_3339().stats(), None);
+    }
+}
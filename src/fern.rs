@@ -0,0 +1,77 @@
+//! A [`fern`] formatter rendering timestamps with [`LocalTime`].
+
+use std::fmt;
+
+use tracing_subscriber::fmt::time::FormatTime;
+
+use crate::LocalTime;
+
+// === impl LocalTime ===
+
+impl<F, P, C> LocalTime<F, P, C>
+where
+    Self: FormatTime + Send + Sync + 'static,
+{
+    /// Returns a formatter for [`fern::Dispatch::format`] writing lines like
+    /// tracing-subscriber's default format, e.g.
+    /// `2024-05-01T10:00:00+08:00  INFO app: started`, with this timer's
+    /// timestamps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    ///
+    /// let dispatch = fern::Dispatch::new()
+    ///     .format(LocalTime::rfc_3339().fern_format())
+    ///     .chain(std::io::stderr());
+    /// # drop(dispatch);
+    /// ```
+    pub fn fern_format(
+        self,
+    ) -> impl Fn(fern::FormatCallback<'_>, &fmt::Arguments<'_>, &log::Record<'_>) + Send + Sync + 'static
+    {
+        move |out, message, record| {
+            out.finish(format_args!(
+                "{} {:>5} {}: {}",
+                self.timestamp(),
+                record.level(),
+                record.target(),
+                message
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::clock::Fixed;
+    use crate::LocalTime;
+    use time::macros::datetime;
+
+    #[test]
+    fn test_fern_format() {
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let sink = lines.clone();
+        let timer = LocalTime::w3c((8, 0, 0)).with_clock(Fixed(datetime!(2024-05-01 02:00 UTC)));
+        let (_, logger) = fern::Dispatch::new()
+            .format(timer.fern_format())
+            .chain(fern::Output::call(move |record| {
+                sink.lock().unwrap().push(record.args().to_string())
+            }))
+            .into_log();
+        logger.log(
+            &log::Record::builder()
+                .args(format_args!("started"))
+                .level(log::Level::Info)
+                .target("app")
+                .build(),
+        );
+        assert_eq!(
+            *lines.lock().unwrap(),
+            ["2024-05-01 10:00:00  INFO app: started"]
+        );
+    }
+}
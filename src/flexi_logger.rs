@@ -0,0 +1,91 @@
+//! A [`flexi_logger`] format function rendering timestamps with
+//! [`LocalTime`](crate::LocalTime).
+//!
+//! flexi_logger's `FormatFunction` is a plain function pointer, so the timer
+//! is installed once per process with [`set_timer`] and [`format`] reads it.
+//! Timestamps come from the timer rather than flexi_logger's `DeferredNow`,
+//! which detects the local offset with the same multithreading caveat
+//! `time` has.
+
+use std::io;
+use std::sync::OnceLock;
+
+use ::flexi_logger::DeferredNow;
+use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
+
+use crate::LocalTime;
+
+type Timer = Box<dyn FormatTime + Send + Sync>;
+
+static TIMER: OnceLock<Timer> = OnceLock::new();
+
+/// Installs the timer [`format`] renders timestamps with.
+///
+/// It can only be set once; later calls return the timer back. Until it's
+/// set, timestamps are RFC 3339 in UTC.
+pub fn set_timer<T: FormatTime + Send + Sync + 'static>(timer: T) -> Result<(), T> {
+    let mut timer = Some(timer);
+    TIMER.get_or_init(|| Box::new(timer.take().expect("only taken once")));
+    timer.map_or(Ok(()), Err)
+}
+
+/// Writes a record like tracing-subscriber's default format, e.g.
+/// `2024-05-01T10:00:00+08:00  INFO [app] started`, with the timer installed
+/// by [`set_timer`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use clia_local_time::flexi_logger::{format, set_timer};
+/// use clia_local_time::LocalTime;
+///
+/// set_timer(LocalTime::w3c((8, 0, 0))).ok();
+/// let _logger = flexi_logger::Logger::try_with_str("info")
+///     .unwrap()
+///     .format(format)
+///     .start()
+///     .unwrap();
+/// ```
+pub fn format(
+    w: &mut dyn io::Write,
+    _now: &mut DeferredNow,
+    record: &log::Record<'_>,
+) -> io::Result<()> {
+    let timer = TIMER.get_or_init(|| Box::new(LocalTime::rfc_3339()));
+    let mut timestamp = String::new();
+    timer
+        .format_time(&mut Writer::new(&mut timestamp))
+        .map_err(|_| io::Error::other("failed to format timestamp"))?;
+    write!(
+        w,
+        "{} {:>5} [{}] {}",
+        timestamp,
+        record.level(),
+        record.module_path().unwrap_or("<unnamed>"),
+        record.args()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use ::flexi_logger::DeferredNow;
+
+    use crate::clock::Fixed;
+    use crate::LocalTime;
+    use time::macros::datetime;
+
+    #[test]
+    fn test_format() {
+        let timer = LocalTime::w3c((8, 0, 0)).with_clock(Fixed(datetime!(2024-05-01 02:00 UTC)));
+        assert!(super::set_timer(timer).is_ok());
+        assert!(super::set_timer(LocalTime::rfc_3339()).is_err());
+        let mut buf = Vec::new();
+        let record = log::Record::builder()
+            .args(format_args!("started"))
+            .level(log::Level::Info)
+            .module_path(Some("app"))
+            .build();
+        super::format(&mut buf, &mut DeferredNow::new(), &record).unwrap();
+        assert_eq!(buf, b"2024-05-01 10:00:00  INFO [app] started");
+    }
+}
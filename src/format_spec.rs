@@ -0,0 +1,211 @@
+//! One config string for the timestamp format, next to [`TimezoneSpec`]
+//! for the timezone.
+//!
+//! [`TimezoneSpec`]: crate::TimezoneSpec
+
+use std::fmt;
+use std::str::FromStr;
+
+use time::{error::InvalidFormatDescription, format_description::OwnedFormatItem, UtcOffset};
+
+use crate::{offset::FixedOffset, LocalTime};
+
+/// A timestamp format, as parsed from `rfc3339`, `unix_millis` or a format
+/// description such as `[hour]:[minute]:[second]`.
+///
+/// With the `serde` feature it is (de)serialized as that string.
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::TimeFormatSpec;
+///
+/// assert_eq!("RFC3339".parse(), Ok(TimeFormatSpec::Rfc3339));
+/// assert_eq!(
+///     "[hour]:[minute]".parse(),
+///     Ok(TimeFormatSpec::Custom("[hour]:[minute]".to_string()))
+/// );
+/// assert!("iso".parse::<TimeFormatSpec>().is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+#[non_exhaustive]
+pub enum TimeFormatSpec {
+    /// [RFC 3339], as rendered by [`LocalTime::rfc_3339`]: `rfc3339`.
+    ///
+    /// [RFC 3339]: https://datatracker.ietf.org/doc/html/rfc3339
+    Rfc3339,
+    /// [RFC 2822], e.g. `Wed, 01 May 2024 10:00:00 +0800`: `rfc2822`.
+    ///
+    /// [RFC 2822]: https://datatracker.ietf.org/doc/html/rfc2822
+    Rfc2822,
+    /// Seconds since the Unix epoch: `unix`.
+    UnixSeconds,
+    /// Milliseconds since the Unix epoch: `unix_millis`.
+    UnixMillis,
+    /// A format description in the syntax of
+    /// [`time::format_description::parse`].
+    Custom(String),
+}
+
+/// The error returned when a [`TimeFormatSpec`] can't be parsed or built.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FormatSpecError {
+    /// The input is neither a known format name nor a format description.
+    Unknown(String),
+    /// The format description is invalid.
+    Invalid(InvalidFormatDescription),
+}
+
+// === impl TimeFormatSpec ===
+
+impl TimeFormatSpec {
+    /// Returns a timer rendering this format in `offset`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::TimeFormatSpec;
+    /// use time::macros::{datetime, offset};
+    ///
+    /// let spec: TimeFormatSpec = "rfc2822".parse().expect("spec should be valid");
+    /// let timer = spec.into_timer(offset!(+8)).expect("spec should build");
+    /// let mut s = String::new();
+    /// timer.format_datetime(datetime!(2024-05-01 02:00 UTC), &mut s).unwrap();
+    /// assert_eq!(s, "Wed, 01 May 2024 10:00:00 +0800");
+    /// ```
+    pub fn into_timer(
+        self,
+        offset: UtcOffset,
+    ) -> Result<LocalTime<OwnedFormatItem>, FormatSpecError> {
+        let format = self.format()?;
+        Ok(LocalTime {
+            fast_rfc3339: self == TimeFormatSpec::Rfc3339,
+            ..LocalTime::with_provider(format, FixedOffset(offset))
+        })
+    }
+
+    /// The format description parsed from this spec.
+    pub(crate) fn format(&self) -> Result<OwnedFormatItem, FormatSpecError> {
+        parse(self.description())
+    }
+
+    /// The format description this format is rendered with. RFC 3339 is
+    /// only rendered with it when the specialized writer fails.
+    fn description(&self) -> &str {
+        match self {
+            TimeFormatSpec::Rfc3339 => {
+                "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond]\
+                 [offset_hour sign:mandatory]:[offset_minute]"
+            }
+            TimeFormatSpec::Rfc2822 => {
+                "[weekday repr:short], [day] [month repr:short] [year] \
+                 [hour]:[minute]:[second] [offset_hour sign:mandatory][offset_minute]"
+            }
+            TimeFormatSpec::UnixSeconds => "[unix_timestamp]",
+            TimeFormatSpec::UnixMillis => "[unix_timestamp precision:millisecond]",
+            TimeFormatSpec::Custom(description) => description,
+        }
+    }
+}
+
+impl FromStr for TimeFormatSpec {
+    type Err = FormatSpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.to_ascii_lowercase().replace('-', "_");
+        Ok(match name.as_str() {
+            "rfc3339" | "rfc_3339" => TimeFormatSpec::Rfc3339,
+            "rfc2822" | "rfc_2822" => TimeFormatSpec::Rfc2822,
+            "unix" | "unix_seconds" => TimeFormatSpec::UnixSeconds,
+            "unix_millis" => TimeFormatSpec::UnixMillis,
+            _ if s.contains('[') => {
+                parse(s)?;
+                TimeFormatSpec::Custom(s.to_string())
+            }
+            _ => return Err(FormatSpecError::Unknown(s.to_string())),
+        })
+    }
+}
+
+impl TryFrom<String> for TimeFormatSpec {
+    type Error = FormatSpecError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for TimeFormatSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeFormatSpec::Rfc3339 => f.write_str("rfc3339"),
+            TimeFormatSpec::Rfc2822 => f.write_str("rfc2822"),
+            TimeFormatSpec::UnixSeconds => f.write_str("unix"),
+            TimeFormatSpec::UnixMillis => f.write_str("unix_millis"),
+            TimeFormatSpec::Custom(description) => f.write_str(description),
+        }
+    }
+}
+
+impl From<TimeFormatSpec> for String {
+    fn from(spec: TimeFormatSpec) -> Self {
+        spec.to_string()
+    }
+}
+
+fn parse(description: &str) -> Result<OwnedFormatItem, FormatSpecError> {
+    time::format_description::parse_owned::<1>(description).map_err(FormatSpecError::Invalid)
+}
+
+// === impl FormatSpecError ===
+
+impl fmt::Display for FormatSpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatSpecError::Unknown(s) => write!(f, "unknown timestamp format `{}`", s),
+            FormatSpecError::Invalid(e) => write!(f, "invalid format description: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FormatSpecError {}
+
+#[cfg(test)]
+mod tests {
+    use super::{FormatSpecError, TimeFormatSpec};
+    use time::macros::{datetime, offset};
+
+    fn render(spec: &str) -> String {
+        let timer = spec
+            .parse::<TimeFormatSpec>()
+            .unwrap()
+            .into_timer(offset!(+8))
+            .unwrap();
+        crate::testing::render(&timer, datetime!(2024-05-01 02:00:00.5 UTC))
+    }
+
+    #[test]
+    fn test_format_spec() {
+        assert_eq!(render("rfc3339"), "2024-05-01T10:00:00.5+08:00");
+        assert_eq!(render("unix"), "1714528800");
+        assert_eq!(render("unix_millis"), "1714528800500");
+        assert_eq!(render("[hour]:[minute]"), "10:00");
+        for s in ["rfc3339", "rfc2822", "unix", "unix_millis", "[hour]"] {
+            assert_eq!(s.parse::<TimeFormatSpec>().unwrap().to_string(), s);
+        }
+        assert!(matches!(
+            "[hour".parse::<TimeFormatSpec>(),
+            Err(FormatSpecError::Invalid(_))
+        ));
+        assert!(matches!(
+            "hh:mm".parse::<TimeFormatSpec>(),
+            Err(FormatSpecError::Unknown(_))
+        ));
+    }
+}
@@ -0,0 +1,226 @@
+//! Hybrid logical clock timestamps, for merging logs from several nodes in
+//! causal order.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use time::{formatting::Formattable, OffsetDateTime};
+use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
+
+use crate::clock::{ClockSource, SystemClock};
+use crate::offset::{FixedOffset, OffsetProvider};
+use crate::LocalTime;
+
+/// Formats [hybrid logical clock] timestamps: the physical time in the
+/// timer's timezone and format, followed by a logical counter, e.g.
+/// `2024-05-01T10:00:00.123+08:00,2`.
+///
+/// The physical component has millisecond resolution. Events within the same
+/// millisecond, or while the clock is behind a timestamp already issued or
+/// [observed](HlcTime::observe), keep the physical component and increment
+/// the counter, so timestamps never go backwards and a message's receipt is
+/// always logged after its sending, on any node. The counter follows the
+/// timer's decoration.
+///
+/// Clones of the clock share its state.
+///
+/// [hybrid logical clock]: https://cse.buffalo.edu/tech-reports/2014-04.pdf
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::{HlcTime, LocalTime};
+///
+/// let hlc = HlcTime::new(LocalTime::rfc_3339());
+/// let collector = tracing_subscriber::fmt()
+///     .with_timer(hlc.clone());
+/// # drop(collector);
+///
+/// // Attach `hlc.tick().to_u64()` to outgoing messages, and pass the ones
+/// // received to `hlc.observe`.
+/// let sent = hlc.tick();
+/// assert!(hlc.observe(sent) > sent);
+/// ```
+#[derive(Debug)]
+pub struct HlcTime<F, P = FixedOffset, C = SystemClock> {
+    timer: Arc<LocalTime<F, P, C>>,
+    /// The last timestamp issued, as packed by [`HlcTimestamp::to_u64`].
+    last: Arc<AtomicU64>,
+}
+
+/// A timestamp issued by an [`HlcTime`]. Timestamps order by their physical
+/// component, then by their logical one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HlcTimestamp {
+    /// Milliseconds since the Unix epoch, below 2<sup>48</sup>.
+    pub physical: u64,
+    /// The counter distinguishing timestamps with the same physical
+    /// component.
+    pub logical: u16,
+}
+
+const PHYSICAL_MAX: u64 = (1 << 48) - 1;
+
+// === impl HlcTime ===
+
+impl<F, P, C> HlcTime<F, P, C> {
+    /// Returns a clock reading physical time from `timer`, which also renders
+    /// it.
+    pub fn new(timer: LocalTime<F, P, C>) -> Self {
+        Self {
+            timer: Arc::new(timer),
+            last: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// The last timestamp issued.
+    pub fn last(&self) -> HlcTimestamp {
+        HlcTimestamp::from_u64(self.last.load(Ordering::Acquire))
+    }
+
+    /// Issues the next timestamp from the physical time `now` and, for a
+    /// received message, its timestamp `remote`.
+    fn issue(&self, now: OffsetDateTime, remote: Option<HlcTimestamp>) -> HlcTimestamp {
+        let physical =
+            (now.unix_timestamp_nanos() / 1_000_000).clamp(0, PHYSICAL_MAX.into()) as u64;
+        let mut next = HlcTimestamp::default();
+        // The closure always returns `Some`, so the update can't fail.
+        let _ = self
+            .last
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |last| {
+                let last = HlcTimestamp::from_u64(last);
+                let latest = remote.map_or(last, |remote| last.max(remote));
+                next = if physical > latest.physical {
+                    HlcTimestamp {
+                        physical,
+                        logical: 0,
+                    }
+                } else {
+                    latest.successor()
+                };
+                Some(next.to_u64())
+            });
+        next
+    }
+}
+
+impl<F: Formattable, P: OffsetProvider, C: ClockSource> HlcTime<F, P, C> {
+    /// Issues a timestamp for a local event, e.g. for sending a message.
+    pub fn tick(&self) -> HlcTimestamp {
+        self.issue(self.timer.now(), None)
+    }
+
+    /// Issues a timestamp for receiving a message sent at `remote`, after
+    /// both `remote` and every timestamp issued so far.
+    pub fn observe(&self, remote: HlcTimestamp) -> HlcTimestamp {
+        self.issue(self.timer.now(), Some(remote))
+    }
+
+    /// Writes `timestamp` as this clock renders it.
+    pub fn format_timestamp(
+        &self,
+        timestamp: HlcTimestamp,
+        w: &mut impl fmt::Write,
+    ) -> fmt::Result {
+        let nanos = i128::from(timestamp.physical) * 1_000_000;
+        let physical = OffsetDateTime::from_unix_timestamp_nanos(nanos).map_err(|_| fmt::Error)?;
+        self.timer.format_datetime(physical, w)?;
+        write!(w, ",{}", timestamp.logical)
+    }
+}
+
+impl<F, P, C> Clone for HlcTime<F, P, C> {
+    fn clone(&self) -> Self {
+        Self {
+            timer: self.timer.clone(),
+            last: self.last.clone(),
+        }
+    }
+}
+
+impl<F: Formattable, P: OffsetProvider, C: ClockSource> FormatTime for HlcTime<F, P, C> {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        self.format_timestamp(self.tick(), w)
+    }
+}
+
+// === impl HlcTimestamp ===
+
+impl HlcTimestamp {
+    /// Packs the timestamp into 64 bits, e.g. for a message header: the
+    /// physical component in the upper 48 and the logical one in the lower
+    /// 16. Packed timestamps order like unpacked ones.
+    pub fn to_u64(self) -> u64 {
+        (self.physical.min(PHYSICAL_MAX) << 16) | u64::from(self.logical)
+    }
+
+    /// Unpacks a timestamp packed by [`to_u64`](HlcTimestamp::to_u64).
+    pub fn from_u64(packed: u64) -> Self {
+        Self {
+            physical: packed >> 16,
+            logical: packed as u16,
+        }
+    }
+
+    /// The timestamp right after this one. When the counter would overflow,
+    /// the physical component advances instead.
+    fn successor(self) -> Self {
+        match self.logical.checked_add(1) {
+            Some(logical) => Self { logical, ..self },
+            None => Self {
+                physical: (self.physical + 1).min(PHYSICAL_MAX),
+                logical: 0,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HlcTime, HlcTimestamp};
+    use crate::{clock::ReplayClock, LocalTime};
+    use time::macros::datetime;
+
+    #[test]
+    fn test_hlc() {
+        let clock = ReplayClock::new(datetime!(2024-05-01 02:00:00.123 UTC));
+        let hlc = HlcTime::new(LocalTime::rfc_3339().with_clock(clock.clone()));
+        let first = hlc.tick();
+        assert_eq!(first.logical, 0);
+        assert_eq!(
+            hlc.tick(),
+            HlcTimestamp {
+                logical: 1,
+                ..first
+            }
+        );
+
+        let remote = HlcTimestamp {
+            physical: first.physical + 5,
+            logical: 7,
+        };
+        assert_eq!(
+            hlc.observe(remote),
+            HlcTimestamp {
+                logical: 8,
+                ..remote
+            }
+        );
+        clock.seek(datetime!(2024-05-01 02:00:00.124 UTC));
+        assert_eq!(
+            hlc.tick(),
+            HlcTimestamp {
+                logical: 9,
+                ..remote
+            }
+        );
+        clock.seek(datetime!(2024-05-01 02:00:01 UTC));
+        assert_eq!(hlc.tick().logical, 0);
+
+        let mut s = String::new();
+        hlc.format_timestamp(remote, &mut s).unwrap();
+        assert_eq!(s, "2024-05-01T02:00:00.128Z,7");
+        assert_eq!(HlcTimestamp::from_u64(remote.to_u64()), remote);
+    }
+}
@@ -0,0 +1,76 @@
+//! Timestamps matching the `humantime` crate's RFC 3339 output.
+
+use time::{format_description::StaticFormatDescription, macros::format_description};
+
+use crate::LocalTime;
+
+/// The subsecond digits of a [`LocalTime::humantime`] timestamp, matching
+/// the `format_rfc3339*` function of the same suffix.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HumantimePrecision {
+    /// `format_rfc3339_seconds`, e.g. `2024-05-01T02:00:00Z`.
+    #[default]
+    Seconds,
+    /// `format_rfc3339_millis`, e.g. `2024-05-01T02:00:00.123Z`.
+    Millis,
+    /// `format_rfc3339_micros`, e.g. `2024-05-01T02:00:00.123456Z`.
+    Micros,
+    /// `format_rfc3339_nanos`, e.g. `2024-05-01T02:00:00.123456789Z`.
+    Nanos,
+}
+
+// === impl LocalTime ===
+
+impl LocalTime<StaticFormatDescription> {
+    /// Returns a formatter producing what `humantime`'s `format_rfc3339*`
+    /// functions produce: UTC with a `Z` suffix and a fixed number of
+    /// subsecond digits, trailing zeros included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::{HumantimePrecision, LocalTime};
+    /// use time::macros::datetime;
+    ///
+    /// let timer = LocalTime::humantime(HumantimePrecision::Millis);
+    /// let mut s = String::new();
+    /// timer.format_datetime(datetime!(2024-05-01 10:00:00.5 +8), &mut s).unwrap();
+    /// assert_eq!(s, "2024-05-01T02:00:00.500Z");
+    /// ```
+    pub fn humantime(precision: HumantimePrecision) -> Self {
+        let format = match precision {
+            HumantimePrecision::Seconds => {
+                format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]Z")
+            }
+            HumantimePrecision::Millis => format_description!(
+                "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z"
+            ),
+            HumantimePrecision::Micros => format_description!(
+                "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:6]Z"
+            ),
+            HumantimePrecision::Nanos => format_description!(
+                "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:9]Z"
+            ),
+        };
+        Self::with_timezone(format, (0, 0, 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HumantimePrecision;
+    use crate::{testing::render, LocalTime};
+    use time::macros::datetime;
+
+    #[test]
+    fn test_precisions() {
+        let at = datetime!(2024-05-01 02:00:00.1234567 UTC);
+        for (precision, expected) in [
+            (HumantimePrecision::Seconds, "2024-05-01T02:00:00Z"),
+            (HumantimePrecision::Micros, "2024-05-01T02:00:00.123456Z"),
+            (HumantimePrecision::Nanos, "2024-05-01T02:00:00.123456700Z"),
+        ] {
+            assert_eq!(render(&LocalTime::humantime(precision), at), expected);
+        }
+    }
+}
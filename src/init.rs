@@ -0,0 +1,53 @@
+//! Installing a `fmt` subscriber with a [`LocalTime`](crate::LocalTime) in
+//! one call.
+
+use std::error::Error;
+
+use time::UtcOffset;
+
+use crate::TimeFormatSpec;
+
+/// Installs a `tracing_subscriber::fmt` subscriber timestamping events in
+/// `format` and `offset` as the global default.
+///
+/// # Panics
+///
+/// Panics if the format is invalid or a global default subscriber has
+/// already been set. Use [`try_init`] to handle these instead.
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::TimeFormatSpec;
+/// use time::macros::offset;
+///
+/// clia_local_time::init(offset!(+8), TimeFormatSpec::Rfc3339);
+/// tracing::info!("timestamped in UTC+8");
+/// ```
+pub fn init(offset: UtcOffset, format: TimeFormatSpec) {
+    try_init(offset, format).expect("Unable to install global subscriber")
+}
+
+/// Installs a `tracing_subscriber::fmt` subscriber timestamping events in
+/// `format` and `offset` as the global default, failing if the format is
+/// invalid or a global default subscriber has already been set.
+pub fn try_init(
+    offset: UtcOffset,
+    format: TimeFormatSpec,
+) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+    let timer = format.into_timer(offset)?;
+    tracing_subscriber::fmt().with_timer(timer).try_init()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::try_init;
+    use crate::TimeFormatSpec;
+    use time::UtcOffset;
+
+    #[test]
+    fn test_try_init() {
+        let custom = TimeFormatSpec::Custom("[hour".to_string());
+        assert!(try_init(UtcOffset::UTC, custom).is_err());
+    }
+}
@@ -0,0 +1,361 @@
+//! Looking inside a format before any timestamp is written, e.g. to find out
+//! whether it renders the offset at all, or to catch formats that would
+//! render misleading timestamps.
+
+use std::fmt;
+
+use time::format_description::{well_known, BorrowedFormatItem, Component, OwnedFormatItem};
+
+use crate::{ClockSource, LocalTime, OffsetProvider};
+
+/// A format whose components can be listed ahead of formatting.
+///
+/// Implemented for parsed format descriptions, borrowed or owned, and for the
+/// `time` crate's well-known formats, which report the components they are
+/// rendered from.
+pub trait InspectFormat {
+    /// Calls `f` with every component the format renders, in order.
+    ///
+    /// Only the first alternative of a `[first ...]` item is rendered, so
+    /// only its components are reported.
+    fn for_each_component(&self, f: &mut dyn FnMut(Component));
+
+    /// Returns whether the format renders any part of the UTC offset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::InspectFormat;
+    /// use time::{format_description::well_known::Rfc3339, macros::format_description};
+    ///
+    /// assert!(Rfc3339.has_offset());
+    /// assert!(!format_description!("[hour]:[minute]:[second]").has_offset());
+    /// ```
+    fn has_offset(&self) -> bool {
+        let mut found = false;
+        self.for_each_component(&mut |component| {
+            found |= matches!(
+                component,
+                Component::OffsetHour(_) | Component::OffsetMinute(_) | Component::OffsetSecond(_)
+            );
+        });
+        found
+    }
+
+    /// Returns whether the format renders a zero offset as the UTC designator
+    /// `Z`, as RFC 3339 and ISO 8601 do. Format descriptions always render
+    /// it numerically.
+    fn has_utc_designator(&self) -> bool {
+        false
+    }
+}
+
+impl<T: InspectFormat + ?Sized> InspectFormat for &T {
+    fn for_each_component(&self, f: &mut dyn FnMut(Component)) {
+        (**self).for_each_component(f)
+    }
+
+    fn has_utc_designator(&self) -> bool {
+        (**self).has_utc_designator()
+    }
+}
+
+impl InspectFormat for BorrowedFormatItem<'_> {
+    #[allow(deprecated)]
+    fn for_each_component(&self, f: &mut dyn FnMut(Component)) {
+        match self {
+            BorrowedFormatItem::Component(component) => f(*component),
+            BorrowedFormatItem::Compound(items) => items.for_each_component(f),
+            BorrowedFormatItem::Optional(item) => item.for_each_component(f),
+            BorrowedFormatItem::First(items) => {
+                if let Some(item) = items.first() {
+                    item.for_each_component(f);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl InspectFormat for [BorrowedFormatItem<'_>] {
+    fn for_each_component(&self, f: &mut dyn FnMut(Component)) {
+        for item in self {
+            item.for_each_component(f);
+        }
+    }
+}
+
+impl InspectFormat for Vec<BorrowedFormatItem<'_>> {
+    fn for_each_component(&self, f: &mut dyn FnMut(Component)) {
+        self.as_slice().for_each_component(f)
+    }
+}
+
+impl InspectFormat for OwnedFormatItem {
+    #[allow(deprecated)]
+    fn for_each_component(&self, f: &mut dyn FnMut(Component)) {
+        match self {
+            OwnedFormatItem::Component(component) => f(*component),
+            OwnedFormatItem::Compound(items) => items.for_each_component(f),
+            OwnedFormatItem::Optional(item) => item.for_each_component(f),
+            OwnedFormatItem::First(items) => {
+                if let Some(item) = items.first() {
+                    item.for_each_component(f);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl InspectFormat for [OwnedFormatItem] {
+    fn for_each_component(&self, f: &mut dyn FnMut(Component)) {
+        for item in self {
+            item.for_each_component(f);
+        }
+    }
+}
+
+impl InspectFormat for Vec<OwnedFormatItem> {
+    fn for_each_component(&self, f: &mut dyn FnMut(Component)) {
+        self.as_slice().for_each_component(f)
+    }
+}
+
+impl InspectFormat for well_known::Rfc3339 {
+    fn for_each_component(&self, f: &mut dyn FnMut(Component)) {
+        date_time_offset(f)
+    }
+
+    fn has_utc_designator(&self) -> bool {
+        true
+    }
+}
+
+/// Only the default configuration, which renders the offset.
+impl InspectFormat for well_known::Iso8601 {
+    fn for_each_component(&self, f: &mut dyn FnMut(Component)) {
+        date_time_offset(f)
+    }
+
+    fn has_utc_designator(&self) -> bool {
+        true
+    }
+}
+
+impl InspectFormat for well_known::Rfc2822 {
+    fn for_each_component(&self, f: &mut dyn FnMut(Component)) {
+        f(Component::WeekdayShort(Default::default()));
+        f(Component::Day(Default::default()));
+        f(Component::MonthShort(Default::default()));
+        f(Component::CalendarYearFullStandardRange(Default::default()));
+        f(Component::Hour24(Default::default()));
+        f(Component::Minute(Default::default()));
+        f(Component::Second(Default::default()));
+        f(Component::OffsetHour(Default::default()));
+        f(Component::OffsetMinute(Default::default()));
+    }
+}
+
+/// A likely mistake in a timer's format, returned by [`LocalTime::validate`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FormatWarning {
+    /// The format renders the offset, but the offset can't be detected and
+    /// falls back to UTC, so `+00:00` is written whatever the local zone is.
+    UtcFallback,
+    /// The hour is on a 12-hour clock, but the format has no `[period]`, so
+    /// morning and evening can't be told apart.
+    TwelveHourWithoutPeriod,
+    /// A component that only applies to parsing, such as `[ignore]` or
+    /// `[end]`, and renders nothing.
+    Unsupported(Component),
+}
+
+// === impl FormatWarning ===
+
+impl fmt::Display for FormatWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatWarning::UtcFallback => {
+                f.write_str("the offset can't be detected, so UTC is rendered as the local offset")
+            }
+            FormatWarning::TwelveHourWithoutPeriod => {
+                f.write_str("12-hour clock without a [period] component")
+            }
+            FormatWarning::Unsupported(component) => {
+                write!(f, "{:?} renders nothing when formatting", component)
+            }
+        }
+    }
+}
+
+/// The components of an RFC 3339 or default ISO 8601 timestamp.
+fn date_time_offset(f: &mut dyn FnMut(Component)) {
+    f(Component::CalendarYearFullStandardRange(Default::default()));
+    f(Component::MonthNumerical(Default::default()));
+    f(Component::Day(Default::default()));
+    f(Component::Hour24(Default::default()));
+    f(Component::Minute(Default::default()));
+    f(Component::Second(Default::default()));
+    f(Component::Subsecond(Default::default()));
+    f(Component::OffsetHour(Default::default()));
+    f(Component::OffsetMinute(Default::default()));
+}
+
+// === impl LocalTime ===
+
+impl<F: InspectFormat, P, C> LocalTime<F, P, C> {
+    /// Appends the offset, e.g. ` +08:00`, to every timestamp if the format
+    /// doesn't render it already, so `[hour]:[minute]:[second]` logs can't be
+    /// misread as another zone. Offset seconds are only written when nonzero.
+    ///
+    /// The format is inspected once, here.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::macros::{datetime, format_description};
+    ///
+    /// let format = format_description!("[hour]:[minute]:[second]");
+    /// let timer = LocalTime::with_timezone(format, (8, 0, 0)).with_auto_offset();
+    /// let mut s = String::new();
+    /// timer.format_datetime(datetime!(2024-05-01 02:00 UTC), &mut s).unwrap();
+    /// assert_eq!(s, "10:00:00 +08:00");
+    /// ```
+    pub fn with_auto_offset(self) -> Self {
+        Self {
+            auto_offset: !self.format.has_offset(),
+            ..self
+        }
+    }
+}
+
+impl<F: InspectFormat, P: OffsetProvider, C: ClockSource> LocalTime<F, P, C> {
+    /// Checks the format for common mistakes that would render misleading
+    /// timestamps rather than fail, returning an empty list if there are
+    /// none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::{FormatWarning, LocalTime};
+    /// use time::macros::format_description;
+    ///
+    /// let timer = LocalTime::new(format_description!("[hour repr:12]:[minute]"));
+    /// assert_eq!(timer.validate(), [FormatWarning::TwelveHourWithoutPeriod]);
+    /// assert!(LocalTime::twelve_hour((8, 0, 0)).validate().is_empty());
+    /// ```
+    #[allow(deprecated)]
+    pub fn validate(&self) -> Vec<FormatWarning> {
+        let (mut offset, mut twelve_hour, mut period) = (false, false, false);
+        let mut unsupported = Vec::new();
+        self.format
+            .for_each_component(&mut |component| match component {
+                Component::OffsetHour(_)
+                | Component::OffsetMinute(_)
+                | Component::OffsetSecond(_) => offset = true,
+                Component::Hour12(_) => twelve_hour = true,
+                Component::Hour(hour) if hour.is_12_hour_clock => twelve_hour = true,
+                Component::Period(_) => period = true,
+                Component::Ignore(_) | Component::End(_) => {
+                    unsupported.push(FormatWarning::Unsupported(component))
+                }
+                _ => {}
+            });
+        let mut warnings = Vec::new();
+        if twelve_hour && !period {
+            warnings.push(FormatWarning::TwelveHourWithoutPeriod);
+        }
+        let now = self.clock.now_utc();
+        if offset && self.offset.is_fallback(now) && self.offset.offset_at(now).is_utc() {
+            warnings.push(FormatWarning::UtcFallback);
+        }
+        warnings.extend(unsupported);
+        warnings
+    }
+
+    /// Logs every warning [`validate`](LocalTime::validate) finds as a `WARN`
+    /// event with target `clia_local_time::validate`.
+    ///
+    /// Call it right after installing the subscriber.
+    pub fn emit_warnings(&self) {
+        for warning in self.validate() {
+            tracing::warn!(target: "clia_local_time::validate", "timestamp format: {}", warning);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FormatWarning, InspectFormat};
+    use crate::testing::render;
+    use crate::LocalTime;
+    use crate::OffsetProvider;
+    use time::format_description::well_known::{Rfc2822, Rfc3339};
+    use time::macros::{datetime, format_description};
+    use time::{OffsetDateTime, UtcOffset};
+
+    #[test]
+    fn test_has_offset() {
+        assert!(Rfc3339.has_offset());
+        assert!(Rfc2822.has_offset());
+        assert!(format_description!("[hour] [offset_hour]").has_offset());
+        assert!(!format_description!("[hour]:[minute]").has_offset());
+        // Only the first alternative is rendered.
+        let first =
+            time::format_description::parse_owned::<2>("[first [[hour]] [[offset_hour]]]").unwrap();
+        assert!(!first.has_offset());
+        let optional =
+            time::format_description::parse_owned::<2>("[hour][optional [[offset_hour]]]").unwrap();
+        assert!(optional.has_offset());
+    }
+
+    #[test]
+    fn test_auto_offset() {
+        let at = datetime!(2024-05-01 02:00 UTC);
+        let timer = LocalTime::with_timezone(format_description!("[hour]:[minute]"), (-3, -30, -5));
+        assert_eq!(render(&timer.with_auto_offset(), at), "22:29 -03:30:05");
+        // Already rendered, so nothing is appended.
+        let timer = LocalTime::rfc_3339_seconds((8, 0, 0)).with_auto_offset();
+        assert_eq!(render(&timer, at), "2024-05-01T10:00:00+08:00");
+        assert!(!timer.config().auto_offset);
+    }
+
+    #[derive(Clone)]
+    struct Undetected;
+
+    impl OffsetProvider for Undetected {
+        fn offset_at(&self, _utc: OffsetDateTime) -> UtcOffset {
+            UtcOffset::UTC
+        }
+
+        fn is_fallback(&self, _utc: OffsetDateTime) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let timer = LocalTime::with_provider(Rfc3339, Undetected);
+        assert_eq!(timer.validate(), [FormatWarning::UtcFallback]);
+        let timer = LocalTime::with_provider(format_description!("[hour]:[minute]"), Undetected);
+        assert!(timer.validate().is_empty());
+
+        let format = time::format_description::parse_owned::<2>(
+            "[hour repr:12][ignore count:2][offset_hour]",
+        )
+        .unwrap();
+        let warnings = LocalTime::with_provider(format, Undetected).validate();
+        assert_eq!(
+            warnings[..2],
+            [
+                FormatWarning::TwelveHourWithoutPeriod,
+                FormatWarning::UtcFallback
+            ]
+        );
+        assert!(matches!(warnings[2], FormatWarning::Unsupported(_)));
+        assert_eq!(warnings.len(), 3);
+    }
+}
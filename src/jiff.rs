@@ -0,0 +1,178 @@
+//! Named timezones with real tz database semantics, backed by [`jiff`].
+//!
+//! [`JiffLocalTime`] looks the offset up for every event in a
+//! [`jiff::tz::TimeZone`], so daylight saving transitions are applied without
+//! this crate reimplementing tz rules. Formatting still goes through the same
+//! `time` formats as [`LocalTime`]. [`ZoneOffset`] applies the same rules to a
+//! [`LocalTime`] built with [`LocalTime::with_provider`].
+
+use std::fmt;
+
+use ::jiff::{tz::TimeZone, Timestamp};
+use time::{format_description::well_known, formatting::Formattable, OffsetDateTime, UtcOffset};
+use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
+
+use crate::{format_datetime, rfc3339, LocalTime, OffsetProvider};
+
+/// Formats the current time in a named timezone using a [formatter] from the
+/// [`time` crate].
+///
+/// [formatter]: https://docs.rs/time/0.3/time/formatting/trait.Formattable.html
+/// [`time` crate]: https://docs.rs/time/0.3/time/
+#[derive(Clone, Debug)]
+pub struct JiffLocalTime<F> {
+    format: F,
+    zone: ZoneOffset,
+    pub(crate) fast_rfc3339: bool,
+}
+
+/// An [`OffsetProvider`] evaluating a tz database zone's rules for every
+/// instant.
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::{jiff::ZoneOffset, LocalTime};
+/// use time::format_description::well_known::Rfc3339;
+///
+/// let zone = ZoneOffset::new("Europe/Berlin").expect("zone should exist in the tz database");
+/// let timer = LocalTime::with_provider(Rfc3339, zone);
+/// let collector = tracing_subscriber::fmt()
+///     .with_timer(timer);
+/// # drop(collector);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ZoneOffset(TimeZone);
+
+// === impl ZoneOffset ===
+
+impl ZoneOffset {
+    /// Looks the named zone, e.g. `Asia/Shanghai`, up in the tz database.
+    pub fn new(zone: &str) -> Result<Self, ::jiff::Error> {
+        TimeZone::get(zone).map(ZoneOffset)
+    }
+}
+
+impl From<TimeZone> for ZoneOffset {
+    fn from(zone: TimeZone) -> Self {
+        ZoneOffset(zone)
+    }
+}
+
+impl OffsetProvider for ZoneOffset {
+    fn offset_at(&self, utc: OffsetDateTime) -> UtcOffset {
+        Timestamp::new(utc.unix_timestamp(), utc.nanosecond() as i32)
+            .ok()
+            .and_then(|ts| UtcOffset::from_whole_seconds(self.0.to_offset(ts).seconds()).ok())
+            .unwrap_or(UtcOffset::UTC)
+    }
+}
+
+// === impl JiffLocalTime ===
+
+impl JiffLocalTime<well_known::Rfc3339> {
+    /// Returns a formatter that formats the current time in the named timezone
+    /// in the [RFC 3339] format.
+    ///
+    /// [RFC 3339]: https://datatracker.ietf.org/doc/html/rfc3339
+    pub fn rfc_3339(zone: &str) -> Result<Self, ::jiff::Error> {
+        Ok(Self {
+            fast_rfc3339: true,
+            ..Self::new(zone, well_known::Rfc3339)?
+        })
+    }
+}
+
+impl<F: Formattable> JiffLocalTime<F> {
+    /// Returns a formatter that formats the current time in the named timezone,
+    /// e.g. `Asia/Shanghai`, using the provided format.
+    ///
+    /// Returns an error if the zone can't be found in the tz database.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::JiffLocalTime;
+    /// use time::macros::format_description;
+    ///
+    /// let timer = JiffLocalTime::new(
+    ///     "Europe/Berlin",
+    ///     format_description!("[hour]:[minute]:[second]"),
+    /// )
+    /// .expect("zone should exist in the tz database");
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(timer);
+    /// # drop(collector);
+    /// ```
+    pub fn new(zone: &str, format: F) -> Result<Self, ::jiff::Error> {
+        Ok(Self::with_zone(TimeZone::get(zone)?, format))
+    }
+
+    /// Returns a formatter for an already resolved [`TimeZone`].
+    pub fn with_zone(zone: TimeZone, format: F) -> Self {
+        Self {
+            format,
+            zone: ZoneOffset(zone),
+            fast_rfc3339: false,
+        }
+    }
+}
+
+impl<F> JiffLocalTime<F> {
+    fn offset_at(&self, now: OffsetDateTime) -> UtcOffset {
+        self.zone.offset_at(now)
+    }
+}
+
+impl<F> FormatTime for JiffLocalTime<F>
+where
+    F: Formattable,
+{
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        let now = OffsetDateTime::now_utc();
+        let now = now.to_offset(self.offset_at(now));
+        if self.fast_rfc3339 {
+            rfc3339::write(now, w)
+        } else {
+            format_datetime(now, w, &self.format)
+        }
+    }
+}
+
+// === impl LocalTime ===
+
+impl<F: Formattable> LocalTime<F> {
+    /// Returns a formatter for a named timezone, e.g. `Asia/Shanghai`.
+    ///
+    /// This is a shorthand for [`JiffLocalTime::new`]. Being a constructor,
+    /// it's only defined on `LocalTime<F>`; for a [`LocalTime`] following the
+    /// zone, with every other builder available, pass a [`ZoneOffset`] to
+    /// [`LocalTime::with_provider`].
+    pub fn jiff_zone(zone: &str, format: F) -> Result<JiffLocalTime<F>, ::jiff::Error> {
+        JiffLocalTime::new(zone, format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JiffLocalTime;
+    use crate::testing::render_now;
+    use time::macros::datetime;
+
+    #[test]
+    fn test_dst_offset() {
+        let timer = JiffLocalTime::rfc_3339("Europe/Berlin").unwrap();
+        let winter = timer.offset_at(datetime!(2024-01-15 12:00 UTC));
+        let summer = timer.offset_at(datetime!(2024-07-15 12:00 UTC));
+        assert_eq!(winter.whole_hours(), 1);
+        assert_eq!(summer.whole_hours(), 2);
+    }
+
+    #[test]
+    fn test_rfc_3339() {
+        let timer = JiffLocalTime::rfc_3339("Asia/Shanghai").unwrap();
+        let s = render_now(&timer);
+        assert!(s.ends_with("+08:00"), "{}", s);
+        assert_eq!(&s[10..11], "T");
+    }
+}
@@ -0,0 +1,249 @@
+//! Degrading gracefully when the clock can't be read.
+//!
+//! Some sandboxes block clock syscalls, and a misconfigured clock may read
+//! before the Unix epoch. With [`LocalTime::with_degradation`], every event
+//! walks an explicit ladder until a rung succeeds:
+//!
+//! 1. [`Rung::Precise`]: the system clock, read with `clock_gettime` on
+//!    Unix so a blocked syscall fails instead of panicking.
+//! 2. [`Rung::Coarse`]: `CLOCK_REALTIME_COARSE` (Linux, `coarse` feature).
+//! 3. [`Rung::Cached`]: the last instant any rung above produced.
+//! 4. [`Rung::Sequence`]: a counter, rendered as `#42`, so lines stay ordered.
+
+use std::fmt;
+use std::sync::atomic::{AtomicI64, AtomicU64, AtomicU8, Ordering};
+use std::sync::Arc;
+
+use time::OffsetDateTime;
+
+use crate::{clock, ClockKind, LocalTime};
+
+/// A rung of the degradation ladder.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Rung {
+    /// The system clock.
+    Precise,
+    /// The coarse-grained system clock.
+    Coarse,
+    /// The last instant read successfully.
+    Cached,
+    /// A sequence counter, used when no instant was ever read.
+    Sequence,
+}
+
+type Hook = dyn Fn(Rung) + Send + Sync;
+
+pub(crate) struct Ladder {
+    hook: Box<Hook>,
+    rung: AtomicU8,
+    /// Unix nanoseconds of the last successful read, or `i64::MIN`.
+    last: AtomicI64,
+    sequence: AtomicU64,
+}
+
+/// What the ladder produced for one event.
+pub(crate) enum Reading {
+    Instant(OffsetDateTime, Rung),
+    Sequence(u64),
+}
+
+// === impl Rung ===
+
+impl Rung {
+    /// The kind of clock readings on this rung come from.
+    pub(crate) fn kind(self) -> ClockKind {
+        match self {
+            Rung::Precise => ClockKind::Wall,
+            Rung::Coarse => ClockKind::Coarse,
+            Rung::Cached => ClockKind::Cached,
+            Rung::Sequence => ClockKind::Sequence,
+        }
+    }
+
+    fn from_u8(n: u8) -> Self {
+        match n {
+            0 => Rung::Precise,
+            1 => Rung::Coarse,
+            2 => Rung::Cached,
+            _ => Rung::Sequence,
+        }
+    }
+}
+
+// === impl Ladder ===
+
+impl Ladder {
+    fn new(hook: Box<Hook>) -> Self {
+        Self {
+            hook,
+            rung: AtomicU8::new(Rung::Precise as u8),
+            last: AtomicI64::new(i64::MIN),
+            sequence: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn rung(&self) -> Rung {
+        Rung::from_u8(self.rung.load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn read(&self) -> Reading {
+        self.read_with(clock::try_now_utc, clock::try_coarse_now_utc)
+    }
+
+    fn read_with(
+        &self,
+        precise: impl FnOnce() -> Option<OffsetDateTime>,
+        coarse: impl FnOnce() -> Option<OffsetDateTime>,
+    ) -> Reading {
+        let (rung, reading) = if let Some(now) = precise() {
+            (Rung::Precise, self.remember(now, Rung::Precise))
+        } else if let Some(now) = coarse() {
+            (Rung::Coarse, self.remember(now, Rung::Coarse))
+        } else {
+            match self.last.load(Ordering::Relaxed) {
+                i64::MIN => (
+                    Rung::Sequence,
+                    Reading::Sequence(self.sequence.fetch_add(1, Ordering::Relaxed)),
+                ),
+                last => (
+                    Rung::Cached,
+                    OffsetDateTime::from_unix_timestamp_nanos(last.into())
+                        .map_or(Reading::Sequence(0), |last| {
+                            Reading::Instant(last, Rung::Cached)
+                        }),
+                ),
+            }
+        };
+        if self.rung.swap(rung as u8, Ordering::Relaxed) != rung as u8 {
+            (self.hook)(rung);
+        }
+        reading
+    }
+
+    fn remember(&self, now: OffsetDateTime, rung: Rung) -> Reading {
+        if let Ok(nanos) = i64::try_from(now.unix_timestamp_nanos()) {
+            self.last.store(nanos, Ordering::Relaxed);
+        }
+        Reading::Instant(now, rung)
+    }
+}
+
+impl fmt::Debug for Ladder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ladder")
+            .field("rung", &self.rung())
+            .finish_non_exhaustive()
+    }
+}
+
+// === impl LocalTime ===
+
+impl<F, P, C> LocalTime<F, P, C> {
+    /// Falls back along the [degradation ladder](crate::ladder) when the
+    /// clock can't be read, calling `hook` whenever the active rung changes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::{ladder::Rung, LocalTime};
+    ///
+    /// let timer = LocalTime::rfc_3339().with_degradation(|rung| {
+    ///     if rung != Rung::Precise {
+    ///         eprintln!("timestamps degraded to {:?}", rung);
+    ///     }
+    /// });
+    /// assert_eq!(timer.rung(), Some(Rung::Precise));
+    /// ```
+    pub fn with_degradation(self, hook: impl Fn(Rung) + Send + Sync + 'static) -> Self {
+        Self {
+            ladder: Some(Arc::new(Ladder::new(Box::new(hook)))),
+            ..self
+        }
+    }
+
+    /// The rung the last event was timestamped with, if
+    /// [`with_degradation`](LocalTime::with_degradation) is enabled.
+    pub fn rung(&self) -> Option<Rung> {
+        self.ladder.as_ref().map(|ladder| ladder.rung())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ladder, Reading, Rung};
+    use crate::{LocalTime, TimeScale};
+    use std::sync::{Arc, Mutex};
+    use time::{macros::datetime, Duration};
+
+    #[test]
+    fn test_ladder() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let hook = {
+            let seen = seen.clone();
+            move |rung| seen.lock().unwrap().push(rung)
+        };
+        let ladder = Ladder::new(Box::new(hook));
+        let now = datetime!(2024-05-01 10:00 UTC);
+        let fail = || None;
+
+        assert!(matches!(ladder.read_with(fail, fail), Reading::Sequence(0)));
+        assert!(matches!(ladder.read_with(fail, fail), Reading::Sequence(1)));
+        assert!(
+            matches!(ladder.read_with(fail, || Some(now)), Reading::Instant(t, Rung::Coarse) if t == now)
+        );
+        assert!(
+            matches!(ladder.read_with(fail, fail), Reading::Instant(t, Rung::Cached) if t == now)
+        );
+        assert!(matches!(
+            ladder.read_with(|| Some(now), fail),
+            Reading::Instant(_, Rung::Precise)
+        ));
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [Rung::Sequence, Rung::Coarse, Rung::Cached, Rung::Precise]
+        );
+    }
+
+    #[test]
+    fn test_degradation_keeps_adjustments() {
+        let now = datetime!(2024-05-01 10:00 UTC);
+        let render = |timer: LocalTime<_>| {
+            let mut s = String::new();
+            timer
+                .with_degradation(|_| {})
+                .format_reading(Reading::Instant(now, Rung::Precise), &mut s)
+                .unwrap();
+            s
+        };
+        let skewed = LocalTime::rfc_3339().with_skew(Duration::hours(5));
+        assert_eq!(render(skewed), "2024-05-01T15:00:00Z");
+        let tai = LocalTime::rfc_3339().with_time_scale(TimeScale::Tai);
+        assert_eq!(render(tai), "2024-05-01T10:00:37Z");
+    }
+
+    #[test]
+    fn test_degradation_source_tag() {
+        let now = datetime!(2024-05-01 10:00 UTC);
+        let timer = LocalTime::rfc_3339()
+            .with_source_tag()
+            .with_degradation(|_| {});
+        let render = |reading| {
+            let mut s = String::new();
+            timer.format_reading(reading, &mut s).unwrap();
+            s
+        };
+        assert_eq!(
+            render(Reading::Instant(now, Rung::Precise)),
+            "2024-05-01T10:00:00Z W"
+        );
+        assert_eq!(
+            render(Reading::Instant(now, Rung::Coarse)),
+            "2024-05-01T10:00:00Z C"
+        );
+        assert_eq!(
+            render(Reading::Instant(now, Rung::Cached)),
+            "2024-05-01T10:00:00Z L"
+        );
+        assert_eq!(render(Reading::Sequence(42)), "#42 S");
+    }
+}
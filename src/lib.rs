@@ -1,9 +1,246 @@
 //! A LocalTime implementation to set timezone manually.
+//!
+//! Without the default `std` feature the crate is `#![no_std]`, and only the
+//! core of the formatting path is available with the `time` feature: offset
+//! computation in [`offset`] and the allocation-free RFC 3339 writer in
+//! [`rfc3339`]. The `chrono` feature alone builds [`ChronoLocalTime`] without
+//! `time`.
+#![cfg_attr(not(any(feature = "std", feature = "chrono")), no_std)]
+#![cfg_attr(docsrs, feature(doc_cfg))]
 
-use std::fmt;
-use std::io;
-use time::{format_description::well_known, formatting::Formattable, OffsetDateTime, UtcOffset};
-use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
+#[cfg(feature = "std")]
+use std::{fmt, io, sync::Arc};
+#[cfg(feature = "std")]
+use time::{
+    format_description::{well_known, StaticFormatDescription},
+    formatting::Formattable,
+    macros::format_description,
+    OffsetDateTime, UtcOffset,
+};
+
+#[cfg(feature = "std")]
+use clock::SystemClock;
+#[cfg(feature = "std")]
+use failure::{Counters, OnError};
+#[cfg(feature = "std")]
+use ladder::{Ladder, Reading};
+#[cfg(feature = "std")]
+use monotonic::Monotonic;
+#[cfg(feature = "std")]
+use offset::FixedOffset;
+#[cfg(feature = "std")]
+use repeat::Repeats;
+
+#[cfg(feature = "std")]
+mod anchor;
+#[cfg(feature = "std")]
+mod ansi;
+#[cfg(feature = "appender")]
+#[cfg_attr(docsrs, doc(cfg(feature = "appender")))]
+pub mod appender;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod backend;
+#[cfg(feature = "background")]
+mod background;
+#[cfg(feature = "std")]
+mod backoff;
+#[cfg(feature = "std")]
+mod cache;
+#[cfg(feature = "std")]
+mod calendar;
+#[cfg(feature = "cbor")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cbor")))]
+pub mod cbor;
+#[cfg(feature = "chrono")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+pub mod chrono;
+#[cfg(feature = "clap")]
+#[cfg_attr(docsrs, doc(cfg(feature = "clap")))]
+pub mod clap;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod clock;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod config;
+#[cfg(feature = "std")]
+mod decimal_comma;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod duration;
+#[cfg(feature = "std")]
+mod elapsed;
+#[cfg(feature = "std")]
+mod epoch;
+#[cfg(feature = "std")]
+mod failure;
+#[cfg(feature = "fern")]
+mod fern;
+#[cfg(feature = "flexi_logger")]
+#[cfg_attr(docsrs, doc(cfg(feature = "flexi_logger")))]
+pub mod flexi_logger;
+#[cfg(feature = "std")]
+mod format_spec;
+#[cfg(feature = "std")]
+mod hlc;
+#[cfg(feature = "std")]
+mod humantime;
+#[cfg(feature = "std")]
+mod init;
+#[cfg(feature = "std")]
+mod inspect;
+#[cfg(feature = "jiff")]
+#[cfg_attr(docsrs, doc(cfg(feature = "jiff")))]
+pub mod jiff;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod ladder;
+#[cfg(feature = "local-offset")]
+mod local_offset;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod locale;
+#[cfg(feature = "std")]
+#[macro_use]
+mod macros;
+#[cfg(feature = "std")]
+mod monotonic;
+#[cfg(feature = "std")]
+mod multizone;
+#[cfg(feature = "ntp")]
+mod ntp;
+#[cfg(feature = "std")]
+mod numeric_utc;
+#[cfg(feature = "time")]
+pub mod offset;
+#[cfg(feature = "parsing")]
+mod parse;
+#[cfg(feature = "phc")]
+mod phc;
+#[cfg(feature = "std")]
+mod redacted;
+#[cfg(feature = "std")]
+mod repeat;
+#[cfg(feature = "time")]
+pub mod rfc3339;
+#[cfg(feature = "std")]
+mod round;
+#[cfg(feature = "std")]
+mod scale;
+#[cfg(feature = "parsing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "parsing")))]
+pub mod scan;
+#[cfg(feature = "std")]
+mod schedule;
+#[cfg(feature = "std")]
+mod shared;
+#[cfg(feature = "std")]
+mod snowflake;
+#[cfg(feature = "std")]
+mod span_elapsed;
+#[cfg(feature = "strftime")]
+#[cfg_attr(docsrs, doc(cfg(feature = "strftime")))]
+pub mod strftime;
+#[cfg(feature = "std")]
+mod subscriber;
+#[cfg(feature = "std")]
+mod system;
+#[cfg(feature = "std")]
+mod timestamp;
+#[cfg(feature = "std")]
+mod timezone;
+#[cfg(feature = "std")]
+mod ts_field;
+#[cfg(feature = "tsc")]
+mod tsc;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod tzif;
+#[cfg(feature = "std")]
+mod ulid;
+#[cfg(feature = "std")]
+mod utc;
+#[cfg(feature = "std")]
+mod writers;
+
+#[cfg(feature = "chrono")]
+pub use crate::chrono::ChronoLocalTime;
+#[cfg(feature = "jiff")]
+pub use crate::jiff::JiffLocalTime;
+#[cfg(feature = "std")]
+pub use anchor::Anchor;
+#[cfg(feature = "std")]
+pub use ansi::AnsiStyle;
+#[cfg(feature = "background")]
+pub use background::BackgroundTime;
+#[cfg(feature = "std")]
+pub use backoff::{Backoff, RetryNote};
+#[cfg(feature = "std")]
+pub use cache::CachedTime;
+#[cfg(feature = "std")]
+pub use calendar::JulianDate;
+#[cfg(feature = "std")]
+pub use clock::{ClockKind, ClockSource};
+#[cfg(feature = "std")]
+pub use config::{Config, Difference};
+#[cfg(feature = "std")]
+pub use duration::{format_duration, DurationStyle};
+#[cfg(feature = "std")]
+pub use elapsed::{DeltaScope, Relative, RelativeTo, WithDelta, WithUptime};
+#[cfg(feature = "std")]
+pub use epoch::{EpochUnit, JsonEpochTime, UnquotedTimestamp};
+#[cfg(feature = "std")]
+pub use failure::{FormatStats, Placeholder};
+#[cfg(feature = "std")]
+pub use format_spec::{FormatSpecError, TimeFormatSpec};
+#[cfg(feature = "std")]
+pub use hlc::{HlcTime, HlcTimestamp};
+#[cfg(feature = "std")]
+pub use humantime::HumantimePrecision;
+#[cfg(feature = "std")]
+pub use init::{init, try_init};
+#[cfg(feature = "std")]
+pub use inspect::{FormatWarning, InspectFormat};
+#[cfg(feature = "local-offset")]
+pub use local_offset::{init_local_offset, local_offset};
+#[cfg(feature = "std")]
+pub use locale::{Locale, LocalizedTime, WeekRule};
+#[doc(hidden)]
+#[cfg(feature = "std")]
+pub use macros::__private;
+#[cfg(feature = "std")]
+pub use multizone::{MultiZoneTime, ZoneTime};
+#[cfg(feature = "ntp")]
+pub use ntp::NtpClock;
+#[cfg(feature = "time")]
+pub use offset::OffsetProvider;
+#[cfg(feature = "parsing")]
+pub use parse::TimestampParseError;
+#[cfg(feature = "phc")]
+pub use phc::PhcClock;
+#[cfg(feature = "std")]
+pub use redacted::RedactedTime;
+#[cfg(feature = "std")]
+pub use scale::TimeScale;
+#[cfg(feature = "std")]
+pub use schedule::Boundary;
+#[cfg(feature = "std")]
+pub use shared::SharedTime;
+#[cfg(feature = "std")]
+pub use snowflake::SnowflakeTime;
+#[cfg(feature = "std")]
+pub use span_elapsed::{SpanTimingLayer, WithSpanElapsed};
+#[cfg(feature = "std")]
+pub use timezone::{convert, SpecOffset, TimezoneError, TimezoneSpec};
+#[cfg(feature = "std")]
+pub use ts_field::TimestampField;
+#[cfg(feature = "tsc")]
+pub use tsc::TscClock;
+#[cfg(feature = "std")]
+pub use ulid::UlidTime;
+#[cfg(feature = "std")]
+pub use utc::UtcTime;
 
 /// Formats the current [local time] using a [formatter] from the [`time` crate].
 ///
@@ -13,19 +250,41 @@ use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
 /// [UTC time]: https://docs.rs/time/0.3/time/struct.OffsetDateTime.html#method.now_utc
 /// [formatter]: https://docs.rs/time/0.3/time/formatting/trait.Formattable.html
 /// [`time` crate]: https://docs.rs/time/0.3/time/
+///
+/// The offset is chosen by an [`OffsetProvider`], a [`FixedOffset`] unless
+/// the timer is built with [`LocalTime::with_provider`].
 #[derive(Clone, Debug)]
 // #[cfg_attr(docsrs, doc(cfg(all(feature = "time", feature = "local-time"))))]
 // #[cfg(feature = "local-time")]
-pub struct LocalTime<F> {
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub struct LocalTime<F, P = FixedOffset, C = SystemClock> {
     format: F,
-    tz_hours: i8,
-    tz_minutes: i8,
-    tz_seconds: i8,
+    offset: P,
+    clock: C,
+    prefix: String,
+    suffix: String,
+    width: Option<usize>,
+    skew: time::Duration,
+    scale: TimeScale,
+    source_tag: bool,
+    ladder: Option<Arc<Ladder>>,
+    monotonic: Option<Arc<Monotonic>>,
+    fast_rfc3339: bool,
+    auto_offset: bool,
+    on_error: Option<Arc<OnError>>,
+    stats: Option<Arc<Counters>>,
+    round_to: Option<time::Duration>,
+    repeats: Option<Arc<Repeats>>,
+    ansi_style: Option<AnsiStyle>,
+    numeric_utc: bool,
+    decimal_comma: bool,
 }
 
 // === impl LocalTime ===
 
 // #[cfg(feature = "local-time")]
+#[cfg(feature = "std")]
 impl LocalTime<well_known::Rfc3339> {
     /// Returns a formatter that formats the current [local time] in the
     /// [RFC 3339] format (a subset of the [ISO 8601] timestamp format).
@@ -33,22 +292,194 @@ impl LocalTime<well_known::Rfc3339> {
     /// # Examples
     ///
     /// ```
-    /// use tracing_subscriber::fmt::{self, time};
+    /// use clia_local_time::LocalTime;
     ///
     /// let collector = tracing_subscriber::fmt()
-    ///     .with_timer(time::LocalTime::rfc_3339());
+    ///     .with_timer(LocalTime::rfc_3339());
     /// # drop(collector);
     /// ```
     ///
+    /// The timestamp is written by a specialized formatter rather than
+    /// `time`'s generic one, with identical output.
+    ///
     /// [local time]: https://docs.rs/time/0.3/time/struct.OffsetDateTime.html#method.now_local
     /// [RFC 3339]: https://datatracker.ietf.org/doc/html/rfc3339
     /// [ISO 8601]: https://en.wikipedia.org/wiki/ISO_8601
     pub fn rfc_3339() -> Self {
-        Self::new(well_known::Rfc3339)
+        Self {
+            fast_rfc3339: true,
+            ..Self::new(well_known::Rfc3339)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl LocalTime<StaticFormatDescription> {
+    /// Returns a formatter for the `date` and `time` fields of the
+    /// [W3C Extended Log File Format], e.g. `2024-05-01 10:00:00`.
+    ///
+    /// The two fields are separated by a single space, so the timestamp can be
+    /// emitted as the leading `date time` columns of a W3C access log line. Both
+    /// fields are rendered in the provided timezone rather than in UTC.
+    ///
+    /// Timezone format: (tz_hours, tz_minutes, tz_seconds)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    ///
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(LocalTime::w3c((8, 0, 0)));
+    /// # drop(collector);
+    /// ```
+    ///
+    /// [W3C Extended Log File Format]: https://www.w3.org/TR/WD-logfile.html
+    pub fn w3c(tz_hms: (i8, i8, i8)) -> Self {
+        Self::with_timezone(
+            format_description!("[year]-[month]-[day] [hour]:[minute]:[second]"),
+            tz_hms,
+        )
+    }
+
+    /// Returns a formatter matching the timestamps OpenTelemetry exporters
+    /// emit: RFC 3339 in UTC with nine subsecond digits and an explicit
+    /// offset, e.g. `2024-05-01T02:00:00.123456789+00:00`.
+    ///
+    /// Unlike [`LocalTime::rfc_3339`], trailing zeros are kept, so console
+    /// logs and exported spans line up byte for byte.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::macros::datetime;
+    ///
+    /// let mut s = String::new();
+    /// LocalTime::otel()
+    ///     .format_datetime(datetime!(2024-05-01 10:00:00.5 +8), &mut s)
+    ///     .unwrap();
+    /// assert_eq!(s, "2024-05-01T02:00:00.500000000+00:00");
+    /// ```
+    pub fn otel() -> Self {
+        Self::with_timezone(
+            format_description!(
+                "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:9]\
+                 [offset_hour sign:mandatory]:[offset_minute]"
+            ),
+            (0, 0, 0),
+        )
+    }
+
+    /// Returns a formatter for [RFC 3339] timestamps with exactly three
+    /// subsecond digits, e.g. `2024-05-01T10:00:00.120+08:00`.
+    ///
+    /// Unlike [`LocalTime::rfc_3339`], trailing zeros are kept and UTC is
+    /// written as `+00:00`, so every timestamp has the same width.
+    ///
+    /// Timezone format: (tz_hours, tz_minutes, tz_seconds)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::macros::datetime;
+    ///
+    /// let mut s = String::new();
+    /// LocalTime::rfc_3339_millis((8, 0, 0))
+    ///     .format_datetime(datetime!(2024-05-01 02:00:00.12 UTC), &mut s)
+    ///     .unwrap();
+    /// assert_eq!(s, "2024-05-01T10:00:00.120+08:00");
+    /// ```
+    ///
+    /// [RFC 3339]: https://datatracker.ietf.org/doc/html/rfc3339
+    pub fn rfc_3339_millis(tz_hms: (i8, i8, i8)) -> Self {
+        Self::with_timezone(
+            format_description!(
+                "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]\
+                 [offset_hour sign:mandatory]:[offset_minute]"
+            ),
+            tz_hms,
+        )
+    }
+
+    /// Returns a formatter for [RFC 3339] timestamps without subseconds, e.g.
+    /// `2024-05-01T10:00:00+08:00`.
+    ///
+    /// Timezone format: (tz_hours, tz_minutes, tz_seconds)
+    ///
+    /// [RFC 3339]: https://datatracker.ietf.org/doc/html/rfc3339
+    pub fn rfc_3339_seconds(tz_hms: (i8, i8, i8)) -> Self {
+        Self::with_timezone(
+            format_description!(
+                "[year]-[month]-[day]T[hour]:[minute]:[second]\
+                 [offset_hour sign:mandatory]:[offset_minute]"
+            ),
+            tz_hms,
+        )
+    }
+
+    /// Returns a formatter for [RFC 3339] timestamps with a space instead of
+    /// the `T` and three subsecond digits, e.g.
+    /// `2024-05-01 10:00:00.123+08:00`, as PostgreSQL and many log corpora
+    /// write them.
+    ///
+    /// Timezone format: (tz_hours, tz_minutes, tz_seconds)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::macros::datetime;
+    ///
+    /// let mut s = String::new();
+    /// LocalTime::rfc_3339_space((8, 0, 0))
+    ///     .format_datetime(datetime!(2024-05-01 02:00:00.123 UTC), &mut s)
+    ///     .unwrap();
+    /// assert_eq!(s, "2024-05-01 10:00:00.123+08:00");
+    /// ```
+    ///
+    /// [RFC 3339]: https://datatracker.ietf.org/doc/html/rfc3339
+    pub fn rfc_3339_space(tz_hms: (i8, i8, i8)) -> Self {
+        Self::with_timezone(
+            format_description!(
+                "[year]-[month]-[day] [hour]:[minute]:[second].[subsecond digits:3]\
+                 [offset_hour sign:mandatory]:[offset_minute]"
+            ),
+            tz_hms,
+        )
+    }
+
+    /// Returns a formatter for a 12-hour clock with milliseconds, the period
+    /// and the offset, e.g. `2024-05-01 01:00:00.123 PM +08:00`.
+    ///
+    /// Timezone format: (tz_hours, tz_minutes, tz_seconds)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::macros::datetime;
+    ///
+    /// let mut s = String::new();
+    /// LocalTime::twelve_hour((8, 0, 0))
+    ///     .format_datetime(datetime!(2024-05-01 05:00:00.123 UTC), &mut s)
+    ///     .unwrap();
+    /// assert_eq!(s, "2024-05-01 01:00:00.123 PM +08:00");
+    /// ```
+    pub fn twelve_hour(tz_hms: (i8, i8, i8)) -> Self {
+        Self::with_timezone(
+            format_description!(
+                "[year]-[month]-[day] [hour repr:12]:[minute]:[second].[subsecond digits:3] \
+                 [period] [offset_hour sign:mandatory]:[offset_minute]"
+            ),
+            tz_hms,
+        )
     }
 }
 
 // #[cfg(feature = "local-time")]
+#[cfg(feature = "std")]
 impl<F: Formattable> LocalTime<F> {
     /// Returns a formatter that formats the current [local time] using the
     /// [`time` crate] with the provided provided format. The format may be any
@@ -74,7 +505,7 @@ impl<F: Formattable> LocalTime<F> {
     /// Using the [`format_description!`] macro:
     ///
     /// ```
-    /// use tracing_subscriber::fmt::{self, time::LocalTime};
+    /// use clia_local_time::LocalTime;
     /// use time::macros::format_description;
     ///
     /// let timer = LocalTime::new(format_description!("[hour]:[minute]:[second]"));
@@ -86,7 +517,7 @@ impl<F: Formattable> LocalTime<F> {
     /// Using [`time::format_description::parse`]:
     ///
     /// ```
-    /// use tracing_subscriber::fmt::{self, time::LocalTime};
+    /// use clia_local_time::LocalTime;
     ///
     /// let time_format = time::format_description::parse("[hour]:[minute]:[second]")
     ///     .expect("format string should be valid!");
@@ -103,7 +534,7 @@ impl<F: Formattable> LocalTime<F> {
     /// [`LocalTime::rfc_3339`]):
     ///
     /// ```
-    /// use tracing_subscriber::fmt::{self, time::LocalTime};
+    /// use clia_local_time::LocalTime;
     ///
     /// let timer = LocalTime::new(time::format_description::well_known::Rfc3339);
     /// let collector = tracing_subscriber::fmt()
@@ -118,42 +549,362 @@ impl<F: Formattable> LocalTime<F> {
     /// [`format_description!`]: https://docs.rs/time/0.3/time/macros/macro.format_description.html
     /// [`time::format_description::parse`]: https://docs.rs/time/0.3/time/format_description/fn.parse.html
     /// [`time` book]: https://time-rs.github.io/book/api/format-description.html
-    pub fn new(format: F) -> Self {
-        Self {
-            format,
-            tz_hours: 0,
-            tz_minutes: 0,
-            tz_seconds: 0,
-        }
+    pub const fn new(format: F) -> Self {
+        Self::with_timezone(format, (0, 0, 0))
     }
 
     /// New with a format and timezone setting.
-    /// 
+    ///
     /// Timezone format: (tz_hours, tz_minutes, tz_seconds)
-    /// 
+    ///
+    /// The offset is validated here once; an out-of-range timezone falls back
+    /// to UTC.
+    ///
     /// # Examples:
-    /// 
-    /// ```
+    ///
+    /// ```text
     ///     (8, 0, 0)
     ///     (-2, 30, 0)
     /// ```
-    /// 
-    pub fn with_timezone(format: F, tz_hms: (i8, i8, i8)) -> Self {
+    ///
+    /// This is a `const fn`, so a timer can be built in a `static` and be
+    /// ready before `main` runs:
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::format_description::BorrowedFormatItem;
+    /// use time::macros::format_description;
+    ///
+    /// static TIMER: LocalTime<&[BorrowedFormatItem<'_>]> =
+    ///     LocalTime::with_timezone(format_description!("[hour]:[minute]:[second]"), (8, 0, 0));
+    ///
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(&TIMER);
+    /// # drop(collector);
+    /// ```
+    pub const fn with_timezone(format: F, tz_hms: (i8, i8, i8)) -> Self {
+        let offset = match UtcOffset::from_hms(tz_hms.0, tz_hms.1, tz_hms.2) {
+            Ok(offset) => offset,
+            Err(_) => UtcOffset::UTC,
+        };
+        Self::with_provider(format, FixedOffset(offset))
+    }
+
+    /// New with a format and an offset in fractional hours, e.g. `5.5` for
+    /// India or `5.75` for Nepal, rounded to the nearest minute.
+    ///
+    /// Like [`with_timezone`](LocalTime::with_timezone), an out-of-range or
+    /// non-finite offset falls back to UTC.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::macros::{datetime, format_description};
+    ///
+    /// let timer = LocalTime::with_offset_hours_f32(format_description!("[hour]:[minute]"), 5.75);
+    /// let mut s = String::new();
+    /// timer.format_datetime(datetime!(2024-05-01 02:00 UTC), &mut s).unwrap();
+    /// assert_eq!(s, "07:45");
+    /// ```
+    pub fn with_offset_hours_f32(format: F, hours: f32) -> Self {
+        let minutes = (f64::from(hours) * 60.0).round();
+        // Keeps the seconds from overflowing; `from_whole_seconds` rejects
+        // the rest of the out-of-range offsets.
+        let offset = if minutes.abs() < 26.0 * 60.0 {
+            UtcOffset::from_whole_seconds(minutes as i32 * 60).unwrap_or(UtcOffset::UTC)
+        } else {
+            UtcOffset::UTC
+        };
+        Self::with_provider(format, FixedOffset(offset))
+    }
+
+    /// New with a format and an offset east of UTC given as a duration, e.g.
+    /// `Duration::hours(8)`, truncated to whole seconds.
+    ///
+    /// Like [`with_timezone`](LocalTime::with_timezone), an out-of-range
+    /// offset falls back to UTC.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::macros::{datetime, format_description};
+    /// use time::Duration;
+    ///
+    /// let timer = LocalTime::with_offset_duration(
+    ///     format_description!("[hour]:[minute]"),
+    ///     Duration::hours(-3) - Duration::minutes(30),
+    /// );
+    /// let mut s = String::new();
+    /// timer.format_datetime(datetime!(2024-05-01 02:00 UTC), &mut s).unwrap();
+    /// assert_eq!(s, "22:30");
+    /// ```
+    pub fn with_offset_duration(format: F, offset: time::Duration) -> Self {
+        let offset = i32::try_from(offset.whole_seconds())
+            .ok()
+            .and_then(|seconds| UtcOffset::from_whole_seconds(seconds).ok())
+            .unwrap_or(UtcOffset::UTC);
+        Self::with_provider(format, FixedOffset(offset))
+    }
+
+    /// New with a format and an unsigned offset, west of UTC if `negative`,
+    /// for config layers producing a [`std::time::Duration`]. See
+    /// [`with_offset_duration`](LocalTime::with_offset_duration).
+    pub fn with_offset_std_duration(
+        format: F,
+        offset: std::time::Duration,
+        negative: bool,
+    ) -> Self {
+        let offset = time::Duration::try_from(offset).unwrap_or(time::Duration::MAX);
+        let offset = if negative { -offset } else { offset };
+        Self::with_offset_duration(format, offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<F: Formattable, P: OffsetProvider> LocalTime<F, P> {
+    /// Returns a formatter whose offset is chosen by `offset` for every
+    /// event.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::{offset::SystemOffset, LocalTime};
+    /// use time::format_description::well_known::Rfc3339;
+    ///
+    /// let timer = LocalTime::with_provider(Rfc3339, SystemOffset::new());
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(timer);
+    /// # drop(collector);
+    /// ```
+    pub const fn with_provider(format: F, offset: P) -> Self {
         Self {
             format,
-            tz_hours: tz_hms.0,
-            tz_minutes: tz_hms.1,
-            tz_seconds: tz_hms.2,
+            offset,
+            clock: SystemClock::new(),
+            prefix: String::new(),
+            suffix: String::new(),
+            width: None,
+            skew: time::Duration::ZERO,
+            scale: TimeScale::Utc,
+            source_tag: false,
+            ladder: None,
+            monotonic: None,
+            fast_rfc3339: false,
+            auto_offset: false,
+            on_error: None,
+            stats: None,
+            round_to: None,
+            repeats: None,
+            ansi_style: None,
+            numeric_utc: false,
+            decimal_comma: false,
         }
     }
 }
 
-// #[cfg(feature = "local-time")]
-impl<F> FormatTime for LocalTime<F>
-where
-    F: Formattable,
-{
-    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+#[cfg(feature = "std")]
+impl<F, P, C> LocalTime<F, P, C> {
+    /// Wraps the emitted timestamp in `prefix` and `suffix`, e.g. `("[", "]")`
+    /// for `[2024-05-01T10:00:00+08:00]` or `("ts=", "")` for a logfmt-style
+    /// key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    ///
+    /// let timer = LocalTime::rfc_3339().decorated("[", "]");
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(timer);
+    /// # drop(collector);
+    /// ```
+    pub fn decorated(self, prefix: impl Into<String>, suffix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            suffix: suffix.into(),
+            ..self
+        }
+    }
+
+    /// Pads the formatted timestamp with spaces, or truncates it, to exactly
+    /// `width` characters, so console columns stay aligned even when the
+    /// subsecond precision of the format varies. Any decoration is written
+    /// around the padded timestamp.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    ///
+    /// let timer = LocalTime::rfc_3339().with_width(35);
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(timer);
+    /// # drop(collector);
+    /// ```
+    pub fn with_width(self, width: usize) -> Self {
+        Self {
+            width: Some(width),
+            ..self
+        }
+    }
+
+    /// Appends a one-character marker naming the clock that produced each
+    /// timestamp, e.g. `2024-05-01T10:00:00+08:00 W`, so post-hoc analysis
+    /// knows which clock a line came from. See [`ClockKind`] for the markers.
+    pub fn with_source_tag(self) -> Self {
+        Self {
+            source_tag: true,
+            ..self
+        }
+    }
+
+    /// Adds `skew`, which may be negative, to every timestamp read from the
+    /// clock, e.g. to align logs from a device whose clock is known to be off,
+    /// or to test time-sensitive alerting.
+    ///
+    /// Timestamps passed to [`format_datetime`](LocalTime::format_datetime)
+    /// are not adjusted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::{clock::Fixed, LocalTime};
+    /// use time::{macros::datetime, Duration};
+    ///
+    /// let timer = LocalTime::rfc_3339()
+    ///     .with_clock(Fixed(datetime!(2024-05-01 10:00 UTC)))
+    ///     .with_skew(Duration::seconds(-90));
+    /// assert_eq!(timer.now(), datetime!(2024-05-01 09:58:30 UTC));
+    /// ```
+    pub fn with_skew(self, skew: time::Duration) -> Self {
+        Self { skew, ..self }
+    }
+
+    /// Reads the time from `clock` instead of the system clock.
+    ///
+    /// [`with_degradation`](LocalTime::with_degradation) always reads the
+    /// system clocks, so it supersedes this.
+    pub fn with_clock<C2: ClockSource>(self, clock: C2) -> LocalTime<F, P, C2> {
+        LocalTime {
+            format: self.format,
+            offset: self.offset,
+            clock,
+            prefix: self.prefix,
+            suffix: self.suffix,
+            width: self.width,
+            skew: self.skew,
+            scale: self.scale,
+            source_tag: self.source_tag,
+            ladder: self.ladder,
+            monotonic: self.monotonic,
+            fast_rfc3339: self.fast_rfc3339,
+            auto_offset: self.auto_offset,
+            on_error: self.on_error,
+            stats: self.stats,
+            round_to: self.round_to,
+            repeats: self.repeats,
+            ansi_style: self.ansi_style,
+            numeric_utc: self.numeric_utc,
+            decimal_comma: self.decimal_comma,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<F, P> LocalTime<F, P> {
+    /// Reads the time from a cheaper, coarse-grained clock, trading 1-4ms of
+    /// accuracy for a faster clock read.
+    ///
+    /// This uses `CLOCK_REALTIME_COARSE` on Linux when the `coarse` feature is
+    /// enabled, and the normal system clock everywhere else.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    ///
+    /// let timer = LocalTime::rfc_3339().coarse();
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(timer);
+    /// # drop(collector);
+    /// ```
+    pub fn coarse(self) -> Self {
+        Self {
+            clock: SystemClock::coarse(),
+            ..self
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<F, P: OffsetProvider, C: ClockSource> LocalTime<F, P, C> {
+    /// Returns the current time in the configured offset, read from the same
+    /// clock as logged timestamps, e.g. for file names, metrics or audit
+    /// records that should agree with the logs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    ///
+    /// let timer = LocalTime::w3c((8, 0, 0));
+    /// let now = timer.now();
+    /// assert_eq!(now.offset().whole_hours(), 8);
+    /// ```
+    pub fn now(&self) -> OffsetDateTime {
+        self.localize(self.clock.now_utc())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<F, P: OffsetProvider, C> LocalTime<F, P, C> {
+    /// Applies the skew, time scale and monotonicity guard to an instant read from a
+    /// clock, and converts it to the configured offset.
+    fn localize(&self, utc: OffsetDateTime) -> OffsetDateTime {
+        let mut now = utc + self.skew + self.scale.offset_from_utc(utc);
+        if let Some(monotonic) = &self.monotonic {
+            now = monotonic.clamp(now);
+        }
+        now.to_offset(self.offset.offset_at(now))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+impl<F> LocalTime<F> {
+    /// The configured offset, for checking what constructors validated.
+    fn offset(&self) -> UtcOffset {
+        self.offset.0
+    }
+}
+
+#[cfg(feature = "std")]
+impl<F: Formattable, P: OffsetProvider, C: ClockSource> LocalTime<F, P, C> {
+    /// Renders `dt` exactly as this timer renders the current time: converted
+    /// to the configured offset, in the configured format and decoration.
+    ///
+    /// This is useful for stored timestamps, e.g. when replaying buffered
+    /// events.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::macros::datetime;
+    ///
+    /// let timer = LocalTime::w3c((8, 0, 0)).decorated("[", "]");
+    /// let mut s = String::new();
+    /// timer.format_datetime(datetime!(2024-05-01 02:00 UTC), &mut s).unwrap();
+    /// assert_eq!(s, "[2024-05-01 10:00:00]");
+    /// ```
+    pub fn format_datetime(&self, dt: OffsetDateTime, w: &mut impl fmt::Write) -> fmt::Result {
+        self.format_decorated(dt.to_offset(self.offset.offset_at(dt)), w)
+    }
+
+    /// Writes the current time, as every supported version of
+    /// tracing-subscriber's `FormatTime` does.
+    fn write_now(&self, w: &mut impl fmt::Write) -> fmt::Result {
         //
         //
         //
@@ -163,14 +914,124 @@ where
         //
 
         // let now = OffsetDateTime::now_local().map_err(|_| fmt::Error)?;
-        let offset = UtcOffset::from_hms(self.tz_hours, self.tz_minutes, self.tz_seconds)
-            .unwrap_or(UtcOffset::UTC);
-        let now = OffsetDateTime::now_utc().to_offset(offset);
-        format_datetime(now, w, &self.format)
+        let Some(ladder) = &self.ladder else {
+            return self.format_decorated(self.now(), w);
+        };
+        self.format_reading(ladder.read(), w)
+    }
+
+    /// Writes what the degradation ladder read, localized like any other
+    /// reading.
+    fn format_reading(&self, reading: Reading, w: &mut impl fmt::Write) -> fmt::Result {
+        match reading {
+            Reading::Instant(now, rung) => self.format_tagged(self.localize(now), rung.kind(), w),
+            Reading::Sequence(n) => {
+                write!(w, "{}#{}", self.prefix, n)?;
+                if self.source_tag {
+                    write!(w, " {}", ClockKind::Sequence.marker())?;
+                }
+                w.write_str(&self.suffix)
+            }
+        }
+    }
+
+    fn format_decorated(&self, now: OffsetDateTime, w: &mut impl fmt::Write) -> fmt::Result {
+        self.format_tagged(now, self.clock.kind(), w)
+    }
+
+    /// Writes the bare timestamp, without decoration.
+    fn format_timestamp(&self, now: OffsetDateTime, w: &mut impl fmt::Write) -> fmt::Result {
+        let now = self.round_down(now);
+        let numeric_utc = self.numeric_utc && now.offset().is_utc();
+        if !numeric_utc && !self.decimal_comma {
+            return self.format_rounded(now, w);
+        }
+        let mut s = String::new();
+        self.format_rounded(now, &mut s)?;
+        if self.decimal_comma {
+            decimal_comma::replace_separator(&mut s, b'.', ",");
+        }
+        if numeric_utc {
+            numeric_utc::write_numeric(&s, w)
+        } else {
+            w.write_str(&s)
+        }
+    }
+
+    /// Writes the bare timestamp of `now`, already rounded.
+    fn format_rounded(&self, now: OffsetDateTime, w: &mut impl fmt::Write) -> fmt::Result {
+        // The specialized writer fails before writing anything, and the
+        // generic path reports why.
+        let formatted = if self.fast_rfc3339 && rfc3339::write(now, w).is_ok() {
+            Ok(())
+        } else if self.on_error.is_none() {
+            try_format_datetime(now, w, &self.format)
+        } else {
+            let mut s = String::new();
+            let formatted = try_format_datetime(now, &mut s, &self.format);
+            if formatted.is_ok() {
+                w.write_str(&s)?;
+            }
+            formatted
+        };
+        if let Some(stats) = &self.stats {
+            stats.record(formatted.is_ok());
+        }
+        if let Err(error) = formatted {
+            let Some(on_error) = &self.on_error else {
+                return Err(fmt::Error);
+            };
+            on_error.call(&error, now, w)?;
+            if let Some(stats) = &self.stats {
+                stats.record_fallback();
+            }
+        }
+        if self.auto_offset {
+            write_offset(now.offset(), w)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the bare timestamp, followed by the repeat counter if it
+    /// repeats the last one.
+    fn format_counted(&self, now: OffsetDateTime, w: &mut impl fmt::Write) -> fmt::Result {
+        let Some(repeats) = &self.repeats else {
+            return self.format_timestamp(now, w);
+        };
+        let mut s = String::new();
+        self.format_timestamp(now, &mut s)?;
+        w.write_str(&s)?;
+        match repeats.record(&s) {
+            1 => Ok(()),
+            n => write!(w, "#{}", n),
+        }
+    }
+
+    fn format_tagged(
+        &self,
+        now: OffsetDateTime,
+        source: ClockKind,
+        w: &mut impl fmt::Write,
+    ) -> fmt::Result {
+        w.write_str(&self.prefix)?;
+        match self.width {
+            None => self.format_counted(now, w)?,
+            Some(width) => {
+                let mut s = String::new();
+                self.format_counted(now, &mut s)?;
+                let end = s.char_indices().nth(width).map_or(s.len(), |(i, _)| i);
+                write!(w, "{:<width$}", &s[..end])?;
+            }
+        }
+        if self.source_tag {
+            write!(w, " {}", source.marker())?;
+        }
+        w.write_str(&self.suffix)
     }
 }
 
 // #[cfg(feature = "local-time")]
+#[cfg(feature = "std")]
 impl<F> Default for LocalTime<F>
 where
     F: Formattable + Default,
@@ -180,15 +1041,55 @@ where
     }
 }
 
+/// Returns the current time in `offset`.
+///
+/// # Examples
+///
+/// ```
+/// use time::macros::offset;
+///
+/// let now = clia_local_time::now_with_offset(offset!(+8));
+/// assert_eq!(now.offset(), offset!(+8));
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn now_with_offset(offset: UtcOffset) -> OffsetDateTime {
+    OffsetDateTime::now_utc().to_offset(offset)
+}
+
+/// Formats an arbitrary [`Formattable`] through [`WriteAdaptor`].
+///
+/// Formats the crate renders itself, such as [`LocalTime::rfc_3339`], write
+/// straight into `into` instead.
+#[cfg(feature = "std")]
 fn format_datetime(
     now: OffsetDateTime,
-    into: &mut Writer<'_>,
+    into: &mut impl fmt::Write,
     fmt: &impl Formattable,
 ) -> fmt::Result {
+    try_format_datetime(now, into, fmt).map_err(|_| fmt::Error)
+}
+
+#[cfg(feature = "std")]
+fn try_format_datetime(
+    now: OffsetDateTime,
+    into: &mut impl fmt::Write,
+    fmt: &impl Formattable,
+) -> Result<(), time::error::Format> {
     let mut into = WriteAdaptor::new(into);
-    now.format_into(&mut into, fmt)
-        .map_err(|_| fmt::Error)
-        .map(|_| ())
+    now.format_into(&mut into, fmt).map(|_| ())
+}
+
+/// Writes ` +08:00`, or ` +05:53:28` if the offset has seconds.
+#[cfg(feature = "std")]
+fn write_offset(offset: UtcOffset, w: &mut impl fmt::Write) -> fmt::Result {
+    let (hours, minutes, seconds) = offset.as_hms();
+    let sign = if offset.is_negative() { '-' } else { '+' };
+    write!(w, " {}{:02}:{:02}", sign, hours.abs(), minutes.abs())?;
+    if seconds != 0 {
+        write!(w, ":{:02}", seconds.abs())?;
+    }
+    Ok(())
 }
 
 /// A bridge between `fmt::Write` and `io::Write`.
@@ -198,49 +1099,203 @@ fn format_datetime(
 /// `tracing-subscriber`'s `FormatEvent`/`FormatTime` traits expect a
 /// `fmt::Write` implementation, while `serde_json::Serializer` and `time`'s
 /// `format_into` methods expect an `io::Write`.
+///
+/// A multi-byte character split across two writes is buffered until its
+/// remaining bytes arrive, rather than rejected as invalid UTF-8.
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::WriteAdaptor;
+/// use time::{macros::datetime, format_description::well_known::Rfc3339};
+///
+/// let mut s = String::new();
+/// datetime!(2024-05-01 10:00 UTC)
+///     .format_into(&mut WriteAdaptor::new(&mut s), &Rfc3339)
+///     .unwrap();
+/// assert_eq!(s, "2024-05-01T10:00:00Z");
+/// ```
 // #[cfg(any(feature = "json", feature = "time"))]
-pub(crate) struct WriteAdaptor<'a> {
-    fmt_write: &'a mut dyn fmt::Write,
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub struct WriteAdaptor<W> {
+    fmt_write: W,
+    /// The leading bytes of an incomplete character.
+    pending: [u8; 4],
+    pending_len: usize,
 }
 
 // === impl WriteAdaptor ===
 
 // #[cfg(any(feature = "json", feature = "time"))]
-impl<'a> WriteAdaptor<'a> {
-    pub fn new(fmt_write: &'a mut dyn fmt::Write) -> Self {
-        Self { fmt_write }
+#[cfg(feature = "std")]
+impl<W: fmt::Write> WriteAdaptor<W> {
+    /// Returns an `io::Write` that writes into `fmt_write`.
+    pub fn new(fmt_write: W) -> Self {
+        Self {
+            fmt_write,
+            pending: [0; 4],
+            pending_len: 0,
+        }
     }
-}
-// #[cfg(any(feature = "json", feature = "time"))]
-impl<'a> io::Write for WriteAdaptor<'a> {
-    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+
+    /// Returns the wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.fmt_write
+    }
+
+    fn write_utf8(&mut self, buf: &[u8]) -> io::Result<()> {
         let s =
             std::str::from_utf8(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        self.fmt_write.write_str(s).map_err(io::Error::other)
+    }
 
-        self.fmt_write
-            .write_str(s)
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+    /// Completes the buffered character from the front of `buf`, returning
+    /// the bytes that follow it.
+    fn complete_pending<'b>(&mut self, buf: &'b [u8]) -> io::Result<&'b [u8]> {
+        let width = match self.pending[0] {
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            _ => 4,
+        };
+        let take = (width - self.pending_len).min(buf.len());
+        self.pending[self.pending_len..self.pending_len + take].copy_from_slice(&buf[..take]);
+        self.pending_len += take;
+        if self.pending_len == width {
+            let pending = self.pending;
+            self.pending_len = 0;
+            self.write_utf8(&pending[..width])?;
+        }
+        Ok(&buf[take..])
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> WriteAdaptor<&'a mut dyn fmt::Write> {
+    /// Returns an `io::Write` that writes into a `fmt::Write` trait object.
+    pub fn from_dyn(fmt_write: &'a mut dyn fmt::Write) -> Self {
+        Self::new(fmt_write)
+    }
+}
+// #[cfg(any(feature = "json", feature = "time"))]
+#[cfg(feature = "std")]
+impl<W: fmt::Write> io::Write for WriteAdaptor<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut rest = buf;
+        if self.pending_len > 0 {
+            rest = self.complete_pending(rest)?;
+        }
+        match std::str::from_utf8(rest) {
+            Ok(s) => self.fmt_write.write_str(s).map_err(io::Error::other)?,
+            Err(e) => {
+                let (valid, tail) = rest.split_at(e.valid_up_to());
+                self.write_utf8(valid)?;
+                if e.error_len().is_some() {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, e));
+                }
+                // At most three bytes of a character that isn't complete yet.
+                self.pending[..tail.len()].copy_from_slice(tail);
+                self.pending_len = tail.len();
+            }
+        }
+        Ok(buf.len())
+    }
 
-        Ok(s.as_bytes().len())
+    /// Writes all of `buf`, buffering a trailing incomplete character rather
+    /// than failing on it.
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        let mut buf = buf;
+        while !buf.is_empty() {
+            let n = self.write(buf)?;
+            buf = &buf[n..];
+        }
+        Ok(())
     }
 
+    /// Fails if a character is still incomplete.
     fn flush(&mut self) -> io::Result<()> {
+        if self.pending_len > 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "incomplete UTF-8 sequence",
+            ));
+        }
         Ok(())
     }
 }
 
 // #[cfg(any(feature = "json", feature = "time"))]
-impl<'a> fmt::Debug for WriteAdaptor<'a> {
+#[cfg(feature = "std")]
+impl<W> fmt::Debug for WriteAdaptor<W> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.pad("WriteAdaptor { .. }")
     }
 }
 // === blanket impls ===
 
-#[cfg(test)]
+/// Rendering helpers shared by the tests of every module.
+#[cfg(all(test, feature = "std"))]
+pub(crate) mod testing {
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    use time::{formatting::Formattable, OffsetDateTime};
+    use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
+
+    use crate::{clock::ClockSource, offset::OffsetProvider, LocalTime};
+
+    /// A writer collecting the output of a layer, shared by its clones.
+    #[derive(Clone, Default)]
+    pub(crate) struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl Capture {
+        /// The bytes written so far.
+        pub(crate) fn bytes(&self) -> Vec<u8> {
+            self.0.lock().unwrap().clone()
+        }
+
+        /// The text written so far.
+        pub(crate) fn output(&self) -> String {
+            String::from_utf8(self.bytes()).unwrap()
+        }
+    }
+
+    impl io::Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Renders `dt` as `timer` would render the current time.
+    pub(crate) fn render<F, P, C>(timer: &LocalTime<F, P, C>, dt: OffsetDateTime) -> String
+    where
+        F: Formattable,
+        P: OffsetProvider,
+        C: ClockSource,
+    {
+        let mut s = String::new();
+        timer.format_datetime(dt, &mut s).unwrap();
+        s
+    }
+
+    /// Renders the current time with `timer`'s `FormatTime` impl.
+    pub(crate) fn render_now(timer: &impl FormatTime) -> String {
+        let mut s = String::new();
+        timer.format_time(&mut Writer::new(&mut s)).unwrap();
+        s
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
 mod tests {
-    use super::LocalTime;
-    use time::macros::format_description;
+    use super::{format_datetime, testing::render, LocalTime, WriteAdaptor};
+    use std::io::Write;
+    use time::{macros::format_description, Duration, OffsetDateTime, UtcOffset};
+    use tracing_subscriber::fmt::format::Writer;
 
     #[test]
     fn test_init_tracing() {
@@ -252,4 +1307,93 @@ mod tests {
         );
         tracing_subscriber::fmt().with_timer(timer).init();
     }
+
+    #[test]
+    fn test_w3c() {
+        let timer = LocalTime::w3c((8, 0, 0));
+        let now = OffsetDateTime::UNIX_EPOCH.to_offset(UtcOffset::from_hms(8, 0, 0).unwrap());
+        let mut s = String::new();
+        format_datetime(now, &mut Writer::new(&mut s), &timer.format).unwrap();
+        assert_eq!(s, "1970-01-01 08:00:00");
+    }
+
+    #[test]
+    fn test_offset_hours_f32() {
+        let offset =
+            |hours| LocalTime::with_offset_hours_f32(format_description!(""), hours).offset();
+        assert_eq!(offset(5.5), UtcOffset::from_hms(5, 30, 0).unwrap());
+        assert_eq!(offset(-3.5), UtcOffset::from_hms(-3, -30, 0).unwrap());
+        assert_eq!(offset(5.749), UtcOffset::from_hms(5, 45, 0).unwrap());
+        assert_eq!(offset(30.0), UtcOffset::UTC);
+        assert_eq!(offset(f32::NAN), UtcOffset::UTC);
+    }
+
+    #[test]
+    fn test_offset_duration() {
+        let offset = |d| LocalTime::with_offset_duration(format_description!(""), d).offset();
+        assert_eq!(
+            offset(Duration::hours(8)),
+            UtcOffset::from_hms(8, 0, 0).unwrap()
+        );
+        assert_eq!(
+            offset(Duration::seconds_f64(-9000.9)),
+            UtcOffset::from_hms(-2, -30, 0).unwrap()
+        );
+        assert_eq!(offset(Duration::hours(26)), UtcOffset::UTC);
+        assert_eq!(offset(Duration::MIN), UtcOffset::UTC);
+
+        let offset = |d, negative| {
+            LocalTime::with_offset_std_duration(format_description!(""), d, negative).offset()
+        };
+        let d = std::time::Duration::from_secs(5 * 3600 + 45 * 60);
+        assert_eq!(offset(d, false), UtcOffset::from_hms(5, 45, 0).unwrap());
+        assert_eq!(offset(d, true), UtcOffset::from_hms(-5, -45, 0).unwrap());
+        assert_eq!(offset(std::time::Duration::MAX, true), UtcOffset::UTC);
+    }
+
+    #[test]
+    fn test_decorated() {
+        let timer = LocalTime::w3c((0, 0, 0)).decorated("ts=[", "] ");
+        assert_eq!(
+            render(&timer, OffsetDateTime::UNIX_EPOCH),
+            "ts=[1970-01-01 00:00:00] "
+        );
+    }
+
+    #[test]
+    fn test_width() {
+        let timer = |width| {
+            LocalTime::w3c((0, 0, 0))
+                .with_width(width)
+                .decorated("[", "]")
+        };
+        let epoch = OffsetDateTime::UNIX_EPOCH;
+        assert_eq!(render(&timer(21), epoch), "[1970-01-01 00:00:00  ]");
+        assert_eq!(render(&timer(10), epoch), "[1970-01-01]");
+    }
+
+    #[test]
+    fn test_source_tag() {
+        let timer = LocalTime::w3c((0, 0, 0)).with_source_tag();
+        assert_eq!(
+            render(&timer, OffsetDateTime::UNIX_EPOCH),
+            "1970-01-01 00:00:00 W"
+        );
+    }
+
+    #[test]
+    fn test_write_adaptor_split_utf8() {
+        let mut s = String::new();
+        let mut w = WriteAdaptor::new(&mut s);
+        let bytes = "mars, März".as_bytes();
+        // Split inside the two-byte 'ä', then feed the rest byte by byte.
+        w.write_all(&bytes[..8]).unwrap();
+        assert!(w.flush().is_err());
+        for b in &bytes[8..] {
+            w.write_all(&[*b]).unwrap();
+        }
+        w.flush().unwrap();
+        assert!(w.write(&[0xff]).is_err());
+        assert_eq!(s, "mars, März");
+    }
 }
@@ -0,0 +1,120 @@
+//! Caching the local offset `time` detects, for programs that can detect it
+//! before spawning threads.
+//!
+//! On Unix, the C library calls `time` detects the local offset with read
+//! the environment, and race with `setenv` in other threads. Recent `time`
+//! releases no longer refuse to detect it once other threads exist, so
+//! [`init_local_offset`] checks itself, makes the one call at startup, and
+//! keeps the result for every timer built afterwards.
+//!
+//! The check only runs where it is needed. macOS, illumos and NetBSD have a
+//! thread-safe environment, and the platform APIs used elsewhere, e.g. on
+//! Windows, don't read it, so detection works from any thread there.
+
+use std::sync::OnceLock;
+
+use time::{formatting::Formattable, OffsetDateTime, UtcOffset};
+
+use crate::{offset::FixedOffset, LocalTime, TimezoneError};
+
+static CACHED: OnceLock<UtcOffset> = OnceLock::new();
+
+/// Detects the local offset with `OffsetDateTime::now_local` and caches it,
+/// returning the cached offset on later calls.
+///
+/// Call this at the start of `main`, before any thread is spawned. On Unix,
+/// detection fails with [`TimezoneError::IndeterminateSystemOffset`] once the
+/// process has more than one thread, or if its threads can't be counted, so
+/// a late call is an error rather than a data race. On platforms with a
+/// thread-safe environment there is no thread check, and none is needed; see
+/// the [module docs](self). Failed calls are not cached and may be retried.
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::LocalTime;
+///
+/// // Before spawning threads; falls back to UTC if detection fails.
+/// let _ = clia_local_time::init_local_offset();
+/// let collector = tracing_subscriber::fmt()
+///     .with_timer(LocalTime::cached_local(time::format_description::well_known::Rfc3339));
+/// # drop(collector);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "local-offset")))]
+pub fn init_local_offset() -> Result<UtcOffset, TimezoneError> {
+    if let Some(offset) = CACHED.get() {
+        return Ok(*offset);
+    }
+    if !crate::system::detection_is_sound() {
+        return Err(TimezoneError::IndeterminateSystemOffset);
+    }
+    let offset = OffsetDateTime::now_local()
+        .map_err(|_| TimezoneError::IndeterminateSystemOffset)?
+        .offset();
+    Ok(*CACHED.get_or_init(|| offset))
+}
+
+/// The offset cached by [`init_local_offset`], if it succeeded.
+#[cfg_attr(docsrs, doc(cfg(feature = "local-offset")))]
+pub fn local_offset() -> Option<UtcOffset> {
+    CACHED.get().copied()
+}
+
+// === impl LocalTime ===
+
+impl<F: Formattable> LocalTime<F> {
+    /// Returns a formatter that renders timestamps in the offset cached by
+    /// [`init_local_offset`], or in UTC if it wasn't called or failed.
+    ///
+    /// The offset is fixed, so it doesn't follow daylight saving transitions
+    /// after startup; use [`LocalTime::system`] for that.
+    #[cfg_attr(docsrs, doc(cfg(feature = "local-offset")))]
+    pub fn cached_local(format: F) -> Self {
+        let offset = local_offset().unwrap_or(UtcOffset::UTC);
+        LocalTime::with_provider(format, FixedOffset(offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{init_local_offset, local_offset};
+    use crate::{LocalTime, TimezoneError};
+    use time::{macros::format_description, UtcOffset};
+
+    #[test]
+    fn test_init_local_offset() {
+        // The test harness is multi-threaded, so detection may be refused.
+        match init_local_offset() {
+            Ok(offset) => assert_eq!(local_offset(), Some(offset)),
+            Err(e) => {
+                assert_eq!(e, TimezoneError::IndeterminateSystemOffset);
+                assert_eq!(local_offset(), None);
+            }
+        }
+        let timer = LocalTime::cached_local(format_description!("[hour]"));
+        assert_eq!(timer.offset(), local_offset().unwrap_or(UtcOffset::UTC));
+    }
+
+    #[cfg(all(
+        unix,
+        not(any(target_os = "macos", target_os = "illumos", target_os = "netbsd"))
+    ))]
+    #[test]
+    fn test_late_call_caches_nothing() {
+        let before = local_offset();
+        // Keep a second thread alive for the duration of the call.
+        let (stop, wait) = std::sync::mpsc::channel::<()>();
+        let thread = std::thread::spawn(move || wait.recv());
+        let result = init_local_offset();
+        drop(stop);
+        let _ = thread.join();
+        match before {
+            // An earlier test ran single-threaded and cached an offset.
+            Some(offset) => assert_eq!(result, Ok(offset)),
+            None => {
+                assert_eq!(result, Err(TimezoneError::IndeterminateSystemOffset));
+                assert_eq!(local_offset(), None);
+            }
+        }
+    }
+}
@@ -0,0 +1,562 @@
+//! Month and weekday names in languages other than English.
+//!
+//! The `time` crate always renders `[month repr:long]`, `[weekday]` and
+//! friends in English. [`LocalizedTime`] walks the format items itself and
+//! substitutes the names from a [`Locale`] table, delegating every other
+//! component to `time`. It can also render week numbers by a locale's
+//! [`WeekRule`].
+
+use std::fmt;
+
+use time::format_description::{Component, OwnedFormatItem};
+use time::{Date, Month, OffsetDateTime, Weekday};
+use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
+
+use crate::{LocalTime, WriteAdaptor};
+
+/// A language for month and weekday names.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Locale {
+    /// English, as rendered by the `time` crate itself.
+    #[default]
+    English,
+    /// German.
+    German,
+    /// French.
+    French,
+    /// Japanese.
+    Japanese,
+    /// Simplified Chinese.
+    Chinese,
+}
+
+struct Names {
+    months_long: [&'static str; 12],
+    months_short: [&'static str; 12],
+    /// Starting on Monday.
+    weekdays_long: [&'static str; 7],
+    /// Starting on Monday.
+    weekdays_short: [&'static str; 7],
+}
+
+static ENGLISH: Names = Names {
+    months_long: [
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ],
+    months_short: [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ],
+    weekdays_long: [
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+        "Sunday",
+    ],
+    weekdays_short: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+};
+
+static GERMAN: Names = Names {
+    months_long: [
+        "Januar",
+        "Februar",
+        "März",
+        "April",
+        "Mai",
+        "Juni",
+        "Juli",
+        "August",
+        "September",
+        "Oktober",
+        "November",
+        "Dezember",
+    ],
+    months_short: [
+        "Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.", "Sept.", "Okt.", "Nov.",
+        "Dez.",
+    ],
+    weekdays_long: [
+        "Montag",
+        "Dienstag",
+        "Mittwoch",
+        "Donnerstag",
+        "Freitag",
+        "Samstag",
+        "Sonntag",
+    ],
+    weekdays_short: ["Mo.", "Di.", "Mi.", "Do.", "Fr.", "Sa.", "So."],
+};
+
+static FRENCH: Names = Names {
+    months_long: [
+        "janvier",
+        "février",
+        "mars",
+        "avril",
+        "mai",
+        "juin",
+        "juillet",
+        "août",
+        "septembre",
+        "octobre",
+        "novembre",
+        "décembre",
+    ],
+    months_short: [
+        "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.", "nov.",
+        "déc.",
+    ],
+    weekdays_long: [
+        "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+    ],
+    weekdays_short: ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+};
+
+static JAPANESE: Names = Names {
+    months_long: [
+        "1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月",
+    ],
+    months_short: [
+        "1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月",
+    ],
+    weekdays_long: [
+        "月曜日",
+        "火曜日",
+        "水曜日",
+        "木曜日",
+        "金曜日",
+        "土曜日",
+        "日曜日",
+    ],
+    weekdays_short: ["月", "火", "水", "木", "金", "土", "日"],
+};
+
+static CHINESE: Names = Names {
+    months_long: [
+        "一月",
+        "二月",
+        "三月",
+        "四月",
+        "五月",
+        "六月",
+        "七月",
+        "八月",
+        "九月",
+        "十月",
+        "十一月",
+        "十二月",
+    ],
+    months_short: [
+        "1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月",
+    ],
+    weekdays_long: [
+        "星期一",
+        "星期二",
+        "星期三",
+        "星期四",
+        "星期五",
+        "星期六",
+        "星期日",
+    ],
+    weekdays_short: ["周一", "周二", "周三", "周四", "周五", "周六", "周日"],
+};
+
+// === impl Locale ===
+
+impl Locale {
+    fn names(self) -> &'static Names {
+        match self {
+            Locale::English => &ENGLISH,
+            Locale::German => &GERMAN,
+            Locale::French => &FRENCH,
+            Locale::Japanese => &JAPANESE,
+            Locale::Chinese => &CHINESE,
+        }
+    }
+
+    /// Returns the name of the month `dt` falls in.
+    pub fn month_name(self, dt: OffsetDateTime, short: bool) -> &'static str {
+        let names = self.names();
+        let table = if short {
+            &names.months_short
+        } else {
+            &names.months_long
+        };
+        table[usize::from(u8::from(dt.month())) - 1]
+    }
+
+    /// Returns the name of the weekday `dt` falls on.
+    pub fn weekday_name(self, dt: OffsetDateTime, short: bool) -> &'static str {
+        let names = self.names();
+        let table = if short {
+            &names.weekdays_short
+        } else {
+            &names.weekdays_long
+        };
+        table[weekday_index(dt.weekday())]
+    }
+}
+
+fn weekday_index(weekday: Weekday) -> usize {
+    usize::from(weekday.number_days_from_monday())
+}
+
+/// A convention for numbering the weeks of a year.
+///
+/// "Week 18" covers different days depending on the convention, so logs
+/// that render week numbers should use the one their readers expect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WeekRule {
+    /// ISO 8601: weeks start on Monday, and week 1 contains January 4th.
+    Iso,
+    /// United States: weeks start on Sunday, and week 1 contains January 1st.
+    Us,
+    /// Middle East: weeks start on Saturday, and week 1 contains January 1st.
+    MiddleEastern,
+}
+
+// === impl WeekRule ===
+
+impl WeekRule {
+    /// The day weeks start on.
+    pub fn first_day(self) -> Weekday {
+        match self {
+            WeekRule::Iso => Weekday::Monday,
+            WeekRule::Us => Weekday::Sunday,
+            WeekRule::MiddleEastern => Weekday::Saturday,
+        }
+    }
+
+    /// Returns the number of the week `date` falls in, starting at 1.
+    ///
+    /// ISO weeks around New Year may belong to the neighbouring year, e.g.
+    /// 2021-01-01 is in week 53; the other conventions always start week 1 on
+    /// January 1st.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::WeekRule;
+    /// use time::macros::date;
+    ///
+    /// // A Sunday.
+    /// let date = date!(2024-05-05);
+    /// assert_eq!(WeekRule::Iso.week_number(date), 18);
+    /// assert_eq!(WeekRule::Us.week_number(date), 19);
+    /// ```
+    pub fn week_number(self, date: Date) -> u8 {
+        if self == WeekRule::Iso {
+            return date.iso_week();
+        }
+        let first = self.first_day().number_days_from_monday();
+        let jan1 = Date::from_calendar_date(date.year(), Month::January, 1)
+            .map_or(0, |jan1| jan1.weekday().number_days_from_monday());
+        // How far into its week January 1st falls.
+        let lead = (jan1 + 7 - first) % 7;
+        ((date.ordinal() - 1 + u16::from(lead)) / 7 + 1) as u8
+    }
+}
+
+/// Formats the current [local time] like [`LocalTime`], rendering month and
+/// weekday names in a [`Locale`].
+///
+/// [local time]: https://docs.rs/time/0.3/time/struct.OffsetDateTime.html#method.now_local
+#[derive(Clone, Debug)]
+pub struct LocalizedTime {
+    inner: LocalTime<OwnedFormatItem>,
+    locale: Locale,
+    week_rule: Option<WeekRule>,
+}
+
+// === impl LocalizedTime ===
+
+impl LocalizedTime {
+    /// New with a format, timezone setting and locale.
+    ///
+    /// Timezone format: (tz_hours, tz_minutes, tz_seconds)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::{Locale, LocalizedTime};
+    /// use time::macros::format_description;
+    ///
+    /// let timer = LocalizedTime::new(
+    ///     format_description!("[weekday], [day]. [month repr:long] [year] [hour]:[minute]"),
+    ///     (1, 0, 0),
+    ///     Locale::German,
+    /// );
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(timer);
+    /// # drop(collector);
+    /// ```
+    pub fn new(format: impl Into<OwnedFormatItem>, tz_hms: (i8, i8, i8), locale: Locale) -> Self {
+        Self {
+            inner: LocalTime::with_timezone(format.into(), tz_hms),
+            locale,
+            week_rule: None,
+        }
+    }
+
+    /// Renders every week number component (`[week_number]` in any `repr`)
+    /// using `rule`, instead of the convention named in the format.
+    pub fn with_week_rule(self, rule: WeekRule) -> Self {
+        Self {
+            week_rule: Some(rule),
+            ..self
+        }
+    }
+
+    fn format_localized(&self, now: OffsetDateTime, w: &mut dyn fmt::Write) -> fmt::Result {
+        format_item(now, &self.inner.format, self, w)
+    }
+}
+
+impl FormatTime for LocalizedTime {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        let now = self.inner.now();
+        self.format_localized(now, w)
+    }
+}
+
+#[allow(deprecated)]
+fn format_item(
+    now: OffsetDateTime,
+    item: &OwnedFormatItem,
+    timer: &LocalizedTime,
+    w: &mut dyn fmt::Write,
+) -> fmt::Result {
+    use time::format_description::modifier::Padding;
+
+    let locale = timer.locale;
+    let name = match item {
+        OwnedFormatItem::Compound(items) => {
+            return items
+                .iter()
+                .try_for_each(|item| format_item(now, item, timer, w));
+        }
+        OwnedFormatItem::Optional(item) => return format_item(now, item, timer, w),
+        // Like `time`, only the first alternative is rendered, even if it
+        // fails.
+        OwnedFormatItem::First(items) => {
+            return match items.first() {
+                Some(item) => format_item(now, item, timer, w),
+                None => Ok(()),
+            };
+        }
+        OwnedFormatItem::Component(component) => {
+            let padding = match component {
+                Component::WeekNumberIso(m) => Some(m.padding),
+                Component::WeekNumberSunday(m) => Some(m.padding),
+                Component::WeekNumberMonday(m) => Some(m.padding),
+                Component::WeekNumber(m) => Some(m.padding),
+                _ => None,
+            };
+            if let (Some(rule), Some(padding)) = (timer.week_rule, padding) {
+                let week = rule.week_number(now.date());
+                return match padding {
+                    Padding::Space => write!(w, "{:2}", week),
+                    Padding::None => write!(w, "{}", week),
+                    _ => write!(w, "{:02}", week),
+                };
+            }
+            localized_name(now, component, locale)
+        }
+        _ => None,
+    };
+    match name {
+        Some(name) => w.write_str(name),
+        None => now
+            .format_into(&mut WriteAdaptor::new(w), item)
+            .map(|_| ())
+            .map_err(|_| fmt::Error),
+    }
+}
+
+/// The name `locale` gives the month or weekday `component` renders, if any.
+#[allow(deprecated)]
+fn localized_name(
+    now: OffsetDateTime,
+    component: &Component,
+    locale: Locale,
+) -> Option<&'static str> {
+    use time::format_description::modifier::{MonthRepr, WeekdayRepr};
+
+    match component {
+        Component::MonthLong(_) => Some(locale.month_name(now, false)),
+        Component::MonthShort(_) => Some(locale.month_name(now, true)),
+        Component::WeekdayLong(_) => Some(locale.weekday_name(now, false)),
+        Component::WeekdayShort(_) => Some(locale.weekday_name(now, true)),
+        Component::Month(m) if m.repr == MonthRepr::Long => Some(locale.month_name(now, false)),
+        Component::Month(m) if m.repr == MonthRepr::Short => Some(locale.month_name(now, true)),
+        Component::Weekday(m) if m.repr == WeekdayRepr::Long => {
+            Some(locale.weekday_name(now, false))
+        }
+        Component::Weekday(m) if m.repr == WeekdayRepr::Short => {
+            Some(locale.weekday_name(now, true))
+        }
+        _ => None,
+    }
+}
+
+// === impl LocalTime ===
+
+impl LocalTime<OwnedFormatItem> {
+    /// Returns a formatter for Chinese-style dates, e.g.
+    /// `2024年05月01日 10时00分00秒`, or with `subsecond_digits` up to `9`
+    /// decimals on the seconds, e.g. `10时00分00.123秒`.
+    ///
+    /// Timezone format: (tz_hours, tz_minutes, tz_seconds)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::macros::datetime;
+    ///
+    /// let mut s = String::new();
+    /// LocalTime::chinese_date((8, 0, 0), 0)
+    ///     .format_datetime(datetime!(2024-05-01 02:00 UTC), &mut s)
+    ///     .unwrap();
+    /// assert_eq!(s, "2024年05月01日 10时00分00秒");
+    /// ```
+    pub fn chinese_date(tz_hms: (i8, i8, i8), subsecond_digits: u8) -> Self {
+        let subsecond = match subsecond_digits.min(9) {
+            0 => String::new(),
+            n => format!(".[subsecond digits:{}]", n),
+        };
+        let format = time::format_description::parse_owned::<1>(&format!(
+            "[year]年[month]月[day]日 [hour]时[minute]分[second]{}秒",
+            subsecond
+        ))
+        .expect("format description should be valid");
+        LocalTime::with_timezone(format, tz_hms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Locale, LocalizedTime, WeekRule};
+    use time::macros::{date, datetime, format_description};
+
+    #[test]
+    fn test_localized_names() {
+        let dt = datetime!(2024-05-01 10:00:00 +08:00);
+        let render = |locale| {
+            let timer = LocalizedTime::new(
+                format_description!("[weekday repr:short] [day] [month repr:long] [year]"),
+                (8, 0, 0),
+                locale,
+            );
+            let mut s = String::new();
+            timer.format_localized(dt, &mut s).unwrap();
+            s
+        };
+        assert_eq!(render(Locale::English), "Wed 01 May 2024");
+        assert_eq!(render(Locale::German), "Mi. 01 Mai 2024");
+        assert_eq!(render(Locale::Chinese), "周三 01 五月 2024");
+    }
+
+    #[test]
+    fn test_week_rules() {
+        // 2022-01-01 is a Saturday.
+        let sat = date!(2022 - 01 - 01);
+        assert_eq!(WeekRule::Iso.week_number(sat), 52);
+        assert_eq!(WeekRule::Us.week_number(sat), 1);
+        assert_eq!(WeekRule::MiddleEastern.week_number(sat), 1);
+        let next = date!(2022 - 01 - 02);
+        assert_eq!(WeekRule::Us.week_number(next), 2);
+        assert_eq!(WeekRule::MiddleEastern.week_number(next), 1);
+
+        let timer = LocalizedTime::new(
+            format_description!("[year]-W[week_number]"),
+            (0, 0, 0),
+            Locale::English,
+        )
+        .with_week_rule(WeekRule::Us);
+        let mut s = String::new();
+        timer
+            .format_localized(datetime!(2022-01-02 00:00 UTC), &mut s)
+            .unwrap();
+        assert_eq!(s, "2022-W02");
+
+        for (format, expected) in [
+            ("[week_number padding:space]", " 2"),
+            ("[week_number padding:none]", "2"),
+        ] {
+            let timer = LocalizedTime::new(
+                time::format_description::parse_owned::<2>(format).unwrap(),
+                (0, 0, 0),
+                Locale::English,
+            )
+            .with_week_rule(WeekRule::Us);
+            let mut s = String::new();
+            timer
+                .format_localized(datetime!(2022-01-02 00:00 UTC), &mut s)
+                .unwrap();
+            assert_eq!(s, expected);
+        }
+    }
+
+    #[test]
+    fn test_first_alternative() {
+        let format = "[first [[month repr:long] [year]] [unix_timestamp]]";
+        let timer = LocalizedTime::new(
+            time::format_description::parse_owned::<2>(format).unwrap(),
+            (0, 0, 0),
+            Locale::German,
+        );
+        let mut s = String::new();
+        timer
+            .format_localized(datetime!(2024-05-01 00:00 UTC), &mut s)
+            .unwrap();
+        assert_eq!(s, "Mai 2024");
+
+        // A writer that only takes four bytes: `Mai 2024` doesn't fit, and
+        // `2024` isn't tried instead.
+        struct Short(String);
+        impl std::fmt::Write for Short {
+            fn write_str(&mut self, s: &str) -> std::fmt::Result {
+                if self.0.len() + s.len() > 4 {
+                    return Err(std::fmt::Error);
+                }
+                self.0.push_str(s);
+                Ok(())
+            }
+        }
+        let format = "[first [[month repr:long] [year]] [year]]";
+        let timer = LocalizedTime::new(
+            time::format_description::parse_owned::<2>(format).unwrap(),
+            (0, 0, 0),
+            Locale::German,
+        );
+        let mut short = Short(String::new());
+        assert!(timer
+            .format_localized(datetime!(2024-05-01 00:00 UTC), &mut short)
+            .is_err());
+        assert_eq!(short.0, "Mai ");
+    }
+
+    #[test]
+    fn test_chinese_date() {
+        let timer = crate::LocalTime::chinese_date((8, 0, 0), 3);
+        assert_eq!(
+            crate::testing::render(&timer, datetime!(2024-05-01 02:00:00.1234 UTC)),
+            "2024年05月01日 10时00分00.123秒"
+        );
+    }
+}
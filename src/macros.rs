@@ -0,0 +1,71 @@
+//! [`local_time!`], a timer whose offset and format are validated at compile
+//! time.
+
+/// Returns a [`LocalTime`](crate::LocalTime) for a fixed offset and a format
+/// description, both validated at compile time.
+///
+/// The offset takes any form [`TimezoneSpec`](crate::TimezoneSpec) parses as
+/// a fixed offset: `±HH[:MM[:SS]]`, `Z` or `UTC`. The format is checked by
+/// `time`'s [`format_description!`], so the calling crate must depend on
+/// `time` too.
+///
+/// The expansion is a constant expression, so it can initialize a `static`.
+///
+/// # Examples
+///
+/// ```
+/// use time::macros::datetime;
+///
+/// let timer = clia_local_time::local_time!("+08:00", "[hour]:[minute]:[second]");
+/// let mut s = String::new();
+/// timer.format_datetime(datetime!(2024-05-01 02:00 UTC), &mut s).unwrap();
+/// assert_eq!(s, "10:00:00");
+/// ```
+///
+/// An invalid offset fails to compile:
+///
+/// ```compile_fail
+/// let timer = clia_local_time::local_time!("+8:00", "[hour]:[minute]:[second]");
+/// ```
+///
+/// [`format_description!`]: https://docs.rs/time/0.3/time/macros/macro.format_description.html
+#[macro_export]
+macro_rules! local_time {
+    ($offset:literal, $format:tt) => {{
+        const OFFSET: $crate::__private::UtcOffset = $crate::__private::offset($offset);
+        $crate::LocalTime::with_provider(
+            $crate::__private::format_description!($format),
+            $crate::offset::FixedOffset(OFFSET),
+        )
+    }};
+}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use time::macros::format_description;
+    pub use time::UtcOffset;
+
+    /// Parses the offset of [`local_time!`], panicking at compile time if it
+    /// is invalid.
+    pub const fn offset(s: &str) -> UtcOffset {
+        let Some((h, m, s)) = crate::timezone::parse_offset(s) else {
+            panic!("invalid offset, expected `±HH[:MM[:SS]]`, `Z` or `UTC`");
+        };
+        match UtcOffset::from_hms(h, m, s) {
+            Ok(offset) => offset,
+            Err(_) => panic!("offset out of range"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::offset;
+
+    #[test]
+    fn test_local_time() {
+        let timer = local_time!("-02:30", "[hour]");
+        assert_eq!(timer.offset(), offset!(-2:30));
+        assert_eq!(local_time!("Z", "[hour]").offset(), offset!(UTC));
+    }
+}
@@ -0,0 +1,120 @@
+//! Keeping timestamps from going backwards.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use time::OffsetDateTime;
+
+use crate::{DeltaScope, LocalTime};
+
+/// The last instant emitted, as Unix nanoseconds.
+#[derive(Debug)]
+pub(crate) struct Monotonic {
+    pub(crate) scope: DeltaScope,
+    last: AtomicI64,
+    /// Identifies this state, shared by clones of its timer, in
+    /// [`LAST_INSTANT`].
+    id: usize,
+}
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// The last instant emitted on this thread, by each timer's state.
+    static LAST_INSTANT: RefCell<HashMap<usize, i64>> = RefCell::new(HashMap::new());
+}
+
+// === impl Monotonic ===
+
+impl Monotonic {
+    fn new(scope: DeltaScope) -> Self {
+        Self {
+            scope,
+            last: AtomicI64::new(i64::MIN),
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    /// Returns `now`, or the last instant emitted if `now` is before it.
+    pub(crate) fn clamp(&self, now: OffsetDateTime) -> OffsetDateTime {
+        let Ok(nanos) = i64::try_from(now.unix_timestamp_nanos()) else {
+            return now;
+        };
+        let last = match self.scope {
+            DeltaScope::Global => self.last.fetch_max(nanos, Ordering::AcqRel),
+            DeltaScope::PerThread => LAST_INSTANT.with(|last| {
+                let mut last = last.borrow_mut();
+                let last = last.entry(self.id).or_insert(i64::MIN);
+                std::mem::replace(last, (*last).max(nanos))
+            }),
+        };
+        if last > nanos {
+            OffsetDateTime::from_unix_timestamp_nanos(last.into()).unwrap_or(now)
+        } else {
+            now
+        }
+    }
+}
+
+// === impl LocalTime ===
+
+impl<F, P, C> LocalTime<F, P, C> {
+    /// Clamps every timestamp to be no earlier than the last one emitted
+    /// within `scope`, so clock steps, e.g. NTP corrections or VM clock jumps,
+    /// never produce out-of-order log lines. Events during a backwards step
+    /// repeat the last timestamp until the clock catches up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::{DeltaScope, LocalTime};
+    ///
+    /// let timer = LocalTime::rfc_3339().monotonic(DeltaScope::Global);
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(timer);
+    /// # drop(collector);
+    /// ```
+    pub fn monotonic(self, scope: DeltaScope) -> Self {
+        Self {
+            monotonic: Some(Arc::new(Monotonic::new(scope))),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{clock::ReplayClock, DeltaScope, LocalTime};
+    use time::macros::datetime;
+
+    #[test]
+    fn test_monotonic() {
+        for scope in [DeltaScope::Global, DeltaScope::PerThread] {
+            let clock = ReplayClock::new(datetime!(2024-05-01 10:00:05 UTC));
+            let timer = LocalTime::w3c((0, 0, 0))
+                .with_clock(clock.clone())
+                .monotonic(scope);
+            assert_eq!(timer.now(), datetime!(2024-05-01 10:00:05 UTC));
+            clock.seek(datetime!(2024-05-01 10:00:01 UTC));
+            assert_eq!(timer.now(), datetime!(2024-05-01 10:00:05 UTC));
+            clock.seek(datetime!(2024-05-01 10:00:06 UTC));
+            assert_eq!(timer.now(), datetime!(2024-05-01 10:00:06 UTC));
+        }
+    }
+
+    #[test]
+    fn test_per_thread_timers_independent() {
+        let clock = ReplayClock::new(datetime!(2024-05-01 10:00:00 UTC));
+        let ahead = LocalTime::w3c((0, 0, 0))
+            .with_clock(clock.clone())
+            .with_skew(time::Duration::MINUTE)
+            .monotonic(DeltaScope::PerThread);
+        let timer = LocalTime::w3c((0, 0, 0))
+            .with_clock(clock)
+            .monotonic(DeltaScope::PerThread);
+        assert_eq!(ahead.now(), datetime!(2024-05-01 10:01:00 UTC));
+        assert_eq!(timer.now(), datetime!(2024-05-01 10:00:00 UTC));
+    }
+}
@@ -0,0 +1,189 @@
+//! One timer configuration rendered in several timezones, e.g. the console in
+//! local time and the log file in UTC.
+//!
+//! [`MultiZoneTime`] is also a [`Layer`]: installed before the `fmt` layers,
+//! it reads the clock once per event, and every [`ZoneTime`] handed out by it
+//! renders that same instant, so the lines of one event agree to the
+//! nanosecond across outputs.
+
+use std::cell::Cell;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use time::{formatting::Formattable, OffsetDateTime, UtcOffset};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::{
+    fmt::{format::Writer, time::FormatTime},
+    layer::{Context, Layer},
+};
+
+use crate::{
+    clock::{ClockSource, SystemClock},
+    offset::{FixedOffset, OffsetProvider},
+    LocalTime,
+};
+
+/// Identifies a [`MultiZoneTime`] and its clones in [`LAST_READ`].
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// The instant the latest event on this thread was read at, and the
+    /// timer that read it.
+    static LAST_READ: Cell<Option<(usize, OffsetDateTime)>> = const { Cell::new(None) };
+}
+
+/// A [`LocalTime`] shared by several layers that render it in different
+/// timezones.
+///
+/// The format, decoration, clock and other settings come from the wrapped
+/// timer; only the offset differs per [`zone`](MultiZoneTime::zone).
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::{LocalTime, MultiZoneTime};
+/// use time::{macros::offset, UtcOffset};
+/// use tracing_subscriber::{fmt, layer::SubscriberExt};
+///
+/// let zones = MultiZoneTime::new(LocalTime::rfc_3339());
+/// let subscriber = tracing_subscriber::registry()
+///     // Reads the clock once per event, so it goes first.
+///     .with(zones.clone())
+///     .with(fmt::layer().with_timer(zones.zone(offset!(+8))))
+///     .with(
+///         fmt::layer()
+///             .with_timer(zones.zone(UtcOffset::UTC))
+///             .with_writer(std::io::stderr),
+///     );
+/// # drop(subscriber);
+/// ```
+#[derive(Debug)]
+pub struct MultiZoneTime<F, P = FixedOffset, C = SystemClock> {
+    timer: Arc<LocalTime<F, P, C>>,
+    id: usize,
+}
+
+/// A [`MultiZoneTime`] rendered in one timezone, returned by
+/// [`MultiZoneTime::zone`].
+#[derive(Debug)]
+pub struct ZoneTime<F, P = FixedOffset, C = SystemClock> {
+    zones: MultiZoneTime<F, P, C>,
+    offset: UtcOffset,
+}
+
+// === impl MultiZoneTime ===
+
+impl<F, P, C> MultiZoneTime<F, P, C> {
+    /// Shares `timer` between zones. Its own offset is not used.
+    pub fn new(timer: LocalTime<F, P, C>) -> Self {
+        Self {
+            timer: Arc::new(timer),
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    /// Returns a timer rendering in `offset`.
+    pub fn zone(&self, offset: UtcOffset) -> ZoneTime<F, P, C> {
+        ZoneTime {
+            zones: self.clone(),
+            offset,
+        }
+    }
+}
+
+impl<F, P: OffsetProvider, C: ClockSource> MultiZoneTime<F, P, C> {
+    /// The instant the current event was read at, or a fresh read if this
+    /// timer's layer hasn't seen it.
+    fn now(&self) -> OffsetDateTime {
+        match LAST_READ.with(Cell::get) {
+            Some((id, now)) if id == self.id => now,
+            _ => self.timer.now(),
+        }
+    }
+}
+
+impl<F, P, C> Clone for MultiZoneTime<F, P, C> {
+    fn clone(&self) -> Self {
+        Self {
+            timer: self.timer.clone(),
+            id: self.id,
+        }
+    }
+}
+
+impl<S, F, P, C> Layer<S> for MultiZoneTime<F, P, C>
+where
+    S: Subscriber,
+    F: Send + Sync + 'static,
+    P: OffsetProvider + Send + Sync + 'static,
+    C: ClockSource + Send + Sync + 'static,
+{
+    fn on_event(&self, _event: &Event<'_>, _ctx: Context<'_, S>) {
+        let now = self.timer.now();
+        LAST_READ.with(|last| last.set(Some((self.id, now))));
+    }
+}
+
+// === impl ZoneTime ===
+
+impl<F, P, C> Clone for ZoneTime<F, P, C> {
+    fn clone(&self) -> Self {
+        Self {
+            zones: self.zones.clone(),
+            offset: self.offset,
+        }
+    }
+}
+
+impl<F, P, C> FormatTime for ZoneTime<F, P, C>
+where
+    F: Formattable,
+    P: OffsetProvider,
+    C: ClockSource,
+{
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        let now = self.zones.now().to_offset(self.offset);
+        self.zones.timer.format_decorated(now, w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MultiZoneTime;
+    use crate::{testing::Capture, LocalTime};
+    use time::macros::{format_description, offset};
+    use tracing_subscriber::{fmt, layer::SubscriberExt};
+
+    #[test]
+    fn test_zones_share_reads() {
+        let zones = MultiZoneTime::new(LocalTime::new(format_description!(
+            "[minute]:[second].[subsecond digits:9] [offset_hour sign:mandatory]"
+        )));
+        let capture = Capture::default();
+        let (east, west) = (capture.clone(), capture.clone());
+        let subscriber = tracing_subscriber::registry()
+            .with(zones.clone())
+            .with(
+                fmt::layer()
+                    .with_ansi(false)
+                    .with_timer(zones.zone(offset!(+8)))
+                    .with_writer(move || east.clone()),
+            )
+            .with(
+                fmt::layer()
+                    .with_ansi(false)
+                    .with_timer(zones.zone(offset!(-4)))
+                    .with_writer(move || west.clone()),
+            );
+        tracing::subscriber::with_default(subscriber, || tracing::info!("hello"));
+
+        let output = capture.output();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        // The same instant in both zones.
+        assert_eq!(lines[0][..15], lines[1][..15]);
+        assert_eq!(&lines[0][16..19], "+08");
+        assert_eq!(&lines[1][16..19], "-04");
+    }
+}
@@ -0,0 +1,211 @@
+//! A clock disciplined by an NTP server.
+
+use std::io;
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, Weak};
+use std::thread;
+use std::time::Duration;
+
+use time::OffsetDateTime;
+
+use crate::clock::{ClockKind, ClockSource};
+
+/// A [`ClockSource`] that corrects the system clock by the offset an NTP
+/// server measures, so timestamps from machines with drifting clocks remain
+/// comparable across a fleet.
+///
+/// A background thread queries the server over SNTP every `interval`. Until
+/// the first successful query, and whenever one fails, the last measured
+/// correction is kept (initially none). The thread stops once every clone of
+/// the clock has been dropped, or when [`shutdown`](NtpClock::shutdown) is
+/// called.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use clia_local_time::{LocalTime, NtpClock};
+///
+/// let clock = NtpClock::spawn("pool.ntp.org:123", Duration::from_secs(64));
+/// let collector = tracing_subscriber::fmt()
+///     .with_timer(LocalTime::rfc_3339().with_clock(clock));
+/// # drop(collector);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "ntp")))]
+#[derive(Clone, Debug)]
+pub struct NtpClock {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    /// Nanoseconds added to the system clock.
+    correction: AtomicI64,
+    stop: AtomicBool,
+}
+
+/// Seconds from the NTP era (1900) to the Unix epoch.
+const NTP_TO_UNIX: i128 = 2_208_988_800;
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+// === impl NtpClock ===
+
+impl NtpClock {
+    /// Starts disciplining the clock against `server`, e.g.
+    /// `pool.ntp.org:123`, re-measuring every `interval`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the thread can't be spawned.
+    pub fn spawn(server: impl Into<String>, interval: Duration) -> Self {
+        let shared = Arc::new(Shared {
+            correction: AtomicI64::new(0),
+            stop: AtomicBool::new(false),
+        });
+        let weak = Arc::downgrade(&shared);
+        let server = server.into();
+        thread::Builder::new()
+            .name("clia-local-time-ntp".into())
+            .spawn(move || run(weak, &server, interval))
+            .expect("failed to spawn the NTP thread");
+        Self { shared }
+    }
+
+    /// The correction currently applied to the system clock.
+    pub fn correction(&self) -> time::Duration {
+        time::Duration::nanoseconds(self.shared.correction.load(Ordering::Relaxed))
+    }
+
+    /// Stops querying the server. The last correction keeps being applied.
+    pub fn shutdown(&self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+impl ClockSource for NtpClock {
+    fn now_utc(&self) -> OffsetDateTime {
+        OffsetDateTime::now_utc() + self.correction()
+    }
+
+    fn kind(&self) -> ClockKind {
+        ClockKind::Disciplined
+    }
+}
+
+fn run(shared: Weak<Shared>, server: &str, interval: Duration) {
+    loop {
+        let correction = measure_offset(server);
+        match shared.upgrade() {
+            Some(shared) if !shared.stop.load(Ordering::Relaxed) => {
+                if let Ok(correction) = correction {
+                    let nanos = correction
+                        .whole_nanoseconds()
+                        .clamp(i64::MIN.into(), i64::MAX.into());
+                    shared.correction.store(nanos as i64, Ordering::Relaxed);
+                }
+            }
+            _ => return,
+        }
+        thread::sleep(interval);
+    }
+}
+
+/// Measures how far the system clock is behind `server` with one SNTP
+/// exchange.
+fn measure_offset(server: &str) -> io::Result<time::Duration> {
+    let addr = server
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no address for NTP server"))?;
+    let socket = UdpSocket::bind(if addr.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    })?;
+    socket.set_read_timeout(Some(TIMEOUT))?;
+    socket.connect(addr)?;
+
+    let sent = OffsetDateTime::now_utc();
+    let mut request = [0u8; 48];
+    // LI = 0, VN = 4, Mode = 3 (client).
+    request[0] = 0x23;
+    request[40..48].copy_from_slice(&to_ntp(sent).to_be_bytes());
+    socket.send(&request)?;
+
+    let mut reply = [0u8; 48];
+    let len = socket.recv(&mut reply)?;
+    let received = OffsetDateTime::now_utc();
+    if len < 48 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "short NTP reply",
+        ));
+    }
+    offset_from_reply(sent, &reply, received)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid NTP reply"))
+}
+
+/// The clock offset from an SNTP reply to a request sent at `sent`, as in
+/// RFC 4330: `((T2 - T1) + (T3 - T4)) / 2`.
+fn offset_from_reply(
+    sent: OffsetDateTime,
+    reply: &[u8; 48],
+    received: OffsetDateTime,
+) -> Option<time::Duration> {
+    let mode = reply[0] & 0x7;
+    let stratum = reply[1];
+    let field = |at: usize| u64::from_be_bytes(reply[at..at + 8].try_into().unwrap());
+    // Mode 4 is a server reply; stratum 0 is a kiss-o'-death packet.
+    if mode != 4 || stratum == 0 || field(24) != to_ntp(sent) || field(40) == 0 {
+        return None;
+    }
+    let server_received = from_ntp(field(32))?;
+    let server_sent = from_ntp(field(40))?;
+    Some(((server_received - sent) + (server_sent - received)) / 2)
+}
+
+fn to_ntp(t: OffsetDateTime) -> u64 {
+    let seconds = (i128::from(t.unix_timestamp()) + NTP_TO_UNIX) as u64;
+    let fraction = (u64::from(t.nanosecond()) << 32) / 1_000_000_000;
+    (seconds << 32) | fraction
+}
+
+fn from_ntp(ts: u64) -> Option<OffsetDateTime> {
+    let seconds = i128::from(ts >> 32) - NTP_TO_UNIX;
+    let nanos = ((ts & 0xffff_ffff) * 1_000_000_000) >> 32;
+    OffsetDateTime::from_unix_timestamp_nanos(seconds * 1_000_000_000 + i128::from(nanos)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_ntp, offset_from_reply, to_ntp};
+    use time::{macros::datetime, Duration};
+
+    #[test]
+    fn test_offset_from_reply() {
+        let sent = datetime!(2024-05-01 10:00:00 UTC);
+        let received = sent + Duration::milliseconds(40);
+        // The server's clock is 1.5s ahead; the round trip takes 40ms.
+        let server = sent + Duration::milliseconds(1_520);
+        let mut reply = [0u8; 48];
+        reply[0] = 0x24;
+        reply[1] = 2;
+        reply[24..32].copy_from_slice(&to_ntp(sent).to_be_bytes());
+        reply[32..40].copy_from_slice(&to_ntp(server).to_be_bytes());
+        reply[40..48].copy_from_slice(&to_ntp(server).to_be_bytes());
+
+        let offset = offset_from_reply(sent, &reply, received).unwrap();
+        assert!((offset - Duration::milliseconds(1_500)).abs() < Duration::microseconds(1));
+
+        reply[1] = 0;
+        assert_eq!(offset_from_reply(sent, &reply, received), None);
+    }
+
+    #[test]
+    fn test_ntp_timestamps() {
+        let t = datetime!(2036-02-07 06:28:15.5 UTC);
+        let back = from_ntp(to_ntp(t)).unwrap();
+        assert!((back - t).abs() < Duration::nanoseconds(2));
+    }
+}
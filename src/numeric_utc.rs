@@ -0,0 +1,81 @@
+//! Rendering the zero offset as `+00:00` rather than `Z`.
+
+use std::fmt;
+
+use crate::{InspectFormat, LocalTime};
+
+// === impl LocalTime ===
+
+impl<F: InspectFormat, P, C> LocalTime<F, P, C> {
+    /// Renders a zero offset as `+00:00` instead of `Z` in formats that
+    /// render it as the UTC designator, RFC 3339 and ISO 8601, e.g.
+    /// `2024-05-01T02:00:00+00:00`. Some parsers only accept one of the two.
+    ///
+    /// The format is inspected once, here; a literal `Z` in a format
+    /// description is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::macros::datetime;
+    ///
+    /// let timer = LocalTime::rfc_3339().with_numeric_utc_offset();
+    /// let mut s = String::new();
+    /// timer.format_datetime(datetime!(2024-05-01 02:00 UTC), &mut s).unwrap();
+    /// assert_eq!(s, "2024-05-01T02:00:00+00:00");
+    /// ```
+    pub fn with_numeric_utc_offset(self) -> Self {
+        Self {
+            numeric_utc: self.fast_rfc3339 || self.format.has_utc_designator(),
+            ..self
+        }
+    }
+}
+
+/// Writes `timestamp`, replacing a trailing `Z` with `+00:00`.
+pub(crate) fn write_numeric(timestamp: &str, w: &mut impl fmt::Write) -> fmt::Result {
+    match timestamp.strip_suffix('Z') {
+        Some(timestamp) => {
+            w.write_str(timestamp)?;
+            w.write_str("+00:00")
+        }
+        None => w.write_str(timestamp),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{testing::render, LocalTime, TimeFormatSpec};
+    use time::format_description::well_known::{Iso8601, Rfc3339};
+    use time::macros::{datetime, format_description, offset};
+
+    #[test]
+    fn test_numeric_utc_offset() {
+        let timer = LocalTime::rfc_3339().with_numeric_utc_offset();
+        let utc = datetime!(2024-05-01 02:00:00.5 UTC);
+        assert_eq!(render(&timer, utc), "2024-05-01T02:00:00.5+00:00");
+        assert_eq!(
+            render(&LocalTime::rfc_3339(), utc),
+            "2024-05-01T02:00:00.5Z"
+        );
+        let timer = LocalTime::new(Rfc3339).with_numeric_utc_offset();
+        assert_eq!(render(&timer, utc), "2024-05-01T02:00:00.5+00:00");
+        let timer = LocalTime::with_timezone(Rfc3339, (8, 0, 0)).with_numeric_utc_offset();
+        assert_eq!(render(&timer, utc), "2024-05-01T10:00:00.5+08:00");
+
+        let timer = LocalTime::new(Iso8601::DEFAULT).with_numeric_utc_offset();
+        let s = render(&timer, utc);
+        assert!(s.ends_with("+00:00"), "{}", s);
+
+        // A literal `Z` isn't the offset.
+        let timer =
+            LocalTime::new(format_description!("[hour]:[minute]Z")).with_numeric_utc_offset();
+        assert_eq!(render(&timer, utc), "02:00Z");
+        let timer = TimeFormatSpec::Rfc3339
+            .into_timer(offset!(UTC))
+            .unwrap()
+            .with_numeric_utc_offset();
+        assert_eq!(render(&timer, utc), "2024-05-01T02:00:00.5+00:00");
+    }
+}
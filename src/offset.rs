@@ -0,0 +1,135 @@
+//! Strategies for choosing the UTC offset timestamps are rendered in.
+//!
+//! `LocalTime` is generic over an [`OffsetProvider`], defaulting to
+//! [`FixedOffset`]. `SystemOffset` follows the system's local offset, and
+//! with the `jiff` feature, `jiff::ZoneOffset` evaluates tz database rules, so
+//! daylight saving transitions are applied.
+//!
+//! [`OffsetProvider`], [`FixedOffset`] and [`localize`] are available without
+//! `std`.
+
+use time::{OffsetDateTime, UtcOffset};
+
+/// Chooses the UTC offset for an instant.
+pub trait OffsetProvider {
+    /// Returns the offset in effect at `utc`.
+    fn offset_at(&self, utc: OffsetDateTime) -> UtcOffset;
+
+    /// Returns whether the offset at `utc` is a fallback, used because the
+    /// actual one can't be determined.
+    fn is_fallback(&self, _utc: OffsetDateTime) -> bool {
+        false
+    }
+}
+
+/// Converts `utc` to the offset `provider` chooses for it.
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::offset::{localize, FixedOffset};
+/// use time::macros::{datetime, offset};
+///
+/// let local = localize(datetime!(2024-05-01 02:00 UTC), &FixedOffset(offset!(+8)));
+/// assert_eq!(local, datetime!(2024-05-01 10:00 +8));
+/// ```
+pub fn localize(utc: OffsetDateTime, provider: &impl OffsetProvider) -> OffsetDateTime {
+    utc.to_offset(provider.offset_at(utc))
+}
+
+/// The same offset for every instant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FixedOffset(pub UtcOffset);
+
+/// The system's local offset, detected for every instant.
+///
+/// On Windows the offset is read with `GetDynamicTimeZoneInformation`, on
+/// Apple platforms with `CFTimeZoneCopySystem`, on other Unix systems from
+/// `/etc/localtime` unless `TZ` is set, elsewhere through `time`. Detection
+/// may fail, e.g. because `time` is only consulted on Unix while the process
+/// is single-threaded, since its `localtime_r` call races with `setenv`; the
+/// offset detected when the provider was created is used instead. In
+/// browsers, detection uses `Date.getTimezoneOffset` with the `wasm` feature.
+/// With the `iana-time-zone` feature, instants the platform can't answer for
+/// are looked up in the rules of the zone iana-time-zone names, unless `TZ`
+/// is set.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SystemOffset {
+    fallback: UtcOffset,
+}
+
+// === impl FixedOffset ===
+
+impl OffsetProvider for FixedOffset {
+    fn offset_at(&self, _utc: OffsetDateTime) -> UtcOffset {
+        self.0
+    }
+}
+
+// === impl SystemOffset ===
+
+#[cfg(feature = "std")]
+impl SystemOffset {
+    /// Returns a provider falling back to the offset detected now, or to UTC
+    /// if that fails too.
+    pub fn new() -> Self {
+        let now = OffsetDateTime::now_utc();
+        Self::with_fallback(crate::system::local_offset_at(now).unwrap_or(UtcOffset::UTC))
+    }
+
+    /// Returns a provider falling back to `fallback` when detection fails.
+    pub fn with_fallback(fallback: UtcOffset) -> Self {
+        Self { fallback }
+    }
+
+    /// Returns the system's zone identifier, e.g. `Asia/Shanghai`, where the
+    /// platform reports one: on Apple platforms, or everywhere iana-time-zone
+    /// supports with the `iana-time-zone` feature.
+    pub fn zone_name(&self) -> Option<String> {
+        crate::system::zone_name()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for SystemOffset {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl OffsetProvider for SystemOffset {
+    fn offset_at(&self, utc: OffsetDateTime) -> UtcOffset {
+        crate::system::local_offset_at(utc).unwrap_or(self.fallback)
+    }
+
+    fn is_fallback(&self, utc: OffsetDateTime) -> bool {
+        crate::system::local_offset_at(utc).is_none()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::{FixedOffset, OffsetProvider, SystemOffset};
+    use crate::{testing::render, LocalTime};
+    use time::macros::{datetime, format_description, offset};
+    use time::UtcOffset;
+
+    #[test]
+    fn test_providers() {
+        let at = datetime!(2024-05-01 02:00 UTC);
+        assert_eq!(FixedOffset(offset!(+8)).offset_at(at), offset!(+8));
+
+        let system = SystemOffset::with_fallback(offset!(-3));
+        let offset = system.offset_at(at);
+        assert!(offset == offset!(-3) || UtcOffset::local_offset_at(at) == Ok(offset));
+
+        let timer = LocalTime::with_provider(
+            format_description!("[hour]:[minute]"),
+            FixedOffset(offset!(+8)),
+        );
+        assert_eq!(render(&timer, at), "10:00");
+    }
+}
@@ -0,0 +1,173 @@
+//! Parsing timestamps a [`LocalTime`] emitted, for log-analysis tools that
+//! should agree with the logging side on the format.
+
+use std::error::Error;
+use std::fmt;
+
+use time::{
+    error::{self, TryFromParsed},
+    parsing::Parsable,
+    OffsetDateTime, PrimitiveDateTime, UtcOffset,
+};
+
+use crate::{
+    decimal_comma::replace_separator, offset::FixedOffset, timezone::parse_offset, LocalTime,
+};
+
+/// The error returned by [`LocalTime::parse`].
+#[cfg_attr(docsrs, doc(cfg(feature = "parsing")))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TimestampParseError {
+    /// The input lacks the timer's prefix, suffix or clock marker.
+    Decoration,
+    /// The offset appended by [`LocalTime::with_auto_offset`] is invalid.
+    Offset,
+    /// The timestamp doesn't match the format.
+    Format(error::Parse),
+}
+
+// === impl LocalTime ===
+
+impl<F: Parsable, C> LocalTime<F, FixedOffset, C> {
+    /// Parses a timestamp this timer emitted, undoing its decoration: the
+    /// prefix and suffix, the padding of
+    /// [`with_width`](LocalTime::with_width), the clock marker, the repeat
+    /// counter, an appended offset and a decimal comma. Formats without an
+    /// offset are read in the configured one.
+    ///
+    /// Timestamps truncated by `with_width`, or formats without a date, can't
+    /// be parsed back. Skew and time scales are not undone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::macros::datetime;
+    ///
+    /// let timer = LocalTime::w3c((8, 0, 0)).decorated("[", "]");
+    /// let parsed = timer.parse("[2024-05-01 10:00:00]").expect("timestamp should parse");
+    /// assert_eq!(parsed, datetime!(2024-05-01 02:00 UTC));
+    /// ```
+    pub fn parse(&self, s: &str) -> Result<OffsetDateTime, TimestampParseError> {
+        let mut s = s
+            .strip_prefix(self.prefix.as_str())
+            .and_then(|s| s.strip_suffix(self.suffix.as_str()))
+            .ok_or(TimestampParseError::Decoration)?;
+        if self.source_tag {
+            s = s
+                .rsplit_once(' ')
+                .filter(|(_, marker)| marker.chars().count() == 1)
+                .ok_or(TimestampParseError::Decoration)?
+                .0;
+        }
+        if self.width.is_some() {
+            s = s.trim_end_matches(' ');
+        }
+        if self.repeats.is_some() {
+            if let Some((timestamp, _)) = s
+                .rsplit_once('#')
+                .filter(|(_, n)| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+            {
+                s = timestamp;
+            }
+        }
+        let mut offset = self.offset.0;
+        if self.auto_offset {
+            let (timestamp, appended) = s.rsplit_once(' ').ok_or(TimestampParseError::Offset)?;
+            offset = parse_offset(appended)
+                .and_then(|(h, m, s)| UtcOffset::from_hms(h, m, s).ok())
+                .ok_or(TimestampParseError::Offset)?;
+            s = timestamp;
+        }
+
+        let dotted;
+        if self.decimal_comma {
+            let mut owned = s.to_string();
+            replace_separator(&mut owned, b',', ".");
+            dotted = owned;
+            s = &dotted;
+        }
+
+        match OffsetDateTime::parse(s, &self.format) {
+            Err(error::Parse::TryFromParsed(TryFromParsed::InsufficientInformation)) => {
+                PrimitiveDateTime::parse(s, &self.format).map(|dt| dt.assume_offset(offset))
+            }
+            parsed => parsed,
+        }
+        .map_err(TimestampParseError::Format)
+    }
+}
+
+// === impl TimestampParseError ===
+
+impl fmt::Display for TimestampParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimestampParseError::Decoration => f.write_str("timestamp decoration doesn't match"),
+            TimestampParseError::Offset => f.write_str("invalid appended offset"),
+            TimestampParseError::Format(e) => {
+                write!(f, "timestamp doesn't match the format: {}", e)
+            }
+        }
+    }
+}
+
+impl Error for TimestampParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TimestampParseError::Format(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TimestampParseError;
+    use crate::{testing::render, LocalTime};
+    use time::macros::{datetime, format_description};
+
+    #[test]
+    fn test_parse_round_trip() {
+        let now = datetime!(2024-05-01 02:00:00.25 UTC);
+        let timers = [
+            LocalTime::rfc_3339().with_width(30),
+            LocalTime::rfc_3339()
+                .decorated("ts=", ";")
+                .with_source_tag()
+                .with_repeat_counter(),
+            LocalTime::rfc_3339()
+                .with_decimal_comma()
+                .with_numeric_utc_offset(),
+        ];
+        for timer in timers {
+            for _ in 0..2 {
+                let s = render(&timer, now);
+                assert_eq!(timer.parse(&s), Ok(now), "{}", s);
+            }
+        }
+
+        let timer = LocalTime::with_timezone(
+            format_description!("[year]-[month]-[day] [hour]:[minute]"),
+            (-3, -30, 0),
+        );
+        let s = render(&timer, now);
+        assert_eq!(timer.parse(&s), Ok(datetime!(2024-05-01 02:00 UTC)));
+        let auto = timer.with_auto_offset();
+        assert_eq!(
+            auto.parse("2024-05-01 10:00 +08:00"),
+            Ok(datetime!(2024-05-01 02:00 UTC))
+        );
+        assert_eq!(
+            auto.parse("2024-05-01 10:00 +8"),
+            Err(TimestampParseError::Offset)
+        );
+        assert_eq!(
+            LocalTime::rfc_3339()
+                .decorated("[", "]")
+                .parse("2024-05-01"),
+            Err(TimestampParseError::Decoration)
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! A clock reading a PTP hardware clock.
+
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+use time::OffsetDateTime;
+
+use crate::clock::{ClockKind, ClockSource};
+
+/// A [`ClockSource`] reading a PTP hardware clock (PHC), e.g. `/dev/ptp0`,
+/// the clock of a network card that `ptp4l` synchronizes with a grandmaster,
+/// so hosts sharing a PTP domain log comparable timestamps.
+///
+/// The clock is read as it is. PHCs commonly count TAI, which is 37 seconds
+/// ahead of UTC since 2017; compensate with
+/// [`LocalTime::with_skew`](crate::LocalTime::with_skew) when yours does. If
+/// a read fails, e.g. because the device was removed, the system clock is
+/// read instead.
+///
+/// PHCs are only supported on Linux; elsewhere [`open`](PhcClock::open)
+/// fails with [`io::ErrorKind::Unsupported`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use clia_local_time::{LocalTime, PhcClock};
+///
+/// let clock = PhcClock::open("/dev/ptp0").expect("the PHC should be readable");
+/// let collector = tracing_subscriber::fmt()
+///     .with_timer(LocalTime::rfc_3339().with_clock(clock));
+/// # drop(collector);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "phc")))]
+#[derive(Clone, Debug)]
+pub struct PhcClock {
+    /// The open device, which its clock is addressed through.
+    device: Arc<File>,
+}
+
+// === impl PhcClock ===
+
+impl PhcClock {
+    /// Opens the PHC device at `path`, failing if it can't be read.
+    #[cfg(target_os = "linux")]
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let clock = Self {
+            device: Arc::new(File::open(path)?),
+        };
+        clock.read().ok_or_else(io::Error::last_os_error)?;
+        Ok(clock)
+    }
+
+    /// Reads the clock through the dynamic clock id of the open device.
+    #[cfg(target_os = "linux")]
+    fn read(&self) -> Option<OffsetDateTime> {
+        use std::os::fd::AsRawFd;
+
+        // `FD_TO_CLOCKID` from the kernel's `posix-timers.h`.
+        let id = (!self.device.as_raw_fd() << 3) | 3;
+        crate::clock::clock_gettime(id)
+    }
+
+    /// PHCs can't be read outside Linux.
+    #[cfg(not(target_os = "linux"))]
+    pub fn open(_path: impl AsRef<Path>) -> io::Result<Self> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn read(&self) -> Option<OffsetDateTime> {
+        None
+    }
+}
+
+impl ClockSource for PhcClock {
+    fn now_utc(&self) -> OffsetDateTime {
+        self.read().unwrap_or_else(OffsetDateTime::now_utc)
+    }
+
+    fn kind(&self) -> ClockKind {
+        ClockKind::Phc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PhcClock;
+    use crate::clock::ClockSource;
+
+    #[test]
+    fn test_open() {
+        assert!(PhcClock::open("/nonexistent/ptp0").is_err());
+        // Regular files aren't clocks.
+        assert!(PhcClock::open("/dev/null").is_err());
+        if let Ok(clock) = PhcClock::open("/dev/ptp0") {
+            assert!(clock.now_utc() > time::OffsetDateTime::UNIX_EPOCH);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Relative timestamps for logs that are shared outside the organization.
+
+use std::fmt;
+use std::time::Instant;
+
+use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
+
+use crate::duration::{format_duration, DurationStyle};
+
+/// Formats the time elapsed since the timer was created, e.g.
+/// `T+00:04:12.345`, instead of an absolute timestamp.
+///
+/// Intervals between lines are preserved while the wall-clock time of the run
+/// is hidden. Each layer can be given its own timer, so e.g. the file written
+/// for external sharing can be redacted while the console keeps using
+/// [`LocalTime`](crate::LocalTime).
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::RedactedTime;
+///
+/// let collector = tracing_subscriber::fmt()
+///     .with_timer(RedactedTime::new());
+/// # drop(collector);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct RedactedTime {
+    epoch: Instant,
+}
+
+// === impl RedactedTime ===
+
+impl RedactedTime {
+    /// Returns a timer counting from now.
+    pub fn new() -> Self {
+        Self::since(Instant::now())
+    }
+
+    /// Returns a timer counting from `epoch`, so several timers can share the
+    /// same starting point.
+    pub fn since(epoch: Instant) -> Self {
+        Self { epoch }
+    }
+}
+
+impl Default for RedactedTime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatTime for RedactedTime {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        let elapsed = self.epoch.elapsed();
+        write!(w, "T+{}", format_duration(elapsed, DurationStyle::Colon))
+    }
+}
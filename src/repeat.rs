@@ -0,0 +1,83 @@
+//! Numbering consecutive identical timestamps.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::LocalTime;
+
+/// The last timestamp emitted, and how many times in a row it was.
+#[derive(Debug, Default)]
+pub(crate) struct Repeats {
+    last: Mutex<(String, u32)>,
+}
+
+// === impl Repeats ===
+
+impl Repeats {
+    /// Records `timestamp` as emitted, returning how many consecutive times
+    /// it has been, including this one.
+    pub(crate) fn record(&self, timestamp: &str) -> u32 {
+        let mut last = self.last.lock().unwrap_or_else(PoisonError::into_inner);
+        if last.0 == timestamp {
+            last.1 = last.1.saturating_add(1);
+        } else {
+            last.0.clear();
+            last.0.push_str(timestamp);
+            last.1 = 1;
+        }
+        last.1
+    }
+}
+
+// === impl LocalTime ===
+
+impl<F, P, C> LocalTime<F, P, C> {
+    /// Appends a counter to a timestamp identical to the one emitted just
+    /// before it, e.g. `10:00:00.123`, `10:00:00.123#2`, `10:00:00.123#3`,
+    /// so sorting tools keep the order of events within one tick of the
+    /// format.
+    ///
+    /// Clones of the timer share the last timestamp. The counter is part of
+    /// the timestamp padded or truncated by
+    /// [`with_width`](LocalTime::with_width).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    ///
+    /// let timer = LocalTime::rfc_3339().with_repeat_counter();
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(timer);
+    /// # drop(collector);
+    /// ```
+    pub fn with_repeat_counter(self) -> Self {
+        Self {
+            repeats: Some(Arc::new(Repeats::default())),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{testing, LocalTime};
+    use time::macros::datetime;
+
+    #[test]
+    fn test_repeat_counter() {
+        let timer = LocalTime::w3c((8, 0, 0)).with_repeat_counter();
+        let render = |dt| testing::render(&timer, dt);
+        let now = datetime!(2024-05-01 02:00:00.1 UTC);
+        assert_eq!(render(now), "2024-05-01 10:00:00");
+        assert_eq!(render(now), "2024-05-01 10:00:00#2");
+        assert_eq!(
+            render(datetime!(2024-05-01 02:00:00.9 UTC)),
+            "2024-05-01 10:00:00#3"
+        );
+        assert_eq!(
+            render(datetime!(2024-05-01 02:00:01 UTC)),
+            "2024-05-01 10:00:01"
+        );
+        assert_eq!(render(now), "2024-05-01 10:00:00");
+    }
+}
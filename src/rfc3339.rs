@@ -0,0 +1,137 @@
+//! A hand-rolled RFC 3339 writer, used by `LocalTime::rfc_3339`.
+//!
+//! This renders the same text as `time`'s [`Rfc3339`] into a stack buffer
+//! with plain integer arithmetic, skipping the generic `format_into`
+//! machinery, the `io::Write` adaptor and its error mapping. It needs
+//! neither `std` nor an allocator.
+//!
+//! [`Rfc3339`]: https://docs.rs/time/0.3/time/format_description/well_known/struct.Rfc3339.html
+
+use core::fmt;
+
+use time::OffsetDateTime;
+
+/// The longest output: `9999-12-31T23:59:59.999999999+23:59`.
+const MAX_LEN: usize = 35;
+
+/// Writes `now` in the RFC 3339 format, failing where `time` would: for years
+/// outside `0..=9999`, offsets of 24 hours or more and offsets with a seconds
+/// component.
+///
+/// # Examples
+///
+/// ```
+/// use time::macros::datetime;
+///
+/// let mut s = String::new();
+/// clia_local_time::rfc3339::write(datetime!(2024-05-01 10:00:00.5 +8), &mut s).unwrap();
+/// assert_eq!(s, "2024-05-01T10:00:00.5+08:00");
+/// ```
+pub fn write(now: OffsetDateTime, w: &mut impl fmt::Write) -> fmt::Result {
+    let year = now.year();
+    let offset = now.offset();
+    if !(0..10_000).contains(&year)
+        || offset.whole_hours().unsigned_abs() > 23
+        || offset.seconds_past_minute() != 0
+    {
+        return Err(fmt::Error);
+    }
+
+    let mut buf = [0u8; MAX_LEN];
+    let year = year as u32;
+    two_digits(&mut buf[0..2], year / 100);
+    two_digits(&mut buf[2..4], year % 100);
+    buf[4] = b'-';
+    two_digits(&mut buf[5..7], now.month() as u32);
+    buf[7] = b'-';
+    two_digits(&mut buf[8..10], now.day().into());
+    buf[10] = b'T';
+    two_digits(&mut buf[11..13], now.hour().into());
+    buf[13] = b':';
+    two_digits(&mut buf[14..16], now.minute().into());
+    buf[16] = b':';
+    two_digits(&mut buf[17..19], now.second().into());
+    let mut len = 19;
+
+    let nanos = now.nanosecond();
+    if nanos != 0 {
+        buf[len] = b'.';
+        let mut digits = nanos;
+        for i in (0..9).rev() {
+            buf[len + 1 + i] = b'0' + (digits % 10) as u8;
+            digits /= 10;
+        }
+        // Trailing zeros are trimmed, as `time` does.
+        len += 10 - trailing_zeros(nanos);
+    }
+
+    if offset.is_utc() {
+        buf[len] = b'Z';
+        len += 1;
+    } else {
+        buf[len] = if offset.is_negative() { b'-' } else { b'+' };
+        two_digits(
+            &mut buf[len + 1..len + 3],
+            offset.whole_hours().unsigned_abs().into(),
+        );
+        buf[len + 3] = b':';
+        two_digits(
+            &mut buf[len + 4..len + 6],
+            offset.minutes_past_hour().unsigned_abs().into(),
+        );
+        len += 6;
+    }
+
+    // Only ASCII digits and separators were written.
+    w.write_str(core::str::from_utf8(&buf[..len]).map_err(|_| fmt::Error)?)
+}
+
+fn two_digits(out: &mut [u8], n: u32) {
+    out[0] = b'0' + (n / 10) as u8;
+    out[1] = b'0' + (n % 10) as u8;
+}
+
+/// The number of trailing decimal zeros of a non-zero `n`.
+fn trailing_zeros(mut n: u32) -> usize {
+    let mut zeros = 0;
+    while n.is_multiple_of(10) {
+        n /= 10;
+        zeros += 1;
+    }
+    zeros
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::write;
+    use time::{format_description::well_known::Rfc3339, macros::datetime};
+
+    #[test]
+    fn test_matches_time() {
+        for now in [
+            datetime!(1970-01-01 00:00 UTC),
+            datetime!(2024-05-01 10:00:00.5 +08:00),
+            datetime!(2024-05-01 10:00:00.000000123 -00:30),
+            datetime!(0001-12-31 23:59:59.999999999 -23:59),
+            datetime!(9999-02-28 08:09:10.01 +05:45),
+        ] {
+            let mut s = String::new();
+            write(now, &mut s).unwrap();
+            assert_eq!(s, now.format(&Rfc3339).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut s = String::new();
+        assert!(write(datetime!(2024-05-01 10:00 +05:30:15), &mut s).is_err());
+        assert!(write(datetime!(-0001-05-01 10:00 UTC), &mut s).is_err());
+        for now in [
+            datetime!(2024-05-01 10:00 +24),
+            datetime!(2024-05-01 10:00 -25),
+        ] {
+            assert!(write(now, &mut s).is_err());
+            assert!(now.format(&Rfc3339).is_err());
+        }
+    }
+}
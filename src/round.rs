@@ -0,0 +1,76 @@
+//! Rounding timestamps down to an interval, e.g. for coarse privacy or for
+//! deduplicating log lines by their timestamp.
+
+use time::{Duration, OffsetDateTime};
+
+use crate::LocalTime;
+
+// === impl LocalTime ===
+
+impl<F, P, C> LocalTime<F, P, C> {
+    /// Rounds emitted timestamps down to a multiple of `interval`, e.g.
+    /// `Duration::seconds(10)` renders `10:00:17` as `10:00:10`.
+    ///
+    /// Rounding happens after the offset is applied, so intervals align to
+    /// local wall-clock boundaries: with a whole day, timestamps fall on
+    /// local midnight. A zero, negative or overlong interval disables
+    /// rounding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::{macros::datetime, Duration};
+    ///
+    /// let timer = LocalTime::w3c((5, 30, 0)).rounded_to(Duration::hours(1));
+    /// let mut s = String::new();
+    /// timer.format_datetime(datetime!(2024-05-01 02:00 UTC), &mut s).unwrap();
+    /// assert_eq!(s, "2024-05-01 07:00:00");
+    /// ```
+    pub fn rounded_to(self, interval: Duration) -> Self {
+        let round_to = i64::try_from(interval.whole_nanoseconds())
+            .ok()
+            .filter(|&nanos| nanos > 0)
+            .map(Duration::nanoseconds);
+        Self { round_to, ..self }
+    }
+
+    /// Rounds `now` down to the configured interval, in its own offset.
+    pub(crate) fn round_down(&self, now: OffsetDateTime) -> OffsetDateTime {
+        let Some(interval) = self.round_to else {
+            return now;
+        };
+        let local =
+            now.unix_timestamp_nanos() + i128::from(now.offset().whole_seconds()) * 1_000_000_000;
+        let excess = local.rem_euclid(interval.whole_nanoseconds());
+        // The excess is below the interval, which fits an `i64`.
+        now - Duration::nanoseconds(excess as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{testing::render, LocalTime};
+    use time::{macros::datetime, Duration};
+
+    #[test]
+    fn test_rounded_to() {
+        let timer = LocalTime::w3c((5, 45, 0)).rounded_to(Duration::seconds(10));
+        assert_eq!(
+            render(&timer, datetime!(2024-05-01 02:00:17.9 UTC)),
+            "2024-05-01 07:45:10"
+        );
+
+        let timer = LocalTime::w3c((-2, -30, 0)).rounded_to(Duration::days(1));
+        assert_eq!(
+            render(&timer, datetime!(2024-05-01 01:00 UTC)),
+            "2024-04-30 00:00:00"
+        );
+
+        let timer = LocalTime::w3c((8, 0, 0)).rounded_to(Duration::ZERO);
+        assert_eq!(
+            render(&timer, datetime!(2024-05-01 02:00:17 UTC)),
+            "2024-05-01 10:00:17"
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! Uniform time scales, for logs that must not jump at leap seconds.
+
+use time::{format_description::StaticFormatDescription, macros::format_description};
+use time::{Duration, OffsetDateTime};
+
+use crate::LocalTime;
+
+/// The time scale timestamps are rendered in.
+///
+/// TAI and GPS time count every second, so they run ahead of UTC by the leap
+/// seconds inserted since they started.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TimeScale {
+    /// Coordinated Universal Time, as reported by the clock.
+    #[default]
+    Utc,
+    /// International Atomic Time, 37 seconds ahead of UTC since 2017.
+    Tai,
+    /// GPS time, a constant 19 seconds behind TAI.
+    Gps,
+}
+
+/// The Unix times from which TAI − UTC took each value, in seconds.
+///
+/// Before 1972 the difference wasn't a whole number of seconds; the first
+/// value is used for those instants.
+const LEAP_SECONDS: [(i64, i64); 28] = [
+    (63_072_000, 10),    // 1972-01-01
+    (78_796_800, 11),    // 1972-07-01
+    (94_694_400, 12),    // 1973-01-01
+    (126_230_400, 13),   // 1974-01-01
+    (157_766_400, 14),   // 1975-01-01
+    (189_302_400, 15),   // 1976-01-01
+    (220_924_800, 16),   // 1977-01-01
+    (252_460_800, 17),   // 1978-01-01
+    (283_996_800, 18),   // 1979-01-01
+    (315_532_800, 19),   // 1980-01-01
+    (362_793_600, 20),   // 1981-07-01
+    (394_329_600, 21),   // 1982-07-01
+    (425_865_600, 22),   // 1983-07-01
+    (489_024_000, 23),   // 1985-07-01
+    (567_993_600, 24),   // 1988-01-01
+    (631_152_000, 25),   // 1990-01-01
+    (662_688_000, 26),   // 1991-01-01
+    (709_948_800, 27),   // 1992-07-01
+    (741_484_800, 28),   // 1993-07-01
+    (773_020_800, 29),   // 1994-07-01
+    (820_454_400, 30),   // 1996-01-01
+    (867_715_200, 31),   // 1997-07-01
+    (915_148_800, 32),   // 1999-01-01
+    (1_136_073_600, 33), // 2006-01-01
+    (1_230_768_000, 34), // 2009-01-01
+    (1_341_100_800, 35), // 2012-07-01
+    (1_435_708_800, 36), // 2015-07-01
+    (1_483_228_800, 37), // 2017-01-01
+];
+
+/// TAI − GPS, fixed when GPS time started in 1980.
+const GPS_BEHIND_TAI: i64 = 19;
+
+// === impl TimeScale ===
+
+impl TimeScale {
+    /// Returns how far this scale is ahead of UTC at `utc`.
+    pub fn offset_from_utc(self, utc: OffsetDateTime) -> Duration {
+        let t = utc.unix_timestamp();
+        let i = LEAP_SECONDS.partition_point(|&(at, _)| at <= t);
+        let tai = LEAP_SECONDS[i.saturating_sub(1)].1;
+        match self {
+            TimeScale::Utc => Duration::ZERO,
+            TimeScale::Tai => Duration::seconds(tai),
+            TimeScale::Gps => Duration::seconds(tai - GPS_BEHIND_TAI),
+        }
+    }
+}
+
+// === impl LocalTime ===
+
+impl<F, P, C> LocalTime<F, P, C> {
+    /// Renders timestamps in `scale` rather than UTC, before the offset is
+    /// applied.
+    pub fn with_time_scale(self, scale: TimeScale) -> Self {
+        Self { scale, ..self }
+    }
+}
+
+impl LocalTime<StaticFormatDescription> {
+    /// Returns a formatter rendering International Atomic Time, e.g.
+    /// `2024-05-01T02:00:37.000 TAI`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    ///
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(LocalTime::tai());
+    /// # drop(collector);
+    /// ```
+    pub fn tai() -> Self {
+        Self::with_timezone(
+            format_description!(
+                "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3] TAI"
+            ),
+            (0, 0, 0),
+        )
+        .with_time_scale(TimeScale::Tai)
+    }
+
+    /// Returns a formatter rendering GPS time, e.g.
+    /// `2024-05-01T02:00:18.000 GPS`.
+    pub fn gps() -> Self {
+        Self::with_timezone(
+            format_description!(
+                "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3] GPS"
+            ),
+            (0, 0, 0),
+        )
+        .with_time_scale(TimeScale::Gps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TimeScale;
+    use crate::clock::Fixed;
+    use crate::LocalTime;
+    use time::{macros::datetime, Duration};
+
+    #[test]
+    fn test_offsets() {
+        let at = datetime!(2024-05-01 02:00 UTC);
+        assert_eq!(TimeScale::Utc.offset_from_utc(at), Duration::ZERO);
+        assert_eq!(TimeScale::Tai.offset_from_utc(at), Duration::seconds(37));
+        assert_eq!(TimeScale::Gps.offset_from_utc(at), Duration::seconds(18));
+        // The leap second at the end of 2016.
+        let before = datetime!(2016-12-31 23:59:59 UTC);
+        assert_eq!(
+            TimeScale::Tai.offset_from_utc(before),
+            Duration::seconds(36)
+        );
+        let before_1972 = datetime!(1970-01-01 0:00 UTC);
+        assert_eq!(
+            TimeScale::Tai.offset_from_utc(before_1972),
+            Duration::seconds(10)
+        );
+
+        let timer = LocalTime::gps().with_clock(Fixed(at));
+        assert_eq!(timer.timestamp(), "2024-05-01T02:00:18.000 GPS");
+    }
+}
@@ -0,0 +1,115 @@
+//! Locating the timestamps a [`LocalTime`] emitted at the start of raw log
+//! lines, e.g. for tail or merge tools built on this crate's formats.
+//!
+//! # Examples
+//!
+//! ```
+//! use clia_local_time::{scan, LocalTime};
+//! use time::macros::datetime;
+//!
+//! let timer = LocalTime::w3c((8, 0, 0)).decorated("[", "]");
+//! let line = "[2024-05-01 10:00:00]  INFO app: started";
+//! let extracted = scan::extract(&timer, line).expect("line should start with a timestamp");
+//! assert_eq!(extracted.at, datetime!(2024-05-01 02:00 UTC));
+//! assert_eq!(extracted.timestamp, "[2024-05-01 10:00:00]");
+//! assert_eq!(extracted.rest, "  INFO app: started");
+//! ```
+
+use time::{parsing::Parsable, OffsetDateTime};
+
+use crate::{offset::FixedOffset, LocalTime};
+
+/// A timestamp found at the start of a line by [`extract`].
+#[cfg_attr(docsrs, doc(cfg(feature = "parsing")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Extracted<'a> {
+    /// The instant the timestamp renders.
+    pub at: OffsetDateTime,
+    /// The timestamp, including its decoration.
+    pub timestamp: &'a str,
+    /// The rest of the line, starting right after the timestamp.
+    pub rest: &'a str,
+}
+
+/// Parses the timestamp `timer` wrote at the start of `line`, returning
+/// `None` if the line doesn't start with one.
+///
+/// With [`with_width`](LocalTime::with_width) the timestamp spans a fixed
+/// number of characters, and with a suffix, e.g. from
+/// [`decorated("[", "]")`](LocalTime::decorated), it ends at the first
+/// suffix that completes a valid timestamp. Otherwise it ends at the
+/// shortest run of words that parses, as timestamps are followed by a space
+/// in `fmt` output.
+pub fn extract<'a, F: Parsable, C>(
+    timer: &LocalTime<F, FixedOffset, C>,
+    line: &'a str,
+) -> Option<Extracted<'a>> {
+    let body = line.strip_prefix(timer.prefix.as_str())?;
+    let start = timer.prefix.len();
+    let ends: Box<dyn Iterator<Item = usize>> = if let Some(width) = timer.width {
+        let tag = if timer.source_tag { 2 } else { 0 };
+        let chars = width + tag;
+        let end = match body.char_indices().nth(chars) {
+            Some((i, _)) => i,
+            None if body.chars().count() == chars => body.len(),
+            None => return None,
+        };
+        Box::new(std::iter::once(start + end + timer.suffix.len()))
+    } else if !timer.suffix.is_empty() {
+        let suffix = timer.suffix.as_str();
+        Box::new(
+            body.match_indices(suffix)
+                .map(move |(i, _)| start + i + suffix.len()),
+        )
+    } else {
+        Box::new(
+            body.char_indices()
+                .filter(|&(_, c)| c == ' ')
+                .map(|(i, _)| i)
+                .chain([body.len()])
+                .map(move |i| start + i),
+        )
+    };
+
+    ends.filter(|&end| end <= line.len() && line.is_char_boundary(end))
+        .find_map(|end| {
+            let (timestamp, rest) = line.split_at(end);
+            let at = timer.parse(timestamp).ok()?;
+            Some(Extracted {
+                at,
+                timestamp,
+                rest,
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::extract;
+    use crate::LocalTime;
+    use time::macros::{datetime, format_description};
+
+    #[test]
+    fn test_extract() {
+        let at = datetime!(2024-05-01 02:00 UTC);
+        let timer = LocalTime::w3c((8, 0, 0));
+        let extracted = extract(&timer, "2024-05-01 10:00:00 INFO hello").unwrap();
+        assert_eq!((extracted.at, extracted.rest), (at, " INFO hello"));
+
+        let timer = LocalTime::w3c((8, 0, 0)).with_width(21).with_source_tag();
+        let extracted = extract(&timer, "2024-05-01 10:00:00   W INFO").unwrap();
+        assert_eq!((extracted.at, extracted.rest), (at, " INFO"));
+
+        let timer = LocalTime::with_timezone(
+            format_description!("[hour]:[minute] [day]/[month]/[year]"),
+            (0, 0, 0),
+        )
+        .decorated("<", ">");
+        let extracted = extract(&timer, "<02:00 01/05/2024> <x>").unwrap();
+        assert_eq!((extracted.at, extracted.rest), (at, " <x>"));
+
+        assert_eq!(extract(&LocalTime::rfc_3339(), "INFO hello"), None);
+        assert_eq!(extract(&timer, "<02:00> 01/05/2024"), None);
+    }
+}
@@ -0,0 +1,253 @@
+//! Wall-clock scheduling in the timer's zone.
+
+use std::time::Duration;
+use time::{error::ComponentRange, Date, Month, OffsetDateTime, PrimitiveDateTime, Time, Weekday};
+
+use crate::{clock::ClockSource, offset::OffsetProvider, LocalTime};
+
+/// A recurring wall-clock boundary, e.g. for rotating log files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Boundary {
+    /// The start of every hour.
+    Hourly,
+    /// Midnight.
+    Daily,
+    /// Midnight at the start of the given weekday, e.g. Monday 00:00.
+    Weekly(Weekday),
+    /// Midnight at the start of the first day of the month.
+    Monthly,
+}
+
+// === impl LocalTime ===
+
+impl<F, P: OffsetProvider, C: ClockSource> LocalTime<F, P, C> {
+    /// Returns the next instant at which the wall clock in the configured
+    /// timezone reads `hour:minute`, e.g. `(0, 5)` for "at 00:05 local".
+    ///
+    /// If it is exactly `hour:minute` right now, the next day's is returned.
+    /// The offset is looked up at the returned instant, so days around a
+    /// daylight saving transition may be 23 or 25 hours long. A time the
+    /// transition skips resolves to the instant as far past it, e.g. 02:30
+    /// to 03:30 when clocks go from 02:00 to 03:00, and a repeated time to
+    /// its first occurrence.
+    ///
+    /// Returns an error if `hour` or `minute` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    ///
+    /// let timer = LocalTime::rfc_3339();
+    /// let next = timer.next_local_instant(0, 5).expect("00:05 is a valid time");
+    /// assert_eq!((next.hour(), next.minute()), (0, 5));
+    /// ```
+    pub fn next_local_instant(
+        &self,
+        hour: u8,
+        minute: u8,
+    ) -> Result<OffsetDateTime, ComponentRange> {
+        let now = self.now();
+        Ok(next_after(
+            now,
+            Time::from_hms(hour, minute, 0)?,
+            &self.offset,
+        ))
+    }
+
+    /// Returns how long it is until the wall clock in the configured timezone
+    /// next reads `hour:minute`.
+    ///
+    /// See [`LocalTime::next_local_instant`] for details.
+    pub fn duration_until_local(&self, hour: u8, minute: u8) -> Result<Duration, ComponentRange> {
+        let at = Time::from_hms(hour, minute, 0)?;
+        let now = self.now();
+        Ok((next_after(now, at, &self.offset) - now).unsigned_abs())
+    }
+
+    /// Returns the next instant at which `boundary` is crossed in the
+    /// configured timezone, e.g. the coming Monday 00:00 local for
+    /// `Boundary::Weekly(Weekday::Monday)`.
+    ///
+    /// Daily, weekly and monthly boundaries are resolved like
+    /// [`LocalTime::next_local_instant`]; hourly ones are the next whole hour
+    /// of the local time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::{Boundary, LocalTime};
+    /// use time::Weekday;
+    ///
+    /// let timer = LocalTime::rfc_3339();
+    /// let next = timer.next_boundary(Boundary::Weekly(Weekday::Monday));
+    /// assert_eq!(next.weekday(), Weekday::Monday);
+    /// ```
+    pub fn next_boundary(&self, boundary: Boundary) -> OffsetDateTime {
+        boundary.next_after(self.now(), &self.offset)
+    }
+}
+
+// === impl Boundary ===
+
+impl Boundary {
+    /// The first boundary after `now`, a time in `provider`'s zone.
+    pub(crate) fn next_after(
+        self,
+        now: OffsetDateTime,
+        provider: &impl OffsetProvider,
+    ) -> OffsetDateTime {
+        let today = now.date();
+        let date = match self {
+            Boundary::Hourly => {
+                let next = now.truncate_to_hour() + time::Duration::HOUR;
+                return next.to_offset(provider.offset_at(next));
+            }
+            Boundary::Daily => today.next_day(),
+            Boundary::Weekly(weekday) => {
+                let days = (7 + weekday.number_days_from_monday()
+                    - now.weekday().number_days_from_monday())
+                    % 7;
+                let days = if days == 0 { 7 } else { days };
+                today.checked_add(time::Duration::days(days.into()))
+            }
+            Boundary::Monthly => {
+                let (year, month) = match now.month() {
+                    Month::December => (now.year() + 1, Month::January),
+                    month => (now.year(), month.next()),
+                };
+                Date::from_calendar_date(year, month, 1).ok()
+            }
+        };
+        match date {
+            Some(date) => assume_local(date.midnight(), provider),
+            None => now + time::Duration::DAY,
+        }
+    }
+}
+
+/// The first instant after `now` whose wall clock reads `at`.
+fn next_after(now: OffsetDateTime, at: Time, provider: &impl OffsetProvider) -> OffsetDateTime {
+    let mut date = now.date();
+    // A skipped `at` can resolve to an instant before `now` on today's date,
+    // so try the next days too.
+    for _ in 0..3 {
+        let next = assume_local(PrimitiveDateTime::new(date, at), provider);
+        if next > now {
+            return next;
+        }
+        match date.next_day() {
+            Some(next_day) => date = next_day,
+            None => break,
+        }
+    }
+    now + time::Duration::DAY
+}
+
+/// The instant at which the wall clock in `provider`'s zone reads `wall`.
+///
+/// Times skipped by a transition resolve with the offset before it, and
+/// repeated times to the earlier instant, as [`TimezoneSpec::assume_zone`]
+/// does.
+///
+/// [`TimezoneSpec::assume_zone`]: crate::TimezoneSpec::assume_zone
+fn assume_local(wall: PrimitiveDateTime, provider: &impl OffsetProvider) -> OffsetDateTime {
+    let utc = wall.assume_utc();
+    // The offsets around `wall`, which differ near a transition.
+    let before = wall.assume_offset(provider.offset_at(utc - time::Duration::DAY));
+    let after = wall.assume_offset(provider.offset_at(utc + time::Duration::DAY));
+    let valid = |dt: OffsetDateTime| provider.offset_at(dt) == dt.offset();
+    let at = match (valid(before), valid(after)) {
+        (true, true) => before.min(after),
+        (false, true) => after,
+        _ => before,
+    };
+    at.to_offset(provider.offset_at(at))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{next_after, Boundary};
+    use crate::offset::FixedOffset;
+    use time::macros::{datetime, offset, time};
+    use time::Weekday;
+
+    const CST: FixedOffset = FixedOffset(offset!(+8));
+
+    #[test]
+    fn test_next_after() {
+        let now = datetime!(2024-05-01 10:00:00 +08:00);
+        assert_eq!(
+            next_after(now, time!(10:30), &CST),
+            datetime!(2024-05-01 10:30:00 +08:00)
+        );
+        assert_eq!(
+            next_after(now, time!(00:05), &CST),
+            datetime!(2024-05-02 00:05:00 +08:00)
+        );
+        assert_eq!(
+            next_after(now, time!(10:00), &CST),
+            datetime!(2024-05-02 10:00:00 +08:00)
+        );
+    }
+
+    #[test]
+    fn test_boundaries() {
+        // A Wednesday.
+        let now = datetime!(2024-12-25 10:30:00 +08:00);
+        assert_eq!(
+            Boundary::Hourly.next_after(now, &CST),
+            datetime!(2024-12-25 11:00:00 +08:00)
+        );
+        assert_eq!(
+            Boundary::Daily.next_after(now, &CST),
+            datetime!(2024-12-26 00:00:00 +08:00)
+        );
+        assert_eq!(
+            Boundary::Weekly(Weekday::Monday).next_after(now, &CST),
+            datetime!(2024-12-30 00:00:00 +08:00)
+        );
+        assert_eq!(
+            Boundary::Weekly(Weekday::Wednesday).next_after(now, &CST),
+            datetime!(2025-01-01 00:00:00 +08:00)
+        );
+        assert_eq!(
+            Boundary::Monthly.next_after(now, &CST),
+            datetime!(2025-01-01 00:00:00 +08:00)
+        );
+    }
+
+    #[cfg(feature = "jiff")]
+    #[test]
+    fn test_dst() {
+        use crate::jiff::ZoneOffset;
+
+        // Berlin moves from 02:00 +01:00 to 03:00 +02:00 on 2024-03-31.
+        let berlin = ZoneOffset::new("Europe/Berlin").unwrap();
+        let now = datetime!(2024-03-30 03:00 +1);
+        let next = next_after(now, time!(03:00), &berlin);
+        assert_eq!(next, datetime!(2024-03-31 03:00 +2));
+        assert_eq!(next - now, time::Duration::hours(23));
+        assert_eq!(
+            next_after(now, time!(02:30), &berlin),
+            datetime!(2024-03-31 03:30 +2)
+        );
+        assert_eq!(
+            Boundary::Hourly.next_after(datetime!(2024-03-31 01:30 +1), &berlin),
+            datetime!(2024-03-31 03:00 +2)
+        );
+        // And back from 03:00 +02:00 to 02:00 +01:00 on 2024-10-27.
+        let now = datetime!(2024-10-26 12:00 +2);
+        assert_eq!(
+            Boundary::Daily.next_after(now, &berlin),
+            datetime!(2024-10-27 00:00 +2)
+        );
+        let next = next_after(now, time!(12:00), &berlin);
+        assert_eq!(next, datetime!(2024-10-27 12:00 +1));
+        assert_eq!(next - now, time::Duration::hours(25));
+        assert_eq!(
+            next_after(now, time!(02:30), &berlin),
+            datetime!(2024-10-27 02:30 +2)
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! Sharing one configured timer across several layers.
+//!
+//! `&LocalTime<F>` already implements [`FormatTime`] through
+//! `tracing-subscriber`'s blanket impl for references, but `fmt::Layer`
+//! requires `'static` timers. Coherence rules forbid implementing the foreign
+//! [`FormatTime`] trait for `Arc<LocalTime<F>>` directly, so [`SharedTime`]
+//! wraps the `Arc` instead.
+
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use time::formatting::Formattable;
+use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
+
+use crate::{
+    clock::{ClockSource, SystemClock},
+    offset::{FixedOffset, OffsetProvider},
+    LocalTime,
+};
+
+/// A [`LocalTime`] behind an [`Arc`], cloned without cloning its format.
+///
+/// Returned by [`LocalTime::shared`].
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::LocalTime;
+/// use tracing_subscriber::{fmt, layer::SubscriberExt};
+///
+/// let timer = LocalTime::rfc_3339().shared();
+/// let subscriber = tracing_subscriber::registry()
+///     .with(fmt::layer().with_timer(timer.clone()))
+///     .with(fmt::layer().with_timer(timer).with_writer(std::io::stderr));
+/// # drop(subscriber);
+/// ```
+#[derive(Debug)]
+pub struct SharedTime<F, P = FixedOffset, C = SystemClock>(Arc<LocalTime<F, P, C>>);
+
+// === impl SharedTime ===
+
+impl<F, P, C> Clone for SharedTime<F, P, C> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<F, P, C> Deref for SharedTime<F, P, C> {
+    type Target = LocalTime<F, P, C>;
+
+    fn deref(&self) -> &LocalTime<F, P, C> {
+        &self.0
+    }
+}
+
+impl<F, P, C> From<Arc<LocalTime<F, P, C>>> for SharedTime<F, P, C> {
+    fn from(timer: Arc<LocalTime<F, P, C>>) -> Self {
+        Self(timer)
+    }
+}
+
+impl<F: Formattable, P: OffsetProvider, C: ClockSource> FormatTime for SharedTime<F, P, C> {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        self.0.format_time(w)
+    }
+}
+
+// === impl LocalTime ===
+
+impl<F, P, C> LocalTime<F, P, C> {
+    /// Moves this timer behind an [`Arc`], so one configuration can be handed
+    /// to several layers.
+    pub fn shared(self) -> SharedTime<F, P, C> {
+        SharedTime(Arc::new(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{testing::render_now, LocalTime};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_shared() {
+        let timer = LocalTime::rfc_3339().decorated("<", ">");
+        assert!(render_now(&timer).starts_with('<'));
+
+        let shared = timer.shared();
+        let other = shared.clone();
+        assert!(Arc::ptr_eq(&shared.0, &other.0));
+        assert!(render_now(&other).ends_with('>'));
+
+        let system = LocalTime::system(time::format_description::well_known::Rfc3339);
+        assert!(!render_now(&system.shared()).is_empty());
+    }
+}
@@ -0,0 +1,146 @@
+//! Annotating timestamps with the time elapsed since the enclosing span
+//! opened.
+//!
+//! [`SpanTimingLayer`] records when each span opens in its extensions and,
+//! installed before the `fmt` layers, looks up the enclosing span of every
+//! event, so [`WithSpanElapsed`] can print how long it has been open.
+
+use std::cell::Cell;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use tracing::{span, Event, Subscriber};
+use tracing_subscriber::{
+    fmt::{format::Writer, time::FormatTime},
+    layer::{Context, Layer},
+    registry::LookupSpan,
+};
+
+use crate::LocalTime;
+
+thread_local! {
+    /// How long the enclosing span of the latest event on this thread had
+    /// been open, if it had one.
+    static SPAN_ELAPSED: Cell<Option<Duration>> = const { Cell::new(None) };
+}
+
+/// A [`Layer`] recording when spans open, for [`WithSpanElapsed`] timers.
+///
+/// Requires a subscriber storing span data, such as
+/// `tracing_subscriber::registry()`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SpanTimingLayer;
+
+/// The instant a span opened, stored in its extensions.
+struct Opened(Instant);
+
+/// Formats the time of an inner timer followed by the time elapsed since the
+/// enclosing span of the event opened, e.g.
+/// `2024-05-01 10:00:00.123 (span +12.3ms)`. Events outside any span get no
+/// annotation.
+///
+/// Needs a [`SpanTimingLayer`] installed before the `fmt` layers. Returned
+/// by [`LocalTime::with_span_elapsed`].
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::{LocalTime, SpanTimingLayer};
+/// use tracing_subscriber::{fmt, layer::SubscriberExt};
+///
+/// let subscriber = tracing_subscriber::registry()
+///     // Looks up the enclosing span, so it goes first.
+///     .with(SpanTimingLayer)
+///     .with(fmt::layer().with_timer(LocalTime::rfc_3339().with_span_elapsed()));
+/// # drop(subscriber);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct WithSpanElapsed<T> {
+    timer: T,
+}
+
+// === impl SpanTimingLayer ===
+
+impl<S> Layer<S> for SpanTimingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Opened(Instant::now()));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let elapsed = ctx.event_span(event).and_then(|span| {
+            span.extensions()
+                .get::<Opened>()
+                .map(|opened| opened.0.elapsed())
+        });
+        SPAN_ELAPSED.with(|last| last.set(elapsed));
+    }
+}
+
+// === impl WithSpanElapsed ===
+
+impl<T> WithSpanElapsed<T> {
+    /// Wraps `timer`.
+    pub fn new(timer: T) -> Self {
+        Self { timer }
+    }
+}
+
+impl<T: FormatTime> FormatTime for WithSpanElapsed<T> {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        self.timer.format_time(w)?;
+        match SPAN_ELAPSED.with(Cell::get) {
+            Some(elapsed) => write!(w, " (span +{:.1?})", elapsed),
+            None => Ok(()),
+        }
+    }
+}
+
+// === impl LocalTime ===
+
+impl<F, P, C> LocalTime<F, P, C> {
+    /// Appends the time elapsed since the enclosing span opened to every
+    /// timestamp, e.g. `10:00:00.123 (span +12.3ms)`, which makes
+    /// request-scoped logs easy to follow. See [`WithSpanElapsed`].
+    pub fn with_span_elapsed(self) -> WithSpanElapsed<Self> {
+        WithSpanElapsed::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SpanTimingLayer, WithSpanElapsed};
+    use crate::testing::Capture;
+    use std::time::Duration;
+    use tracing_subscriber::{fmt, layer::SubscriberExt};
+
+    #[test]
+    fn test_span_elapsed() {
+        let capture = Capture::default();
+        let writer = capture.clone();
+        let subscriber = tracing_subscriber::registry().with(SpanTimingLayer).with(
+            fmt::layer()
+                .with_ansi(false)
+                .with_timer(WithSpanElapsed::new(()))
+                .with_writer(move || writer.clone()),
+        );
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("request").in_scope(|| {
+                std::thread::sleep(Duration::from_millis(5));
+                tracing::info!("inside");
+            });
+            tracing::info!("outside");
+        });
+
+        let output = capture.output();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let elapsed = lines[0].split("(span +").nth(1).unwrap();
+        assert!(elapsed.starts_with(char::is_numeric), "{}", lines[0]);
+        assert!(!lines[1].contains("span +"), "{}", lines[1]);
+    }
+}
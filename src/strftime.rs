@@ -0,0 +1,153 @@
+//! `strftime`-style format strings, as used by log4rs, env_logger and chrono.
+//!
+//! The `time` crate already understands the POSIX [`strftime`] specifiers.
+//! This module adds the chrono extensions commonly found in existing logging
+//! configs on top of it:
+//!
+//! | Specifier | Output                                     |
+//! |-----------|--------------------------------------------|
+//! | `%.f`     | `.` followed by the fractional second      |
+//! | `%.3f`    | `.` followed by 3 digits (1 to 9 accepted) |
+//! | `%3f`     | 3 digits, no dot (1 to 9 accepted)         |
+//! | `%f`      | nanoseconds, 9 digits                      |
+//! | `%:z`     | offset with a colon, e.g. `+08:00`         |
+//!
+//! [`strftime`]: https://man7.org/linux/man-pages/man3/strftime.3.html
+
+use time::error::InvalidFormatDescription;
+use time::format_description::{
+    modifier::{OffsetHour, OffsetMinute, Subsecond, SubsecondDigits},
+    parse_strftime_owned, Component, OwnedFormatItem,
+};
+
+use crate::LocalTime;
+
+/// Converts a `strftime` pattern into `time` format items.
+///
+/// # Examples
+///
+/// ```
+/// let format = clia_local_time::strftime::parse("%Y-%m-%d %H:%M:%S%.3f")
+///     .expect("pattern should be valid!");
+/// # drop(format);
+/// ```
+pub fn parse(pattern: &str) -> Result<OwnedFormatItem, InvalidFormatDescription> {
+    let bytes = pattern.as_bytes();
+    let mut items = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'%' {
+            i += 1;
+            continue;
+        }
+        match extension(&bytes[i + 1..]) {
+            Some((item, len)) => {
+                if start < i {
+                    items.push(parse_strftime_owned(&pattern[start..i])?);
+                }
+                items.push(item);
+                i += 1 + len;
+                start = i;
+            }
+            // Leave the specifier (including `%%`) to `time`'s own parser.
+            None => i += 2,
+        }
+    }
+    if start < bytes.len() {
+        items.push(parse_strftime_owned(&pattern[start..])?);
+    }
+    Ok(OwnedFormatItem::Compound(items.into_boxed_slice()))
+}
+
+/// Recognizes a chrono extension following a `%`, returning its format item
+/// and the number of bytes it spans.
+fn extension(spec: &[u8]) -> Option<(OwnedFormatItem, usize)> {
+    match spec {
+        [b'.', b'f', ..] => Some((dotted(SubsecondDigits::OneOrMore), 2)),
+        [b'.', n @ b'1'..=b'9', b'f', ..] => Some((dotted(digits(*n)), 3)),
+        [n @ b'1'..=b'9', b'f', ..] => Some((subsecond(digits(*n)), 2)),
+        [b'f', ..] => Some((subsecond(SubsecondDigits::Nine), 1)),
+        [b':', b'z', ..] => {
+            let mut hour = OffsetHour::default();
+            hour.sign_is_mandatory = true;
+            let items = vec![
+                Component::OffsetHour(hour).into(),
+                OwnedFormatItem::StringLiteral(":".into()),
+                Component::OffsetMinute(OffsetMinute::default()).into(),
+            ];
+            Some((OwnedFormatItem::Compound(items.into_boxed_slice()), 2))
+        }
+        _ => None,
+    }
+}
+
+fn digits(n: u8) -> SubsecondDigits {
+    match n {
+        b'1' => SubsecondDigits::One,
+        b'2' => SubsecondDigits::Two,
+        b'3' => SubsecondDigits::Three,
+        b'4' => SubsecondDigits::Four,
+        b'5' => SubsecondDigits::Five,
+        b'6' => SubsecondDigits::Six,
+        b'7' => SubsecondDigits::Seven,
+        b'8' => SubsecondDigits::Eight,
+        _ => SubsecondDigits::Nine,
+    }
+}
+
+fn subsecond(digits: SubsecondDigits) -> OwnedFormatItem {
+    let mut modifier = Subsecond::default();
+    modifier.digits = digits;
+    Component::Subsecond(modifier).into()
+}
+
+fn dotted(digits: SubsecondDigits) -> OwnedFormatItem {
+    let items = vec![
+        OwnedFormatItem::StringLiteral(".".into()),
+        subsecond(digits),
+    ];
+    OwnedFormatItem::Compound(items.into_boxed_slice())
+}
+
+// === impl LocalTime ===
+
+impl LocalTime<OwnedFormatItem> {
+    /// Returns a formatter for a `strftime` pattern and timezone setting.
+    ///
+    /// See the [module documentation](crate::strftime) for the supported
+    /// chrono extensions.
+    ///
+    /// Timezone format: (tz_hours, tz_minutes, tz_seconds)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    ///
+    /// let timer = LocalTime::strftime("%Y-%m-%d %H:%M:%S%.3f", (8, 0, 0))
+    ///     .expect("pattern should be valid!");
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(timer);
+    /// # drop(collector);
+    /// ```
+    pub fn strftime(pattern: &str, tz_hms: (i8, i8, i8)) -> Result<Self, InvalidFormatDescription> {
+        parse(pattern).map(|format| Self::with_timezone(format, tz_hms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use time::macros::datetime;
+
+    #[test]
+    fn test_chrono_extensions() {
+        let dt = datetime!(2024-05-01 10:00:00.123456789 +08:00);
+        let render = |pattern| dt.format(&parse(pattern).unwrap()).unwrap();
+        assert_eq!(render("%Y-%m-%d %H:%M:%S%.3f"), "2024-05-01 10:00:00.123");
+        assert_eq!(render("%H:%M:%S,%6f%:z"), "10:00:00,123456+08:00");
+        assert_eq!(render("%f %%"), "123456789 %");
+        assert!(parse("%Q").is_err());
+    }
+}
@@ -0,0 +1,75 @@
+//! The [`FormatTime`] impls of [`LocalTime`] and [`UtcTime`], one per
+//! supported version of tracing-subscriber.
+//!
+//! Each forwards to the version-independent `write_now` methods, so
+//! supporting a new tracing-subscriber release means adding a feature and an
+//! impl here, without changing `LocalTime` itself.
+//!
+//! [`FormatTime`]: tracing_subscriber::fmt::time::FormatTime
+
+use std::fmt;
+
+use time::formatting::Formattable;
+
+use crate::{clock::ClockSource, offset::OffsetProvider, LocalTime, UtcTime};
+
+// === tracing-subscriber 0.3 ===
+
+#[cfg(feature = "subscriber-03")]
+impl<F, P, C> tracing_subscriber::fmt::time::FormatTime for LocalTime<F, P, C>
+where
+    F: Formattable,
+    P: OffsetProvider,
+    C: ClockSource,
+{
+    fn format_time(&self, w: &mut tracing_subscriber::fmt::format::Writer<'_>) -> fmt::Result {
+        self.write_styled(w)
+    }
+}
+
+#[cfg(feature = "subscriber-03")]
+impl<F: Formattable, C: ClockSource> tracing_subscriber::fmt::time::FormatTime for UtcTime<F, C> {
+    fn format_time(&self, w: &mut tracing_subscriber::fmt::format::Writer<'_>) -> fmt::Result {
+        self.write_now(w)
+    }
+}
+
+// === tracing-subscriber 0.2 ===
+
+#[cfg(feature = "compat-0-2")]
+impl<F, P, C> tracing_subscriber_0_2::fmt::time::FormatTime for LocalTime<F, P, C>
+where
+    F: Formattable,
+    P: OffsetProvider,
+    C: ClockSource,
+{
+    fn format_time(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        self.write_now(&mut { w })
+    }
+}
+
+#[cfg(feature = "compat-0-2")]
+impl<F: Formattable, C: ClockSource> tracing_subscriber_0_2::fmt::time::FormatTime
+    for UtcTime<F, C>
+{
+    fn format_time(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        self.write_now(&mut { w })
+    }
+}
+
+#[cfg(all(test, feature = "compat-0-2"))]
+mod tests {
+    use tracing_subscriber_0_2::fmt::time::FormatTime;
+
+    use crate::clock::Fixed;
+    use crate::LocalTime;
+    use time::macros::datetime;
+
+    #[test]
+    fn test_format_time_0_2() {
+        let timer = LocalTime::w3c((8, 0, 0)).with_clock(Fixed(datetime!(2024-05-01 02:00 UTC)));
+        let mut s = String::new();
+        FormatTime::format_time(&timer, &mut s).unwrap();
+        assert_eq!(s, "2024-05-01 10:00:00");
+    }
+}
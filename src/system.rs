@@ -0,0 +1,340 @@
+//! Platform backends for detecting the system's local offset.
+//!
+//! On Unix, `time` detects the offset with `localtime_r`, which reads the
+//! environment and races with `setenv` in other threads, and recent `time`
+//! releases no longer refuse to run while other threads exist. These
+//! backends query the platform where it offers a direct answer, then the
+//! named zone iana-time-zone detects with the `iana-time-zone` feature, and
+//! only then fall back to `time`, while doing so is known to be sound.
+
+use time::{OffsetDateTime, UtcOffset};
+
+use crate::offset::SystemOffset;
+use crate::LocalTime;
+
+/// The local offset in effect at `utc`, if the platform can tell.
+pub(crate) fn local_offset_at(utc: OffsetDateTime) -> Option<UtcOffset> {
+    platform::local_offset_at(utc)
+        .or_else(|| named::local_offset_at(utc))
+        .or_else(|| time_local_offset_at(utc))
+}
+
+/// `time`'s own detection, skipped while it could race with `setenv`.
+fn time_local_offset_at(utc: OffsetDateTime) -> Option<UtcOffset> {
+    if !detection_is_sound() {
+        return None;
+    }
+    UtcOffset::local_offset_at(utc).ok()
+}
+
+/// Whether reading the environment can't race with `setenv`: the process is
+/// known to be single-threaded.
+#[cfg(all(
+    unix,
+    not(any(target_os = "macos", target_os = "illumos", target_os = "netbsd"))
+))]
+pub(crate) fn detection_is_sound() -> bool {
+    num_threads::is_single_threaded() == Some(true)
+}
+
+/// The environment is thread-safe here, or not read by detection.
+#[cfg(not(all(
+    unix,
+    not(any(target_os = "macos", target_os = "illumos", target_os = "netbsd"))
+)))]
+pub(crate) fn detection_is_sound() -> bool {
+    true
+}
+
+#[cfg(feature = "iana-time-zone")]
+mod named {
+    use std::sync::OnceLock;
+
+    use ::jiff::{tz::TimeZone, Timestamp};
+    use time::{OffsetDateTime, UtcOffset};
+
+    /// The zone named by iana-time-zone, looked up on first use.
+    static ZONE: OnceLock<Option<TimeZone>> = OnceLock::new();
+
+    /// Looks the offset up in the tz database rules of the system zone.
+    ///
+    /// `TZ` overrides the system zone but not the name iana-time-zone
+    /// reports, so when it's set this defers to the platform backend.
+    pub(super) fn local_offset_at(utc: OffsetDateTime) -> Option<UtcOffset> {
+        if std::env::var_os("TZ").is_some() {
+            return None;
+        }
+        let zone = ZONE
+            .get_or_init(|| TimeZone::get(&zone_name()?).ok())
+            .as_ref()?;
+        let at = Timestamp::from_second(utc.unix_timestamp()).ok()?;
+        UtcOffset::from_whole_seconds(zone.to_offset(at).seconds()).ok()
+    }
+
+    pub(super) fn zone_name() -> Option<String> {
+        iana_time_zone::get_timezone().ok()
+    }
+}
+
+#[cfg(not(feature = "iana-time-zone"))]
+mod named {
+    use time::{OffsetDateTime, UtcOffset};
+
+    pub(super) fn local_offset_at(_utc: OffsetDateTime) -> Option<UtcOffset> {
+        None
+    }
+
+    pub(super) fn zone_name() -> Option<String> {
+        None
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use time::{OffsetDateTime, UtcOffset};
+    use windows_sys::Win32::System::Time::{
+        GetDynamicTimeZoneInformation, DYNAMIC_TIME_ZONE_INFORMATION,
+    };
+
+    const TIME_ZONE_ID_STANDARD: u32 = 1;
+    const TIME_ZONE_ID_DAYLIGHT: u32 = 2;
+    const TIME_ZONE_ID_INVALID: u32 = u32::MAX;
+
+    /// Reads the current bias, including daylight saving time, with
+    /// `GetDynamicTimeZoneInformation`.
+    ///
+    /// Windows only reports the bias in effect now, so other instants are
+    /// left to `time`.
+    pub(super) fn local_offset_at(utc: OffsetDateTime) -> Option<UtcOffset> {
+        if (OffsetDateTime::now_utc() - utc).abs() > time::Duration::MINUTE {
+            return None;
+        }
+        // SAFETY: the struct is plain data, for which all zeroes is valid, and
+        // the call only writes into it.
+        let (id, info) = unsafe {
+            let mut info: DYNAMIC_TIME_ZONE_INFORMATION = std::mem::zeroed();
+            (GetDynamicTimeZoneInformation(&mut info), info)
+        };
+        // Biases are in minutes west of UTC.
+        let bias = match id {
+            TIME_ZONE_ID_INVALID => return None,
+            TIME_ZONE_ID_STANDARD => info.Bias + info.StandardBias,
+            TIME_ZONE_ID_DAYLIGHT => info.Bias + info.DaylightBias,
+            _ => info.Bias,
+        };
+        UtcOffset::from_whole_seconds(-bias * 60).ok()
+    }
+
+    /// Windows zone names, e.g. `China Standard Time`, aren't IANA
+    /// identifiers, so none is reported.
+    pub(super) fn zone_name() -> Option<String> {
+        None
+    }
+}
+
+/// The system's zone identifier, e.g. `Asia/Shanghai`, if the platform
+/// reports one.
+pub(crate) fn zone_name() -> Option<String> {
+    platform::zone_name().or_else(named::zone_name)
+}
+
+#[cfg(target_vendor = "apple")]
+mod platform {
+    use std::ffi::{c_char, c_void, CStr};
+    use std::sync::atomic::{AtomicI64, Ordering};
+
+    use time::{OffsetDateTime, UtcOffset};
+
+    type CFTimeZoneRef = *const c_void;
+    type CFStringRef = *const c_void;
+    type CFIndex = isize;
+
+    const K_CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+    /// Seconds between the Unix epoch and the CoreFoundation epoch, 2001-01-01.
+    const CF_EPOCH: f64 = 978_307_200.0;
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFTimeZoneCopySystem() -> CFTimeZoneRef;
+        fn CFTimeZoneResetSystem();
+        fn CFTimeZoneGetSecondsFromGMT(tz: CFTimeZoneRef, at: f64) -> f64;
+        fn CFTimeZoneGetName(tz: CFTimeZoneRef) -> CFStringRef;
+        fn CFStringGetCString(s: CFStringRef, buf: *mut c_char, len: CFIndex, encoding: u32) -> u8;
+        fn CFRelease(cf: *const c_void);
+    }
+
+    /// The Unix second the cached system zone was last reset in.
+    static LAST_RESET: AtomicI64 = AtomicI64::new(i64::MIN);
+
+    /// Copies the system zone, at most once a second dropping
+    /// CoreFoundation's cached copy so changes to the system timezone are
+    /// picked up.
+    fn with_system_zone<T>(f: impl FnOnce(CFTimeZoneRef) -> Option<T>) -> Option<T> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        if LAST_RESET.swap(now, Ordering::Relaxed) != now {
+            // SAFETY: takes no arguments and only clears a cache.
+            unsafe { CFTimeZoneResetSystem() };
+        }
+        // SAFETY: the copy is owned by us and released below, after its last
+        // use.
+        unsafe {
+            let tz = CFTimeZoneCopySystem();
+            if tz.is_null() {
+                return None;
+            }
+            let result = f(tz);
+            CFRelease(tz);
+            result
+        }
+    }
+
+    /// Reads the offset at `utc` from `CFTimeZoneCopySystem`, which knows the
+    /// zone's full history.
+    pub(super) fn local_offset_at(utc: OffsetDateTime) -> Option<UtcOffset> {
+        let at = utc.unix_timestamp() as f64 - CF_EPOCH;
+        // SAFETY: `tz` is a live time zone.
+        let seconds = with_system_zone(|tz| Some(unsafe { CFTimeZoneGetSecondsFromGMT(tz, at) }))?;
+        UtcOffset::from_whole_seconds(seconds as i32).ok()
+    }
+
+    pub(super) fn zone_name() -> Option<String> {
+        with_system_zone(|tz| {
+            let mut buf = [0 as c_char; 256];
+            // SAFETY: `tz` is a live time zone, the name it returns isn't
+            // owned by us, and `buf` holds `buf.len()` bytes.
+            let ok = unsafe {
+                let name = CFTimeZoneGetName(tz);
+                !name.is_null()
+                    && CFStringGetCString(
+                        name,
+                        buf.as_mut_ptr(),
+                        buf.len() as CFIndex,
+                        K_CF_STRING_ENCODING_UTF8,
+                    ) != 0
+            };
+            if !ok {
+                return None;
+            }
+            // SAFETY: `CFStringGetCString` wrote a nul-terminated string.
+            let name = unsafe { CStr::from_ptr(buf.as_ptr()) };
+            name.to_str().ok().map(str::to_string)
+        })
+    }
+}
+
+#[cfg(all(unix, not(target_vendor = "apple")))]
+mod platform {
+    use std::ffi::OsString;
+    use std::sync::{Mutex, OnceLock, PoisonError};
+
+    use time::{OffsetDateTime, UtcOffset};
+
+    use crate::offset::OffsetProvider;
+    use crate::tzif::Tzif;
+
+    /// The zone in `/etc/localtime`, read on first use.
+    static LOCALTIME: OnceLock<Option<Tzif>> = OnceLock::new();
+
+    /// The zone `TZ` named when it was last read, and that value.
+    static FROM_TZ: Mutex<Option<(OsString, Option<Tzif>)>> = Mutex::new(None);
+
+    /// Looks the offset up in the zone `TZ` names, or in `/etc/localtime`
+    /// when it's unset, without calling `localtime_r`, which races with
+    /// `setenv` while other threads run.
+    ///
+    /// `TZ` is re-read on each call, and its zone whenever it changes.
+    pub(super) fn local_offset_at(utc: OffsetDateTime) -> Option<UtcOffset> {
+        let Some(tz) = std::env::var_os("TZ") else {
+            let zone = LOCALTIME.get_or_init(|| Tzif::system().ok()).as_ref()?;
+            return Some(zone.offset_at(utc));
+        };
+        let mut cached = FROM_TZ.lock().unwrap_or_else(PoisonError::into_inner);
+        if cached.as_ref().is_none_or(|(value, _)| *value != tz) {
+            let zone = tz.to_str().and_then(|tz| Tzif::from_tz(tz).ok());
+            *cached = Some((tz, zone));
+        }
+        let (_, zone) = cached.as_ref()?;
+        Some(zone.as_ref()?.offset_at(utc))
+    }
+
+    pub(super) fn zone_name() -> Option<String> {
+        None
+    }
+}
+
+#[cfg(not(any(windows, unix)))]
+mod platform {
+    use time::{OffsetDateTime, UtcOffset};
+
+    pub(super) fn local_offset_at(_utc: OffsetDateTime) -> Option<UtcOffset> {
+        None
+    }
+
+    pub(super) fn zone_name() -> Option<String> {
+        None
+    }
+}
+
+// === impl LocalTime ===
+
+impl<F> LocalTime<F, SystemOffset> {
+    /// Returns a formatter that renders timestamps in the system's local
+    /// offset, following changes to it, e.g. daylight saving transitions.
+    ///
+    /// This is a shorthand for [`LocalTime::with_provider`] with a
+    /// [`SystemOffset`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use time::format_description::well_known::Rfc3339;
+    ///
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(LocalTime::system(Rfc3339));
+    /// # drop(collector);
+    /// ```
+    pub fn system(format: F) -> Self
+    where
+        F: time::formatting::Formattable,
+    {
+        LocalTime::with_provider(format, SystemOffset::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::OffsetDateTime;
+
+    #[test]
+    fn test_local_offset() {
+        let now = OffsetDateTime::now_utc();
+        if let Some(offset) = super::local_offset_at(now) {
+            assert!(offset.whole_hours().abs() <= 14);
+        }
+    }
+
+    #[cfg(all(
+        unix,
+        not(any(target_os = "macos", target_os = "illumos", target_os = "netbsd"))
+    ))]
+    #[test]
+    fn test_time_fallback_guarded() {
+        let (stop, wait) = std::sync::mpsc::channel::<()>();
+        let thread = std::thread::spawn(move || wait.recv());
+        assert_eq!(super::time_local_offset_at(OffsetDateTime::now_utc()), None);
+        drop(stop);
+        let _ = thread.join();
+    }
+
+    #[cfg(feature = "iana-time-zone")]
+    #[test]
+    fn test_named_zone() {
+        let now = OffsetDateTime::now_utc();
+        if let Some(offset) = super::named::local_offset_at(now) {
+            let name = super::named::zone_name().expect("the zone was found by name");
+            assert!(jiff::tz::TimeZone::get(&name).is_ok(), "{}", name);
+            assert!(offset.whole_hours().abs() <= 14);
+        }
+    }
+}
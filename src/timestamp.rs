@@ -0,0 +1,112 @@
+//! Rendering timestamps outside of tracing-subscriber, e.g. for the `log`
+//! ecosystem.
+//!
+//! env_logger's `Formatter` and most other log sinks implement
+//! [`io::Write`], so [`LocalTime::write_timestamp`] plugs into their format
+//! hooks, giving `log` and `tracing` output identical timestamps.
+//! [`LocalTime::slog_timestamp`] does the same for slog-term.
+
+use std::io;
+use std::panic::{AssertUnwindSafe, RefUnwindSafe, UnwindSafe};
+
+use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
+
+use crate::LocalTime;
+
+// === impl LocalTime ===
+
+impl<F, P, C> LocalTime<F, P, C>
+where
+    Self: FormatTime,
+{
+    /// Returns the current time as this timer renders it in log lines.
+    ///
+    /// If formatting fails, the string holds what was written before the
+    /// failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    ///
+    /// let timer = LocalTime::rfc_3339();
+    /// println!("{} service started", timer.timestamp());
+    /// ```
+    pub fn timestamp(&self) -> String {
+        let mut s = String::new();
+        let _ = self.format_time(&mut Writer::new(&mut s));
+        s
+    }
+
+    /// Writes the current time as this timer renders it in log lines.
+    ///
+    /// # Examples
+    ///
+    /// With env_logger:
+    ///
+    /// ```ignore
+    /// use std::io::Write;
+    ///
+    /// let timer = clia_local_time::LocalTime::rfc_3339();
+    /// env_logger::Builder::new()
+    ///     .format(move |buf, record| {
+    ///         timer.write_timestamp(buf)?;
+    ///         writeln!(buf, " {} {}", record.level(), record.args())
+    ///     })
+    ///     .init();
+    /// ```
+    pub fn write_timestamp(&self, w: &mut (impl io::Write + ?Sized)) -> io::Result<()> {
+        let mut s = String::new();
+        self.format_time(&mut Writer::new(&mut s))
+            .map_err(|_| io::Error::other("failed to format timestamp"))?;
+        w.write_all(s.as_bytes())
+    }
+
+    /// Returns a timestamp function for slog-term's
+    /// `use_custom_timestamp`, which takes a `ThreadSafeTimestampFn`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use clia_local_time::LocalTime;
+    ///
+    /// let decorator = slog_term::TermDecorator::new().build();
+    /// let drain = slog_term::FullFormat::new(decorator)
+    ///     .use_custom_timestamp(LocalTime::rfc_3339().slog_timestamp())
+    ///     .build();
+    /// ```
+    pub fn slog_timestamp(
+        self,
+    ) -> impl Fn(&mut dyn io::Write) -> io::Result<()> + Send + Sync + UnwindSafe + RefUnwindSafe + 'static
+    where
+        Self: Send + Sync + 'static,
+    {
+        // A panic while formatting leaves nothing half-updated that a later
+        // call could observe.
+        let timer = AssertUnwindSafe(self);
+        move |w| timer.write_timestamp(w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::clock::Fixed;
+    use crate::LocalTime;
+    use time::macros::datetime;
+
+    #[test]
+    fn test_write_timestamp() {
+        let timer = LocalTime::w3c((8, 0, 0))
+            .decorated("[", "]")
+            .with_clock(Fixed(datetime!(2024-05-01 02:00 UTC)));
+        assert_eq!(timer.timestamp(), "[2024-05-01 10:00:00]");
+        let mut buf = Vec::new();
+        timer.write_timestamp(&mut buf).unwrap();
+        assert_eq!(buf, b"[2024-05-01 10:00:00]");
+
+        let timestamp = timer.slog_timestamp();
+        let mut buf = Vec::new();
+        timestamp(&mut buf).unwrap();
+        assert_eq!(buf, b"[2024-05-01 10:00:00]");
+    }
+}
@@ -0,0 +1,455 @@
+//! One canonical way to express a timezone in config, env and CLI inputs.
+
+use std::fmt;
+use std::str::FromStr;
+
+use time::{
+    formatting::Formattable, macros::format_description, Duration, OffsetDateTime, UtcOffset,
+};
+
+use crate::{
+    offset::{FixedOffset, OffsetProvider, SystemOffset},
+    system, LocalTime,
+};
+
+/// A timezone, as parsed from `+08:00`, `Asia/Shanghai` or `system`.
+///
+/// [`LocalTime::with_spec`] renders timestamps in it, through a
+/// [`SpecOffset`] following its daylight saving transitions.
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::TimezoneSpec;
+/// use time::macros::offset;
+///
+/// assert_eq!("+08:00".parse(), Ok(TimezoneSpec::Fixed(offset!(+8))));
+/// assert_eq!("system".parse(), Ok(TimezoneSpec::System));
+/// assert_eq!(
+///     "Asia/Shanghai".parse(),
+///     Ok(TimezoneSpec::Named("Asia/Shanghai".to_string()))
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TimezoneSpec {
+    /// A fixed offset: `±HH[:MM[:SS]]`, `Z` or `UTC`.
+    Fixed(UtcOffset),
+    /// A tz database name, e.g. `Asia/Shanghai`.
+    Named(String),
+    /// The system's local offset.
+    System,
+}
+
+/// The error returned when a [`TimezoneSpec`] can't be parsed or resolved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TimezoneError {
+    /// The input is neither an offset, a zone name nor `system`.
+    Invalid(String),
+    /// The named zone can't be found, or the `jiff` feature is disabled.
+    UnknownZone(String),
+    /// The system's local offset can't be determined, e.g. because other
+    /// threads are running.
+    IndeterminateSystemOffset,
+}
+
+/// The offset provider [`LocalTime::with_spec`] builds from a
+/// [`TimezoneSpec`].
+///
+/// Fixed offsets stay fixed. `system` is read for every instant like
+/// [`SystemOffset`], and named zones are evaluated with their tz database
+/// rules like [`ZoneOffset`], so both follow daylight saving transitions
+/// after the timer is built.
+///
+/// [`ZoneOffset`]: https://docs.rs/clia-local-time/latest/clia_local_time/jiff/struct.ZoneOffset.html
+#[derive(Clone, Debug)]
+pub struct SpecOffset(SpecProvider);
+
+#[derive(Clone, Debug)]
+enum SpecProvider {
+    Fixed(FixedOffset),
+    System(SystemOffset),
+    #[cfg(feature = "jiff")]
+    Named(crate::jiff::ZoneOffset),
+}
+
+// === impl TimezoneSpec ===
+
+impl TimezoneSpec {
+    /// Resolves the spec to the offset in effect now.
+    ///
+    /// This is a single offset, which a timer built from it keeps across
+    /// daylight saving transitions; use [`LocalTime::with_spec`] to follow
+    /// them.
+    pub fn resolve(&self) -> Result<UtcOffset, TimezoneError> {
+        self.resolve_at(OffsetDateTime::now_utc())
+    }
+
+    /// Resolves the spec to the offset in effect at `at`, following the
+    /// daylight saving transitions of named zones and the system zone.
+    pub fn resolve_at(&self, at: OffsetDateTime) -> Result<UtcOffset, TimezoneError> {
+        match self {
+            TimezoneSpec::Fixed(offset) => Ok(*offset),
+            TimezoneSpec::Named(name) => resolve_named(name, at),
+            TimezoneSpec::System => resolve_system(at),
+        }
+    }
+
+    /// Returns `dt` as the same instant in this timezone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::TimezoneSpec;
+    /// use time::macros::datetime;
+    ///
+    /// let dt = datetime!(2024-05-01 02:00 UTC);
+    /// let shown = TimezoneSpec::Fixed(time::macros::offset!(+8)).to_zone(dt).unwrap();
+    /// assert_eq!(shown, datetime!(2024-05-01 10:00 +8));
+    /// ```
+    pub fn to_zone(&self, dt: OffsetDateTime) -> Result<OffsetDateTime, TimezoneError> {
+        Ok(dt.to_offset(self.resolve_at(dt)?))
+    }
+
+    /// Reads the date and time of `dt` as a wall-clock time in this
+    /// timezone, ignoring the offset of `dt`.
+    ///
+    /// Times skipped by a daylight saving transition resolve to the offset
+    /// before it, and repeated times to the earlier instant.
+    pub fn assume_zone(&self, dt: OffsetDateTime) -> Result<OffsetDateTime, TimezoneError> {
+        let wall = dt.replace_offset(UtcOffset::UTC);
+        // The offsets around `wall`, which differ near a transition.
+        let before = wall.replace_offset(self.resolve_at(wall - Duration::DAY)?);
+        let after = wall.replace_offset(self.resolve_at(wall + Duration::DAY)?);
+        let valid = |dt: OffsetDateTime| self.resolve_at(dt).map(|offset| offset == dt.offset());
+        Ok(match (valid(before)?, valid(after)?) {
+            (true, true) => before.min(after),
+            (false, true) => after,
+            _ => before,
+        })
+    }
+}
+
+/// Converts `dt`, a wall-clock time in `from`, to the same instant in `to`,
+/// e.g. to show stored log timestamps to users in another zone.
+///
+/// The offset of `dt` is ignored, so timestamps parsed without one can be
+/// passed as they are; see [`TimezoneSpec::assume_zone`]. For values that
+/// already carry the right offset, use [`TimezoneSpec::to_zone`].
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::{convert, TimezoneSpec};
+/// use time::macros::datetime;
+///
+/// let from: TimezoneSpec = "+08:00".parse().expect("spec should be valid");
+/// let to: TimezoneSpec = "-05:00".parse().expect("spec should be valid");
+/// let converted = convert(datetime!(2024-05-01 10:00 UTC), &from, &to)
+///     .expect("fixed offsets should resolve");
+/// assert_eq!(converted, datetime!(2024-04-30 21:00 -5));
+/// ```
+pub fn convert(
+    dt: OffsetDateTime,
+    from: &TimezoneSpec,
+    to: &TimezoneSpec,
+) -> Result<OffsetDateTime, TimezoneError> {
+    to.to_zone(from.assume_zone(dt)?)
+}
+
+impl FromStr for TimezoneSpec {
+    type Err = TimezoneError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || TimezoneError::Invalid(s.to_string());
+        if s.eq_ignore_ascii_case("system") {
+            return Ok(TimezoneSpec::System);
+        }
+        if let Some((h, m, sec)) = parse_offset(s) {
+            return UtcOffset::from_hms(h, m, sec)
+                .map(TimezoneSpec::Fixed)
+                .map_err(|_| invalid());
+        }
+        let is_name = s
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'/' | b'_' | b'-' | b'+'));
+        if s.is_empty() || !is_name || s.starts_with(['+', '-']) {
+            return Err(invalid());
+        }
+        Ok(TimezoneSpec::Named(s.to_string()))
+    }
+}
+
+impl fmt::Display for TimezoneSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimezoneSpec::Fixed(offset) => {
+                let s = if offset.seconds_past_minute() == 0 {
+                    offset.format(format_description!(
+                        "[offset_hour sign:mandatory]:[offset_minute]"
+                    ))
+                } else {
+                    offset.format(format_description!(
+                        "[offset_hour sign:mandatory]:[offset_minute]:[offset_second]"
+                    ))
+                };
+                f.write_str(&s.map_err(|_| fmt::Error)?)
+            }
+            TimezoneSpec::Named(name) => f.write_str(name),
+            TimezoneSpec::System => f.write_str("system"),
+        }
+    }
+}
+
+/// The system offset at `at`, as [`SystemOffset`] looks it up, or else the
+/// one cached by [`init_local_offset`](crate::init_local_offset).
+fn resolve_system(at: OffsetDateTime) -> Result<UtcOffset, TimezoneError> {
+    if let Some(offset) = system::local_offset_at(at) {
+        return Ok(offset);
+    }
+    #[cfg(feature = "local-offset")]
+    if let Some(offset) = crate::local_offset() {
+        return Ok(offset);
+    }
+    Err(TimezoneError::IndeterminateSystemOffset)
+}
+
+#[cfg(feature = "jiff")]
+fn resolve_named(name: &str, at: OffsetDateTime) -> Result<UtcOffset, TimezoneError> {
+    let unknown = || TimezoneError::UnknownZone(name.to_string());
+    let zone = ::jiff::tz::TimeZone::get(name).map_err(|_| unknown())?;
+    let at = ::jiff::Timestamp::from_second(at.unix_timestamp()).map_err(|_| unknown())?;
+    let seconds = zone.to_offset(at).seconds();
+    UtcOffset::from_whole_seconds(seconds).map_err(|_| TimezoneError::UnknownZone(name.to_string()))
+}
+
+#[cfg(not(feature = "jiff"))]
+fn resolve_named(name: &str, _at: OffsetDateTime) -> Result<UtcOffset, TimezoneError> {
+    Err(TimezoneError::UnknownZone(name.to_string()))
+}
+
+/// Parses `±HH[:MM[:SS]]`, `Z` or `UTC` into `(hours, minutes, seconds)`.
+///
+/// This is a `const fn` so [`local_time!`](crate::local_time) can validate
+/// offsets at compile time.
+pub(crate) const fn parse_offset(s: &str) -> Option<(i8, i8, i8)> {
+    let b = s.as_bytes();
+    if eq_ignore_case(b, b"z") || eq_ignore_case(b, b"utc") {
+        return Some((0, 0, 0));
+    }
+    let sign = match b.first() {
+        Some(b'+') => 1,
+        Some(b'-') => -1,
+        _ => return None,
+    };
+    if !matches!(b.len(), 3 | 6 | 9) {
+        return None;
+    }
+    let max = [25, 59, 59];
+    let mut hms = [0; 3];
+    let mut i = 0;
+    // Each part is two digits, preceded by the sign or a colon.
+    while i * 3 + 1 < b.len() {
+        let start = i * 3 + 1;
+        let (hi, lo) = (b[start], b[start + 1]);
+        if (i > 0 && b[start - 1] != b':') || !hi.is_ascii_digit() || !lo.is_ascii_digit() {
+            return None;
+        }
+        let n = ((hi - b'0') * 10 + (lo - b'0')) as i8;
+        if n > max[i] {
+            return None;
+        }
+        hms[i] = n * sign;
+        i += 1;
+    }
+    Some((hms[0], hms[1], hms[2]))
+}
+
+const fn eq_ignore_case(a: &[u8], lower: &[u8]) -> bool {
+    if a.len() != lower.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i].to_ascii_lowercase() != lower[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+// === impl SpecOffset ===
+
+impl SpecOffset {
+    /// Returns a provider for `spec`, failing if it doesn't resolve now.
+    ///
+    /// A `system` spec falls back to the offset detected here when later
+    /// detection fails.
+    pub fn new(spec: &TimezoneSpec) -> Result<Self, TimezoneError> {
+        let offset = spec.resolve()?;
+        Ok(SpecOffset(match spec {
+            TimezoneSpec::Fixed(_) => SpecProvider::Fixed(FixedOffset(offset)),
+            TimezoneSpec::System => SpecProvider::System(SystemOffset::with_fallback(offset)),
+            #[cfg(feature = "jiff")]
+            TimezoneSpec::Named(name) => SpecProvider::Named(
+                crate::jiff::ZoneOffset::new(name)
+                    .map_err(|_| TimezoneError::UnknownZone(name.clone()))?,
+            ),
+            // Without jiff, `resolve` has already failed for named zones.
+            #[cfg(not(feature = "jiff"))]
+            TimezoneSpec::Named(name) => return Err(TimezoneError::UnknownZone(name.clone())),
+        }))
+    }
+}
+
+impl OffsetProvider for SpecOffset {
+    fn offset_at(&self, utc: OffsetDateTime) -> UtcOffset {
+        match &self.0 {
+            SpecProvider::Fixed(fixed) => fixed.offset_at(utc),
+            SpecProvider::System(system) => system.offset_at(utc),
+            #[cfg(feature = "jiff")]
+            SpecProvider::Named(zone) => zone.offset_at(utc),
+        }
+    }
+
+    fn is_fallback(&self, utc: OffsetDateTime) -> bool {
+        match &self.0 {
+            SpecProvider::System(system) => system.is_fallback(utc),
+            _ => false,
+        }
+    }
+}
+
+// === impl TimezoneError ===
+
+impl fmt::Display for TimezoneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimezoneError::Invalid(s) => write!(f, "`{}` is not a UTC offset or zone name", s),
+            TimezoneError::UnknownZone(name) => write!(f, "unknown timezone `{}`", name),
+            TimezoneError::IndeterminateSystemOffset => {
+                f.write_str("the system's local offset can't be determined")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TimezoneError {}
+
+// === impl LocalTime ===
+
+impl<F: Formattable> LocalTime<F> {
+    /// Returns a formatter that renders timestamps in the timezone `spec`
+    /// names.
+    ///
+    /// The spec must resolve now, but isn't frozen: `system` and named zones
+    /// are looked up for every timestamp, so the offset follows daylight
+    /// saving transitions. See [`SpecOffset`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::{LocalTime, TimezoneSpec};
+    /// use time::macros::format_description;
+    ///
+    /// let spec: TimezoneSpec = "+08:00".parse().expect("spec should be valid");
+    /// let timer = LocalTime::with_spec(format_description!("[hour]:[minute]"), &spec)
+    ///     .expect("spec should resolve");
+    /// let collector = tracing_subscriber::fmt()
+    ///     .with_timer(timer);
+    /// # drop(collector);
+    /// ```
+    pub fn with_spec(
+        format: F,
+        spec: &TimezoneSpec,
+    ) -> Result<LocalTime<F, SpecOffset>, TimezoneError> {
+        Ok(LocalTime::with_provider(format, SpecOffset::new(spec)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert, parse_offset, TimezoneError, TimezoneSpec};
+    use time::macros::{datetime, offset};
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(parse_offset("Z"), Some((0, 0, 0)));
+        assert_eq!(parse_offset("+08"), Some((8, 0, 0)));
+        assert_eq!(parse_offset("-02:30"), Some((-2, -30, 0)));
+        assert_eq!(parse_offset("+05:30:15"), Some((5, 30, 15)));
+        assert_eq!(parse_offset("08:00"), None);
+        assert_eq!(parse_offset("+8:00"), None);
+        assert_eq!(parse_offset("+08:"), None);
+        assert_eq!(parse_offset("+08-30"), None);
+        assert_eq!(parse_offset("+08:60"), None);
+    }
+
+    #[test]
+    fn test_spec() {
+        for s in ["-02:30", "+05:30:15", "Europe/Berlin", "system"] {
+            assert_eq!(s.parse::<TimezoneSpec>().unwrap().to_string(), s);
+        }
+        assert_eq!("utc".parse(), Ok(TimezoneSpec::Fixed(offset!(UTC))));
+        assert!(matches!(
+            "+8:00".parse::<TimezoneSpec>(),
+            Err(TimezoneError::Invalid(_))
+        ));
+        assert!(matches!(
+            "Asia Shanghai".parse::<TimezoneSpec>(),
+            Err(TimezoneError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_resolve_system() {
+        let spec = TimezoneSpec::System;
+        if let Ok(offset) = spec.resolve() {
+            assert_eq!(spec.resolve_at(time::OffsetDateTime::now_utc()), Ok(offset));
+        }
+    }
+
+    #[test]
+    fn test_convert() {
+        let (cst, est) = (
+            TimezoneSpec::Fixed(offset!(+8)),
+            TimezoneSpec::Fixed(offset!(-5)),
+        );
+        let dt = datetime!(2024-05-01 10:00 +3);
+        assert_eq!(convert(dt, &cst, &est), Ok(datetime!(2024-04-30 21:00 -5)));
+        assert_eq!(cst.to_zone(dt), Ok(datetime!(2024-05-01 15:00 +8)));
+        assert_eq!(cst.assume_zone(dt), Ok(datetime!(2024-05-01 10:00 +8)));
+    }
+
+    #[cfg(feature = "jiff")]
+    #[test]
+    fn test_convert_named() {
+        let berlin = TimezoneSpec::Named("Europe/Berlin".to_string());
+        let utc = TimezoneSpec::Fixed(offset!(UTC));
+        let summer = convert(datetime!(2024-07-01 12:00 UTC), &berlin, &utc);
+        assert_eq!(summer, Ok(datetime!(2024-07-01 10:00 UTC)));
+        let winter = convert(datetime!(2024-01-01 12:00 UTC), &berlin, &utc);
+        assert_eq!(winter, Ok(datetime!(2024-01-01 11:00 UTC)));
+        // 02:30 is skipped in spring and repeated in autumn.
+        let skipped = berlin.assume_zone(datetime!(2024-03-31 02:30 UTC));
+        assert_eq!(skipped, Ok(datetime!(2024-03-31 02:30 +1)));
+        let repeated = berlin.assume_zone(datetime!(2024-10-27 02:30 UTC));
+        assert_eq!(repeated, Ok(datetime!(2024-10-27 02:30 +2)));
+    }
+
+    #[cfg(feature = "jiff")]
+    #[test]
+    fn test_with_spec_follows_dst() {
+        use crate::offset::OffsetProvider;
+        use crate::LocalTime;
+
+        let spec = TimezoneSpec::Named("Europe/Berlin".to_string());
+        let timer = LocalTime::with_spec(time::format_description::well_known::Rfc3339, &spec)
+            .expect("zone should resolve");
+        let offset = |at| timer.offset.offset_at(at);
+        assert_eq!(offset(datetime!(2024-03-31 00:59 UTC)), offset!(+1));
+        assert_eq!(offset(datetime!(2024-03-31 01:00 UTC)), offset!(+2));
+        assert_eq!(offset(datetime!(2024-10-27 01:00 UTC)), offset!(+1));
+    }
+}
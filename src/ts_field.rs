@@ -0,0 +1,329 @@
+//! A layer adding the timestamp to events as a structured field, for
+//! subscribers that don't take a timer.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{OnceLock, PoisonError, RwLock};
+
+use tracing::callsite::{Callsite, Identifier};
+use tracing::field::{self, DisplayValue, Field, FieldSet, Value, Visit};
+use tracing::metadata::{Kind, LevelFilter};
+use tracing::subscriber::Interest;
+use tracing::{span, Event, Metadata, Subscriber};
+use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// Wraps a [`Layer`], passing it every event with the timestamp of `timer`
+/// appended as a field, `ts` by default.
+///
+/// This gives layers that don't take a timer, e.g. JSON exporters or custom
+/// collectors, timestamps in the configured timezone and format. Other
+/// methods are forwarded unchanged.
+///
+/// Events are rebuilt with the extra field, which tracing only allows for
+/// events with at most 32 fields; larger events are passed on unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::{LocalTime, TimestampField};
+/// use tracing_subscriber::{fmt, layer::SubscriberExt};
+///
+/// let layer = TimestampField::new(
+///     fmt::layer().json().without_time(),
+///     LocalTime::w3c((8, 0, 0)),
+/// );
+/// let subscriber = tracing_subscriber::registry().with(layer);
+/// # drop(subscriber);
+/// ```
+#[derive(Debug)]
+pub struct TimestampField<L, T> {
+    inner: L,
+    timer: T,
+    name: &'static str,
+    /// The metadata of events rebuilt from each callsite's.
+    rebuilt: RwLock<HashMap<Identifier, &'static Metadata<'static>>>,
+}
+
+/// The callsite of rebuilt events.
+struct Rebuilt {
+    metadata: OnceLock<Metadata<'static>>,
+}
+
+/// The most fields an event can be rebuilt with, including the timestamp.
+const MAX_FIELDS: usize = 33;
+
+/// A field value recorded from the original event.
+enum Recorded {
+    I64(i64),
+    U64(u64),
+    I128(i128),
+    U128(u128),
+    F64(f64),
+    Bool(bool),
+    Str(String),
+    Debug(DisplayValue<String>),
+}
+
+#[derive(Default)]
+struct Recorder(Vec<Option<Recorded>>);
+
+// === impl TimestampField ===
+
+impl<L, T> TimestampField<L, T> {
+    /// Wraps `inner`, adding the timestamp of `timer` to its events.
+    pub fn new(inner: L, timer: T) -> Self {
+        Self {
+            inner,
+            timer,
+            name: "ts",
+            rebuilt: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Names the timestamp field `name` instead of `ts`.
+    pub fn with_field_name(self, name: &'static str) -> Self {
+        Self { name, ..self }
+    }
+
+    /// The metadata of the events rebuilt from `original`, leaked once per
+    /// callsite.
+    fn rebuilt(&self, original: &'static Metadata<'static>) -> &'static Metadata<'static> {
+        let id = original.callsite();
+        let read = self.rebuilt.read().unwrap_or_else(PoisonError::into_inner);
+        if let Some(&metadata) = read.get(&id) {
+            return metadata;
+        }
+        drop(read);
+        let mut write = self.rebuilt.write().unwrap_or_else(PoisonError::into_inner);
+        write.entry(id).or_insert_with(|| {
+            let names = original
+                .fields()
+                .iter()
+                .map(|field| field.name())
+                .chain([self.name])
+                .collect::<Vec<_>>();
+            let callsite: &'static Rebuilt = Box::leak(Box::new(Rebuilt {
+                metadata: OnceLock::new(),
+            }));
+            callsite.metadata.get_or_init(|| {
+                Metadata::new(
+                    original.name(),
+                    original.target(),
+                    *original.level(),
+                    original.file(),
+                    original.line(),
+                    original.module_path(),
+                    FieldSet::new(Box::leak(names.into_boxed_slice()), Identifier(callsite)),
+                    Kind::EVENT,
+                )
+            })
+        })
+    }
+}
+
+impl<S, L, T> Layer<S> for TimestampField<L, T>
+where
+    S: Subscriber,
+    L: Layer<S>,
+    T: FormatTime + 'static,
+{
+    fn on_layer(&mut self, subscriber: &mut S) {
+        self.inner.on_layer(subscriber);
+    }
+
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        self.inner.register_callsite(metadata)
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, ctx: Context<'_, S>) -> bool {
+        self.inner.enabled(metadata, ctx)
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        self.inner.max_level_hint()
+    }
+
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        self.inner.on_new_span(attrs, id, ctx);
+    }
+
+    fn on_record(&self, span: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        self.inner.on_record(span, values, ctx);
+    }
+
+    fn on_follows_from(&self, span: &span::Id, follows: &span::Id, ctx: Context<'_, S>) {
+        self.inner.on_follows_from(span, follows, ctx);
+    }
+
+    fn event_enabled(&self, event: &Event<'_>, ctx: Context<'_, S>) -> bool {
+        self.inner.event_enabled(event, ctx)
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let original = event.metadata();
+        if original.fields().len() >= MAX_FIELDS {
+            return self.inner.on_event(event, ctx);
+        }
+        let mut recorder = Recorder::default();
+        recorder.0.resize_with(original.fields().len(), || None);
+        event.record(&mut recorder);
+        let mut ts = String::new();
+        if self.timer.format_time(&mut Writer::new(&mut ts)).is_err() {
+            return self.inner.on_event(event, ctx);
+        }
+
+        let metadata = self.rebuilt(original);
+        let fields = metadata.fields().iter().collect::<Vec<_>>();
+        let ts_field = &fields[fields.len() - 1];
+        let mut values: [(&Field, Option<&dyn Value>); MAX_FIELDS] = [(ts_field, None); MAX_FIELDS];
+        for ((slot, field), value) in values.iter_mut().zip(&fields).zip(&recorder.0) {
+            *slot = (field, value.as_ref().map(Recorded::as_value));
+        }
+        values[fields.len() - 1] = (ts_field, Some(&ts));
+        let values = metadata.fields().value_set(&values);
+        let rebuilt = if let Some(parent) = event.parent() {
+            Event::new_child_of(parent.clone(), metadata, &values)
+        } else if event.is_root() {
+            Event::new_child_of(None, metadata, &values)
+        } else {
+            Event::new(metadata, &values)
+        };
+        self.inner.on_event(&rebuilt, ctx);
+    }
+
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        self.inner.on_enter(id, ctx);
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        self.inner.on_exit(id, ctx);
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        self.inner.on_close(id, ctx);
+    }
+
+    fn on_id_change(&self, old: &span::Id, new: &span::Id, ctx: Context<'_, S>) {
+        self.inner.on_id_change(old, new, ctx);
+    }
+}
+
+// === impl Rebuilt ===
+
+impl Callsite for Rebuilt {
+    fn set_interest(&self, _interest: Interest) {}
+
+    fn metadata(&self) -> &Metadata<'_> {
+        self.metadata
+            .get()
+            .expect("set when the callsite is leaked")
+    }
+}
+
+// === impl Recorded ===
+
+impl Recorded {
+    fn as_value(&self) -> &dyn Value {
+        match self {
+            Recorded::I64(value) => value,
+            Recorded::U64(value) => value,
+            Recorded::I128(value) => value,
+            Recorded::U128(value) => value,
+            Recorded::F64(value) => value,
+            Recorded::Bool(value) => value,
+            Recorded::Str(value) => value,
+            Recorded::Debug(value) => value,
+        }
+    }
+}
+
+// === impl Recorder ===
+
+impl Recorder {
+    fn set(&mut self, field: &Field, value: Recorded) {
+        if let Some(slot) = self.0.get_mut(field.index()) {
+            *slot = Some(value);
+        }
+    }
+}
+
+impl Visit for Recorder {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.set(field, Recorded::I64(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.set(field, Recorded::U64(value));
+    }
+
+    fn record_i128(&mut self, field: &Field, value: i128) {
+        self.set(field, Recorded::I128(value));
+    }
+
+    fn record_u128(&mut self, field: &Field, value: u128) {
+        self.set(field, Recorded::U128(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.set(field, Recorded::F64(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.set(field, Recorded::Bool(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.set(field, Recorded::Str(value.to_string()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.set(
+            field,
+            Recorded::Debug(field::display(format!("{:?}", value))),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TimestampField;
+    use crate::{clock::Fixed, testing::Capture, LocalTime};
+    use time::macros::datetime;
+    use tracing_subscriber::{fmt, layer::SubscriberExt};
+
+    #[test]
+    fn test_timestamp_field() {
+        let capture = Capture::default();
+        let writer = capture.clone();
+        let timer = LocalTime::w3c((8, 0, 0)).with_clock(Fixed(datetime!(2024-05-01 02:00 UTC)));
+        let layer = TimestampField::new(
+            fmt::layer()
+                .json()
+                .without_time()
+                .with_writer(move || writer.clone()),
+            timer,
+        );
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(answer = 42, name = "x", kind = ?Some(1), "hello");
+            tracing::info!("again");
+        });
+
+        let output = capture.output();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(
+            lines[0].contains(
+                r#""fields":{"message":"hello","answer":42,"name":"x","kind":"Some(1)","ts":"2024-05-01 10:00:00"}"#
+            ),
+            "{}",
+            lines[0]
+        );
+        assert!(
+            lines[1].contains(r#""ts":"2024-05-01 10:00:00""#),
+            "{}",
+            lines[1]
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! A clock reading the CPU's time-stamp counter.
+
+use std::sync::{Once, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use time::OffsetDateTime;
+
+use crate::clock::{ClockKind, ClockSource, SystemClock};
+
+/// A [`ClockSource`] reading the CPU's time-stamp counter instead of the
+/// system clock, for latency-sensitive logs that can't afford a system call
+/// per event.
+///
+/// The counter is calibrated against the monotonic clock when the clock is
+/// created, or on a background thread for [`TscClock::default`], and
+/// anchored to a wall-clock reading taken at the same time.
+/// Later readings add the calibrated elapsed time to that anchor with
+/// nanosecond resolution, so they don't follow adjustments of the system
+/// clock; create a new clock to re-anchor.
+///
+/// The counter is only used on x86_64 CPUs reporting an invariant TSC, one
+/// ticking at a constant rate across frequency changes and sleep states, as
+/// on every recent CPU. Elsewhere, and on CPUs without one, the clock reads
+/// the [`SystemClock`] instead, and [`kind`](ClockSource::kind) reports
+/// [`ClockKind::Wall`]. Cores are assumed to be synchronized, which the
+/// invariant TSC bit doesn't guarantee on some multi-socket systems.
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::{LocalTime, TscClock};
+///
+/// let collector = tracing_subscriber::fmt()
+///     .with_timer(LocalTime::rfc_3339().with_clock(TscClock::new()));
+/// # drop(collector);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "tsc")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TscClock {
+    source: Source,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Source {
+    /// The system clock.
+    System,
+    Calibrated(Tsc),
+    /// The calibration shared by [`TscClock::default`] clocks, once ready.
+    Background,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Tsc {
+    anchor: OffsetDateTime,
+    start: u64,
+    /// Nanoseconds per tick, as a 32.32 fixed-point number.
+    scale: u64,
+}
+
+/// How long [`TscClock::new`] calibrates for.
+const CALIBRATION: Duration = Duration::from_millis(10);
+
+/// The calibration of [`Source::Background`], or `None` to read the system
+/// clock.
+static BACKGROUND: OnceLock<Option<Tsc>> = OnceLock::new();
+static SPAWN_CALIBRATION: Once = Once::new();
+
+// === impl TscClock ===
+
+impl TscClock {
+    /// Calibrates the counter for 10ms and anchors it to the current time.
+    pub fn new() -> Self {
+        Self::with_calibration(CALIBRATION)
+    }
+
+    /// Calibrates the counter for `calibration`, blocking the calling thread
+    /// meanwhile, and anchors it to the current time. Longer calibrations
+    /// drift less from the system clock.
+    ///
+    /// Without an invariant TSC this returns at once, reading the system
+    /// clock.
+    pub fn with_calibration(calibration: Duration) -> Self {
+        if !invariant_tsc() {
+            return Self {
+                source: Source::System,
+            };
+        }
+        let (instant, ticks) = (Instant::now(), read_ticks());
+        thread::sleep(calibration);
+        let (elapsed, elapsed_ticks) = (instant.elapsed(), read_ticks().wrapping_sub(ticks));
+        let scale = (elapsed.as_nanos() << 32) / u128::from(elapsed_ticks.max(1));
+        let tsc = Tsc {
+            anchor: OffsetDateTime::now_utc(),
+            start: read_ticks(),
+            scale: u64::try_from(scale).unwrap_or(u64::MAX),
+        };
+        Self {
+            source: Source::Calibrated(tsc),
+        }
+    }
+
+    /// The calibrated counter frequency, in ticks per second, or `None` if
+    /// the clock reads the system clock.
+    pub fn frequency(&self) -> Option<u64> {
+        let tsc = self.tsc()?;
+        Some(((1_000_000_000u128 << 32) / u128::from(tsc.scale.max(1))) as u64)
+    }
+
+    fn tsc(&self) -> Option<&Tsc> {
+        match &self.source {
+            Source::System => None,
+            Source::Calibrated(tsc) => Some(tsc),
+            Source::Background => BACKGROUND.get()?.as_ref(),
+        }
+    }
+}
+
+// === impl Tsc ===
+
+impl Tsc {
+    /// The time elapsed between anchoring and a counter reading of `ticks`.
+    fn elapsed(&self, ticks: u64) -> time::Duration {
+        let nanos = (u128::from(ticks.wrapping_sub(self.start)) * u128::from(self.scale)) >> 32;
+        time::Duration::nanoseconds(i64::try_from(nanos).unwrap_or(i64::MAX))
+    }
+}
+
+/// Returns at once, leaving the calibration to a background thread, which
+/// all default clocks share. Until it finishes, about 10ms later, the clock
+/// reads the system clock.
+impl Default for TscClock {
+    fn default() -> Self {
+        SPAWN_CALIBRATION.call_once(|| {
+            let spawned = thread::Builder::new()
+                .name("clia-local-time-tsc".into())
+                .spawn(|| BACKGROUND.set(TscClock::new().tsc().copied()));
+            if spawned.is_err() {
+                let _ = BACKGROUND.set(None);
+            }
+        });
+        Self {
+            source: Source::Background,
+        }
+    }
+}
+
+impl ClockSource for TscClock {
+    fn now_utc(&self) -> OffsetDateTime {
+        match self.tsc() {
+            Some(tsc) => tsc.anchor + tsc.elapsed(read_ticks()),
+            None => SystemClock::new().now_utc(),
+        }
+    }
+
+    fn kind(&self) -> ClockKind {
+        match self.tsc() {
+            Some(_) => ClockKind::Tsc,
+            None => ClockKind::Wall,
+        }
+    }
+}
+
+/// Whether the CPU reports an invariant TSC: CPUID leaf `0x8000_0007`,
+/// EDX bit 8.
+#[cfg(target_arch = "x86_64")]
+fn invariant_tsc() -> bool {
+    use std::arch::x86_64::__cpuid;
+
+    // SAFETY: `cpuid` is available on every x86_64 CPU, and querying an
+    // extended leaf up to the reported maximum is always valid.
+    #[allow(unused_unsafe)]
+    unsafe {
+        __cpuid(0x8000_0000).eax >= 0x8000_0007 && __cpuid(0x8000_0007).edx & (1 << 8) != 0
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn invariant_tsc() -> bool {
+    false
+}
+
+#[cfg(target_arch = "x86_64")]
+fn read_ticks() -> u64 {
+    // SAFETY: `rdtsc` is available on every x86_64 CPU.
+    unsafe { std::arch::x86_64::_rdtsc() }
+}
+
+/// Never called: [`invariant_tsc`] is false here.
+#[cfg(not(target_arch = "x86_64"))]
+fn read_ticks() -> u64 {
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TscClock;
+    use crate::clock::{ClockKind, ClockSource};
+    use std::time::Duration;
+    use time::OffsetDateTime;
+
+    #[test]
+    fn test_tsc_clock() {
+        let clock = TscClock::with_calibration(Duration::from_millis(20));
+        match clock.kind() {
+            ClockKind::Tsc => assert!(clock.frequency() > Some(0)),
+            kind => assert_eq!((kind, clock.frequency()), (ClockKind::Wall, None)),
+        }
+        let first = clock.now_utc();
+        let second = clock.now_utc();
+        assert!(first <= second);
+        let drift = (OffsetDateTime::now_utc() - second).abs();
+        assert!(drift < time::Duration::milliseconds(100), "{drift}");
+    }
+
+    #[test]
+    fn test_default_calibrates_in_background() {
+        let clock = TscClock::default();
+        // The system clock is read until the calibration is ready.
+        let before = clock.now_utc();
+        assert!((OffsetDateTime::now_utc() - before).abs() < time::Duration::milliseconds(100));
+        while super::BACKGROUND.get().is_none() {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        let calibrated = super::BACKGROUND.get().unwrap().is_some();
+        assert_eq!(clock.kind() == ClockKind::Tsc, calibrated);
+        let drift = (OffsetDateTime::now_utc() - clock.now_utc()).abs();
+        assert!(drift < time::Duration::milliseconds(100), "{drift}");
+    }
+}
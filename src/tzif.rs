@@ -0,0 +1,628 @@
+//! A minimal reader for TZif files ([RFC 8536]), the compiled zone data in
+//! `/etc/localtime` and `/usr/share/zoneinfo`.
+//!
+//! Only what's needed to map instants to offsets is kept: the transition
+//! times, the offset of each local time type, and the footer's POSIX TZ rule
+//! for instants after the last transition. Leap seconds, abbreviations and
+//! the standard/UT indicators are skipped.
+//!
+//! [RFC 8536]: https://www.rfc-editor.org/rfc/rfc8536
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use time::{Date, Month, OffsetDateTime, UtcOffset};
+
+use crate::offset::OffsetProvider;
+
+/// The path the system's local zone is read from on Unix.
+pub const LOCALTIME: &str = "/etc/localtime";
+
+/// The directory zone names in `TZ` are looked up in, unless `TZDIR` is set.
+pub const ZONEINFO: &str = "/usr/share/zoneinfo";
+
+/// The offsets of a zone, parsed from a TZif file.
+///
+/// # Examples
+///
+/// ```no_run
+/// use clia_local_time::{tzif::Tzif, LocalTime};
+/// use time::format_description::well_known::Rfc3339;
+///
+/// let zone = Tzif::system().expect("/etc/localtime should be readable");
+/// let collector = tracing_subscriber::fmt()
+///     .with_timer(LocalTime::with_provider(Rfc3339, zone));
+/// # drop(collector);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tzif {
+    /// Unix times of the transitions, in ascending order.
+    transitions: Vec<i64>,
+    /// The index into `types` taking effect at each transition.
+    indices: Vec<u8>,
+    types: Vec<UtcOffset>,
+    rule: Option<PosixTz>,
+}
+
+/// The error returned when a TZif file can't be read.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TzifError {
+    /// The file can't be read.
+    Io(io::Error),
+    /// The data isn't a valid TZif file; the message says which part.
+    Malformed(&'static str),
+}
+
+/// A POSIX TZ rule, e.g. `EST5EDT,M3.2.0,M11.1.0`, as found in the footer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct PosixTz {
+    /// The standard offset, in seconds east of UTC.
+    std: i32,
+    dst: Option<Dst>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Dst {
+    /// The daylight saving offset, in seconds east of UTC.
+    offset: i32,
+    start: (Rule, i32),
+    end: (Rule, i32),
+}
+
+/// The day of the year a POSIX TZ rule switches on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Rule {
+    /// `Jn`: the day `1..=365`, never counting February 29.
+    Julian(u16),
+    /// `n`: the zero-based day `0..=365`, counting February 29.
+    Ordinal(u16),
+    /// `Mm.w.d`: weekday `d` (Sunday is `0`) of week `w` of month `m`, where
+    /// week `5` is the last.
+    Month(u8, u8, u8),
+}
+
+// === impl Tzif ===
+
+impl Tzif {
+    /// Parses the contents of a TZif file.
+    pub fn parse(data: &[u8]) -> Result<Self, TzifError> {
+        let mut input = Input(data);
+        let v1 = Header::read(&mut input)?;
+        if v1.version == 0 {
+            return Self::read_block(&mut input, &v1, 4);
+        }
+        // Skip the 32-bit data; version 2 and later repeat it with 64-bit
+        // times, followed by the footer.
+        input.take(v1.block_len(4))?;
+        let header = Header::read(&mut input)?;
+        let mut tzif = Self::read_block(&mut input, &header, 8)?;
+        let footer = input.0;
+        let footer = footer
+            .strip_prefix(b"\n")
+            .and_then(|footer| footer.split(|&b| b == b'\n').next())
+            .ok_or(TzifError::Malformed("footer"))?;
+        if !footer.is_empty() {
+            let footer = std::str::from_utf8(footer).map_err(|_| TzifError::Malformed("footer"))?;
+            tzif.rule = Some(PosixTz::parse(footer).ok_or(TzifError::Malformed("footer"))?);
+        }
+        Ok(tzif)
+    }
+
+    /// Reads and parses the TZif file at `path`.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, TzifError> {
+        Self::parse(&std::fs::read(path).map_err(TzifError::Io)?)
+    }
+
+    /// Reads the system's local zone from [`LOCALTIME`].
+    pub fn system() -> Result<Self, TzifError> {
+        Self::read(LOCALTIME)
+    }
+
+    /// Interprets a value of the `TZ` environment variable the way the C
+    /// library does.
+    ///
+    /// A leading `:` is dropped. An absolute path is read as a TZif file, and
+    /// a zone name, e.g. `Europe/Berlin`, is looked up under `TZDIR` or
+    /// [`ZONEINFO`]. Anything else is parsed as a POSIX TZ rule, e.g.
+    /// `CET-1CEST,M3.5.0,M10.5.0/3`; an empty value means UTC.
+    pub fn from_tz(tz: &str) -> Result<Self, TzifError> {
+        let tz = tz.strip_prefix(':').unwrap_or(tz);
+        if tz.is_empty() {
+            return Ok(Self::from_rule(PosixTz { std: 0, dst: None }));
+        }
+        let path = Path::new(tz);
+        if path.is_absolute() {
+            return Self::read(path);
+        }
+        // Like glibc, don't let a name climb out of the zone directory.
+        if !tz.split('/').any(|part| part == "..") {
+            let dir = std::env::var_os("TZDIR").unwrap_or_else(|| ZONEINFO.into());
+            if let Ok(zone) = Self::read(Path::new(&dir).join(path)) {
+                return Ok(zone);
+            }
+        }
+        PosixTz::parse(tz)
+            .map(Self::from_rule)
+            .ok_or(TzifError::Malformed("TZ rule"))
+    }
+
+    /// A zone with no transitions, following `rule` throughout.
+    fn from_rule(rule: PosixTz) -> Self {
+        Self {
+            transitions: Vec::new(),
+            indices: Vec::new(),
+            types: vec![UtcOffset::from_whole_seconds(rule.std).unwrap_or(UtcOffset::UTC)],
+            rule: Some(rule),
+        }
+    }
+
+    fn read_block(
+        input: &mut Input<'_>,
+        header: &Header,
+        time_len: usize,
+    ) -> Result<Self, TzifError> {
+        let transitions = input
+            .take(header.timecnt * time_len)?
+            .chunks_exact(time_len)
+            .map(be_int)
+            .collect::<Vec<_>>();
+        let indices = input.take(header.timecnt)?.to_vec();
+        let types = input
+            .take(header.typecnt * 6)?
+            .chunks_exact(6)
+            .map(|ty| {
+                UtcOffset::from_whole_seconds(be_int(&ty[..4]) as i32)
+                    .map_err(|_| TzifError::Malformed("offset"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        input.take(header.charcnt + header.leapcnt * (time_len + 4))?;
+        input.take(header.isstdcnt + header.isutcnt)?;
+
+        if types.is_empty() || indices.iter().any(|&i| usize::from(i) >= types.len()) {
+            return Err(TzifError::Malformed("local time types"));
+        }
+        if transitions.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(TzifError::Malformed("transition times"));
+        }
+        Ok(Self {
+            transitions,
+            indices,
+            types,
+            rule: None,
+        })
+    }
+
+    /// Returns the next change of offset after `utc`, and the offset it
+    /// changes to.
+    ///
+    /// Calling this again with the returned instant walks through the
+    /// upcoming offsets, e.g. to find the next daylight saving transition.
+    pub fn next_transition(&self, utc: OffsetDateTime) -> Option<(OffsetDateTime, UtcOffset)> {
+        let t = utc.unix_timestamp();
+        let i = self.transitions.partition_point(|&at| at <= t);
+        if let Some(&at) = self.transitions.get(i) {
+            let offset = self.types[usize::from(self.indices[i])];
+            return Some((OffsetDateTime::from_unix_timestamp(at).ok()?, offset));
+        }
+        let (at, offset) = self.rule?.next_transition(t)?;
+        Some((OffsetDateTime::from_unix_timestamp(at).ok()?, offset))
+    }
+}
+
+impl OffsetProvider for Tzif {
+    fn offset_at(&self, utc: OffsetDateTime) -> UtcOffset {
+        let t = utc.unix_timestamp();
+        let i = self.transitions.partition_point(|&at| at <= t);
+        match (i.checked_sub(1), self.rule) {
+            // After the last transition, the footer's rule applies.
+            (_, Some(rule)) if i == self.transitions.len() => rule.offset_at(t),
+            (Some(prev), _) => self.types[usize::from(self.indices[prev])],
+            // Before the first transition, the first type is in effect.
+            (None, _) => self.types[0],
+        }
+    }
+}
+
+// === impl TzifError ===
+
+impl fmt::Display for TzifError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TzifError::Io(e) => write!(f, "failed to read TZif file: {}", e),
+            TzifError::Malformed(part) => write!(f, "malformed TZif {}", part),
+        }
+    }
+}
+
+impl std::error::Error for TzifError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TzifError::Io(e) => Some(e),
+            TzifError::Malformed(_) => None,
+        }
+    }
+}
+
+// === impl PosixTz ===
+
+impl PosixTz {
+    /// Parses a rule in the POSIX TZ syntax, extended as in RFC 8536 to allow
+    /// transition hours from `-167` to `167`.
+    fn parse(s: &str) -> Option<Self> {
+        let mut s = s.as_bytes();
+        skip_name(&mut s)?;
+        let std = -parse_seconds(&mut s, 24)?;
+        if s.is_empty() {
+            return Some(Self { std, dst: None });
+        }
+        skip_name(&mut s)?;
+        let offset = match s.first() {
+            Some(b',') | None => std + 3600,
+            _ => -parse_seconds(&mut s, 24)?,
+        };
+        // Like tzcode, default to the US rules when none are given.
+        let (start, end) = if s.is_empty() {
+            ((Rule::Month(3, 2, 0), 7200), (Rule::Month(11, 1, 0), 7200))
+        } else {
+            s = s.strip_prefix(b",")?;
+            let start = parse_transition(&mut s)?;
+            s = s.strip_prefix(b",")?;
+            (start, parse_transition(&mut s)?)
+        };
+        s.is_empty().then_some(Self {
+            std,
+            dst: Some(Dst { offset, start, end }),
+        })
+    }
+
+    fn offset_at(self, t: i64) -> UtcOffset {
+        let seconds = match self.dst {
+            Some(dst) => {
+                let year = year_of(t + i64::from(self.std));
+                if dst.contains(year, self.std, t) {
+                    dst.offset
+                } else {
+                    self.std
+                }
+            }
+            None => self.std,
+        };
+        UtcOffset::from_whole_seconds(seconds).unwrap_or(UtcOffset::UTC)
+    }
+
+    fn next_transition(self, t: i64) -> Option<(i64, UtcOffset)> {
+        let dst = self.dst?;
+        let year = year_of(t + i64::from(self.std));
+        let offset = |seconds| UtcOffset::from_whole_seconds(seconds).ok();
+        (year..=year + 1)
+            .flat_map(|year| {
+                let (start, end) = dst.bounds(year, self.std)?;
+                let (start, end) = ((start, dst.offset), (end, self.std));
+                Some(if start.0 < end.0 {
+                    [start, end]
+                } else {
+                    [end, start]
+                })
+            })
+            .flatten()
+            .find(|&(at, _)| at > t)
+            .and_then(|(at, seconds)| Some((at, offset(seconds)?)))
+    }
+}
+
+impl Dst {
+    /// The Unix times daylight saving time starts and ends in `year`.
+    fn bounds(&self, year: i32, std: i32) -> Option<(i64, i64)> {
+        let start = self.start.0.date(year)?;
+        let end = self.end.0.date(year)?;
+        let local =
+            |date: Date, time: i32| date.midnight().assume_utc().unix_timestamp() + i64::from(time);
+        // Start times are given in standard time, end times in daylight time.
+        Some((
+            local(start, self.start.1) - i64::from(std),
+            local(end, self.end.1) - i64::from(self.offset),
+        ))
+    }
+
+    fn contains(&self, year: i32, std: i32, t: i64) -> bool {
+        match self.bounds(year, std) {
+            Some((start, end)) if start < end => start <= t && t < end,
+            // Southern hemisphere: daylight time spans the new year.
+            Some((start, end)) => !(end <= t && t < start),
+            None => false,
+        }
+    }
+}
+
+impl Rule {
+    fn date(self, year: i32) -> Option<Date> {
+        match self {
+            Rule::Julian(day) => {
+                let leap = time::util::is_leap_year(year) && day >= 60;
+                Date::from_ordinal_date(year, day + u16::from(leap)).ok()
+            }
+            Rule::Ordinal(day) => Date::from_ordinal_date(year, day + 1).ok(),
+            Rule::Month(month, week, weekday) => {
+                let month = Month::try_from(month).ok()?;
+                let first = Date::from_calendar_date(year, month, 1).ok()?;
+                let first_weekday = first.weekday().number_days_from_sunday();
+                let mut day = 1 + (weekday + 7 - first_weekday) % 7 + (week - 1) * 7;
+                while day > month.length(year) {
+                    day -= 7;
+                }
+                Date::from_calendar_date(year, month, day).ok()
+            }
+        }
+    }
+}
+
+/// The calendar year of the Unix time `t`.
+fn year_of(t: i64) -> i32 {
+    OffsetDateTime::from_unix_timestamp(t).map_or(1970, |dt| dt.year())
+}
+
+/// Skips a zone abbreviation: three or more letters, or anything in `<>`.
+fn skip_name(s: &mut &[u8]) -> Option<()> {
+    let len = if s.first() == Some(&b'<') {
+        s.iter().position(|&b| b == b'>')? + 1
+    } else {
+        let len = s.iter().take_while(|b| b.is_ascii_alphabetic()).count();
+        if len < 3 {
+            return None;
+        }
+        len
+    };
+    *s = &s[len..];
+    Some(())
+}
+
+/// Parses `[+-]hh[:mm[:ss]]` into seconds, with hours up to `max_hours`.
+fn parse_seconds(s: &mut &[u8], max_hours: i32) -> Option<i32> {
+    let sign = match s.first() {
+        Some(b'-') => -1,
+        Some(b'+') => 1,
+        _ => 0,
+    };
+    if sign != 0 {
+        *s = &s[1..];
+    }
+    let hours = parse_number(s, 3)?;
+    let mut seconds = hours * 3600;
+    for scale in [60, 1] {
+        match s.strip_prefix(b":") {
+            Some(rest) => {
+                *s = rest;
+                seconds += parse_number(s, 2).filter(|&n| n < 60)? * scale;
+            }
+            None => break,
+        }
+    }
+    (hours <= max_hours).then_some(if sign < 0 { -seconds } else { seconds })
+}
+
+fn parse_number(s: &mut &[u8], max_digits: usize) -> Option<i32> {
+    let len = s
+        .iter()
+        .take(max_digits)
+        .take_while(|b| b.is_ascii_digit())
+        .count();
+    if len == 0 {
+        return None;
+    }
+    let n = std::str::from_utf8(&s[..len]).ok()?.parse().ok()?;
+    *s = &s[len..];
+    Some(n)
+}
+
+/// Parses a transition rule with its optional `/time`, which defaults to
+/// 02:00.
+fn parse_transition(s: &mut &[u8]) -> Option<(Rule, i32)> {
+    let rule = match s.first()? {
+        b'J' => {
+            *s = &s[1..];
+            Rule::Julian(parse_number(s, 3).filter(|n| (1..=365).contains(n))? as u16)
+        }
+        b'M' => {
+            *s = &s[1..];
+            let month = parse_number(s, 2).filter(|n| (1..=12).contains(n))?;
+            *s = s.strip_prefix(b".")?;
+            let week = parse_number(s, 1).filter(|n| (1..=5).contains(n))?;
+            *s = s.strip_prefix(b".")?;
+            let weekday = parse_number(s, 1).filter(|n| (0..=6).contains(n))?;
+            Rule::Month(month as u8, week as u8, weekday as u8)
+        }
+        _ => Rule::Ordinal(parse_number(s, 3).filter(|n| (0..=365).contains(n))? as u16),
+    };
+    let time = match s.strip_prefix(b"/") {
+        Some(rest) => {
+            *s = rest;
+            parse_seconds(s, 167)?
+        }
+        None => 7200,
+    };
+    Some((rule, time))
+}
+
+// === impl Header ===
+
+/// The counts in a TZif header.
+struct Header {
+    version: u8,
+    isutcnt: usize,
+    isstdcnt: usize,
+    leapcnt: usize,
+    timecnt: usize,
+    typecnt: usize,
+    charcnt: usize,
+}
+
+impl Header {
+    fn read(input: &mut Input<'_>) -> Result<Self, TzifError> {
+        let header = input.take(44)?;
+        if &header[..4] != b"TZif" {
+            return Err(TzifError::Malformed("header"));
+        }
+        let version = match header[4] {
+            0 => 0,
+            v @ b'2'..=b'9' => v - b'0',
+            _ => return Err(TzifError::Malformed("version")),
+        };
+        let count = |i: usize| {
+            let at = 20 + i * 4;
+            u32::from_be_bytes([header[at], header[at + 1], header[at + 2], header[at + 3]])
+                as usize
+        };
+        Ok(Self {
+            version,
+            isutcnt: count(0),
+            isstdcnt: count(1),
+            leapcnt: count(2),
+            timecnt: count(3),
+            typecnt: count(4),
+            charcnt: count(5),
+        })
+    }
+
+    /// The length of the data block following the header.
+    fn block_len(&self, time_len: usize) -> usize {
+        self.timecnt * (time_len + 1)
+            + self.typecnt * 6
+            + self.charcnt
+            + self.leapcnt * (time_len + 4)
+            + self.isstdcnt
+            + self.isutcnt
+    }
+}
+
+/// Reads a big-endian two's complement integer of up to eight bytes.
+fn be_int(bytes: &[u8]) -> i64 {
+    let sign = if bytes[0] & 0x80 != 0 { -1 } else { 0 };
+    bytes.iter().fold(sign, |n, &b| n << 8 | i64::from(b))
+}
+
+struct Input<'a>(&'a [u8]);
+
+impl<'a> Input<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], TzifError> {
+        if self.0.len() < len {
+            return Err(TzifError::Malformed("length"));
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(taken)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PosixTz, Tzif};
+    use crate::offset::OffsetProvider;
+    use time::macros::{datetime, offset};
+
+    /// Builds a version 2 file with one transition to `+01:00` at 1000 and
+    /// `footer`.
+    fn tzif(footer: &str) -> Vec<u8> {
+        let header = |timecnt: u32, typecnt: u32| {
+            let mut header = b"TZif2".to_vec();
+            header.extend([0; 15]);
+            for count in [0, 0, 0, timecnt, typecnt, 4] {
+                header.extend(u32::to_be_bytes(count));
+            }
+            header
+        };
+        let types = [0i32, 3600]
+            .iter()
+            .flat_map(|offset| offset.to_be_bytes().into_iter().chain([0, 0]))
+            .collect::<Vec<_>>();
+        let mut data = header(0, 1);
+        data.extend([0; 6]);
+        data.extend(b"UTC\0");
+        data.extend(header(1, 2));
+        data.extend(1000i64.to_be_bytes());
+        data.push(1);
+        data.extend(&types);
+        data.extend(b"UTC\0");
+        data.extend(format!("\n{}\n", footer).as_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse() {
+        let zone = Tzif::parse(&tzif("")).unwrap();
+        assert_eq!(zone.offset_at(datetime!(1970-01-01 0:00 UTC)), offset!(UTC));
+        assert_eq!(zone.offset_at(datetime!(2024-01-01 0:00 UTC)), offset!(+1));
+        let (at, offset) = zone
+            .next_transition(datetime!(1970-01-01 0:00 UTC))
+            .unwrap();
+        assert_eq!((at.unix_timestamp(), offset), (1000, offset!(+1)));
+        assert_eq!(zone.next_transition(at), None);
+
+        assert!(Tzif::parse(b"TZif2").is_err());
+        assert!(Tzif::parse(&tzif("bogus")).is_err());
+    }
+
+    #[test]
+    fn test_footer() {
+        let zone = Tzif::parse(&tzif("EST5EDT,M3.2.0,M11.1.0")).unwrap();
+        assert_eq!(zone.offset_at(datetime!(2024-01-15 12:00 UTC)), offset!(-5));
+        assert_eq!(zone.offset_at(datetime!(2024-07-15 12:00 UTC)), offset!(-4));
+        // 2024-03-10 02:00 EST.
+        assert_eq!(zone.offset_at(datetime!(2024-03-10 6:59 UTC)), offset!(-5));
+        assert_eq!(zone.offset_at(datetime!(2024-03-10 7:00 UTC)), offset!(-4));
+        let (at, offset) = zone
+            .next_transition(datetime!(2024-07-15 12:00 UTC))
+            .unwrap();
+        assert_eq!((at, offset), (datetime!(2024-11-03 6:00 UTC), offset!(-5)));
+
+        // Southern hemisphere, with daylight time over the new year.
+        let sydney = PosixTz::parse("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+        let t = |dt: time::OffsetDateTime| dt.unix_timestamp();
+        assert_eq!(
+            sydney.offset_at(t(datetime!(2024-01-15 0:00 UTC))),
+            offset!(+11)
+        );
+        assert_eq!(
+            sydney.offset_at(t(datetime!(2024-07-15 0:00 UTC))),
+            offset!(+10)
+        );
+        assert_eq!(
+            PosixTz::parse("<+0330>-3:30").unwrap().offset_at(0),
+            offset!(+3:30)
+        );
+    }
+
+    #[test]
+    fn test_from_tz() {
+        let zone = Tzif::from_tz("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        assert_eq!(zone.offset_at(datetime!(2024-01-15 12:00 UTC)), offset!(+1));
+        assert_eq!(zone.offset_at(datetime!(2024-07-15 12:00 UTC)), offset!(+2));
+        let (at, offset) = zone
+            .next_transition(datetime!(2024-07-15 12:00 UTC))
+            .unwrap();
+        assert_eq!((at, offset), (datetime!(2024-10-27 1:00 UTC), offset!(+1)));
+
+        let utc = Tzif::from_tz(":").unwrap();
+        assert_eq!(utc.offset_at(datetime!(2024-07-15 12:00 UTC)), offset!(UTC));
+        assert!(Tzif::from_tz("../etc/passwd").is_err());
+        assert!(Tzif::from_tz(":/nonexistent/zone").is_err());
+
+        if let Ok(zone) = Tzif::from_tz(":America/New_York") {
+            assert_eq!(zone.offset_at(datetime!(1990-07-01 0:00 UTC)), offset!(-4));
+        }
+    }
+
+    #[test]
+    fn test_system_zoneinfo() {
+        let Ok(zone) = Tzif::read("/usr/share/zoneinfo/America/New_York") else {
+            return;
+        };
+        assert_eq!(zone.offset_at(datetime!(1990-07-01 0:00 UTC)), offset!(-4));
+        assert_eq!(zone.offset_at(datetime!(2100-01-01 0:00 UTC)), offset!(-5));
+        let (at, offset) = zone
+            .next_transition(datetime!(2090-01-01 0:00 UTC))
+            .unwrap();
+        assert_eq!((at, offset), (datetime!(2090-03-12 7:00 UTC), offset!(-4)));
+    }
+}
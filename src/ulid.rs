@@ -0,0 +1,134 @@
+//! [ULID]-style timestamps, which sort like the time they encode.
+//!
+//! [ULID]: https://github.com/ulid/spec
+
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+
+use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
+
+use crate::clock::{ClockSource, SystemClock};
+
+/// Formats the current time as the timestamp of a [ULID]: 48 bits of Unix
+/// milliseconds in Crockford's base32, e.g. `01HWRY2KBV`, or as a whole
+/// ULID with 80 random bits appended, e.g. `01HWRY2KBV3Q5GJ6EYFT8JM7RS`.
+///
+/// Both sort lexicographically in time order, so log lines can double as
+/// sortable correlation IDs. The random bits are unique enough for that,
+/// but not cryptographically secure.
+///
+/// [ULID]: https://github.com/ulid/spec
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::UlidTime;
+///
+/// let collector = tracing_subscriber::fmt()
+///     .with_timer(UlidTime::full());
+/// # drop(collector);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UlidTime<C = SystemClock> {
+    random: bool,
+    clock: C,
+}
+
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// The largest timestamp a ULID holds.
+const MILLIS_MAX: u64 = (1 << 48) - 1;
+
+// === impl UlidTime ===
+
+impl UlidTime {
+    /// Returns a formatter writing the 10-character timestamp of a ULID.
+    pub fn timestamp() -> Self {
+        Self {
+            random: false,
+            clock: SystemClock::new(),
+        }
+    }
+
+    /// Returns a formatter writing a whole, 26-character ULID.
+    pub fn full() -> Self {
+        Self {
+            random: true,
+            ..Self::timestamp()
+        }
+    }
+}
+
+impl<C> UlidTime<C> {
+    /// Reads the time from `clock` instead of the system clock.
+    pub fn with_clock<C2: ClockSource>(self, clock: C2) -> UlidTime<C2> {
+        UlidTime {
+            random: self.random,
+            clock,
+        }
+    }
+}
+
+impl<C: ClockSource> UlidTime<C> {
+    fn write_now(&self, w: &mut impl fmt::Write) -> fmt::Result {
+        let nanos = self.clock.now_utc().unix_timestamp_nanos();
+        let millis = (nanos / 1_000_000).clamp(0, MILLIS_MAX.into()) as u64;
+        if self.random {
+            encode(u128::from(millis) << 80 | random_bits(nanos), 26, w)
+        } else {
+            encode(millis.into(), 10, w)
+        }
+    }
+}
+
+impl<C: ClockSource> FormatTime for UlidTime<C> {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        self.write_now(w)
+    }
+}
+
+/// Writes the low `5 * chars` bits of `value` in Crockford's base32, most
+/// significant first.
+fn encode(value: u128, chars: usize, w: &mut impl fmt::Write) -> fmt::Result {
+    for i in (0..chars).rev() {
+        let digit = (value >> (5 * i)) as usize & 0x1f;
+        w.write_char(CROCKFORD[digit].into())?;
+    }
+    Ok(())
+}
+
+/// 80 random bits, from the randomly keyed hasher of the standard library.
+fn random_bits(nanos: i128) -> u128 {
+    let state = RandomState::new();
+    let [mut high, mut low] = [state.build_hasher(), state.build_hasher()];
+    high.write_i128(nanos);
+    low.write_i128(!nanos);
+    (u128::from(high.finish() & 0xffff) << 64 | u128::from(low.finish())) & ((1 << 80) - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UlidTime;
+    use crate::clock::Fixed;
+    use time::macros::datetime;
+
+    #[test]
+    fn test_ulid_time() {
+        let now = Fixed(datetime!(2024-05-01 02:00:00.123 UTC));
+        let mut s = String::new();
+        UlidTime::timestamp()
+            .with_clock(now)
+            .write_now(&mut s)
+            .unwrap();
+        assert_eq!(s, "01HWRY2KBV");
+
+        let (mut a, mut b) = (String::new(), String::new());
+        let timer = UlidTime::full().with_clock(now);
+        timer.write_now(&mut a).unwrap();
+        timer.write_now(&mut b).unwrap();
+        assert_eq!(a.len(), 26);
+        assert!(a.starts_with("01HWRY2KBV"));
+        assert_ne!(a, b);
+    }
+}
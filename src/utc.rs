@@ -0,0 +1,113 @@
+//! Timestamps in UTC, without the offset handling of [`LocalTime`].
+
+use std::fmt;
+
+use time::{format_description::well_known, formatting::Formattable, UtcOffset};
+
+use crate::clock::{ClockSource, SystemClock};
+use crate::{format_datetime, rfc3339};
+
+#[cfg(doc)]
+use crate::LocalTime;
+
+/// Formats the current UTC time using a [formatter] from the [`time` crate].
+///
+/// Unlike tracing-subscriber's own `UtcTime`, this needs no tracing-subscriber
+/// feature flags, and it reads the same clocks as [`LocalTime`].
+///
+/// [formatter]: https://docs.rs/time/0.3/time/formatting/trait.Formattable.html
+/// [`time` crate]: https://docs.rs/time/0.3/time/
+///
+/// # Examples
+///
+/// ```
+/// use clia_local_time::UtcTime;
+/// use time::macros::format_description;
+///
+/// let collector = tracing_subscriber::fmt()
+///     .with_timer(UtcTime::new(format_description!("[hour]:[minute]:[second]")));
+/// # drop(collector);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct UtcTime<F, C = SystemClock> {
+    format: F,
+    clock: C,
+    fast_rfc3339: bool,
+}
+
+// === impl UtcTime ===
+
+impl UtcTime<well_known::Rfc3339> {
+    /// Returns a formatter for [RFC 3339] timestamps in UTC, e.g.
+    /// `2024-05-01T02:00:00Z`.
+    ///
+    /// [RFC 3339]: https://datatracker.ietf.org/doc/html/rfc3339
+    pub fn rfc_3339() -> Self {
+        Self {
+            fast_rfc3339: true,
+            ..Self::new(well_known::Rfc3339)
+        }
+    }
+}
+
+impl<F: Formattable> UtcTime<F> {
+    /// Returns a formatter rendering the current UTC time in `format`.
+    pub fn new(format: F) -> Self {
+        Self {
+            format,
+            clock: SystemClock::new(),
+            fast_rfc3339: false,
+        }
+    }
+}
+
+impl<F, C> UtcTime<F, C> {
+    /// Reads the time from `clock` instead of the system clock.
+    pub fn with_clock<C2: ClockSource>(self, clock: C2) -> UtcTime<F, C2> {
+        UtcTime {
+            format: self.format,
+            clock,
+            fast_rfc3339: self.fast_rfc3339,
+        }
+    }
+}
+
+impl<F: Formattable, C: ClockSource> UtcTime<F, C> {
+    /// Writes the current time, as every supported version of
+    /// tracing-subscriber's `FormatTime` does.
+    pub(crate) fn write_now(&self, w: &mut impl fmt::Write) -> fmt::Result {
+        let now = self.clock.now_utc().to_offset(UtcOffset::UTC);
+        if self.fast_rfc3339 {
+            rfc3339::write(now, w)
+        } else {
+            format_datetime(now, w, &self.format)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UtcTime;
+    use crate::clock::Fixed;
+    use time::macros::{datetime, format_description};
+
+    #[test]
+    fn test_utc_time() {
+        let now = datetime!(2024-05-01 10:00:00.5 +8);
+        let mut s = String::new();
+        UtcTime::rfc_3339()
+            .with_clock(Fixed(now))
+            .write_now(&mut s)
+            .unwrap();
+        assert_eq!(s, "2024-05-01T02:00:00.5Z");
+
+        let mut s = String::new();
+        UtcTime::new(format_description!(
+            "[hour]:[minute] [offset_hour sign:mandatory]"
+        ))
+        .with_clock(Fixed(now))
+        .write_now(&mut s)
+        .unwrap();
+        assert_eq!(s, "02:00 +00");
+    }
+}
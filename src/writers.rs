@@ -0,0 +1,103 @@
+//! Timers for the console and file layers of one subscriber, derived from a
+//! single [`LocalTime`].
+
+use std::sync::Arc;
+
+use time::{
+    format_description::{well_known::Rfc3339, StaticFormatDescription},
+    macros::format_description,
+    UtcOffset,
+};
+
+use crate::{offset::FixedOffset, repeat::Repeats, LocalTime};
+
+// === impl LocalTime ===
+
+impl<F, P: Clone, C: Clone> LocalTime<F, P, C> {
+    /// Returns a timer for the console, rendering a short
+    /// `[hour]:[minute]:[second]` in this timer's offset.
+    ///
+    /// Together with [`file_timer`](LocalTime::file_timer), this derives the
+    /// timers of every layer from one configuration, so their clock, skew and
+    /// other settings can't drift apart.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clia_local_time::LocalTime;
+    /// use tracing_subscriber::{fmt, layer::SubscriberExt};
+    ///
+    /// let timer = LocalTime::rfc_3339().coarse();
+    /// let subscriber = tracing_subscriber::registry()
+    ///     .with(fmt::layer().with_timer(timer.console_timer()))
+    ///     .with(
+    ///         fmt::layer()
+    ///             .with_ansi(false)
+    ///             .with_timer(timer.file_timer())
+    ///             .with_writer(std::io::sink),
+    ///     );
+    /// # drop(subscriber);
+    /// ```
+    pub fn console_timer(&self) -> LocalTime<StaticFormatDescription, P, C> {
+        self.with_parts(
+            format_description!("[hour]:[minute]:[second]"),
+            self.offset.clone(),
+        )
+    }
+
+    /// Returns a timer for log files, rendering full [RFC 3339] timestamps
+    /// in UTC, e.g. `2024-05-01T02:00:00.123Z`. See
+    /// [`console_timer`](LocalTime::console_timer).
+    ///
+    /// [RFC 3339]: https://datatracker.ietf.org/doc/html/rfc3339
+    pub fn file_timer(&self) -> LocalTime<Rfc3339, FixedOffset, C> {
+        LocalTime {
+            fast_rfc3339: true,
+            ..self.with_parts(Rfc3339, FixedOffset(UtcOffset::UTC))
+        }
+    }
+
+    /// Returns a timer with this one's settings, but `format` and `offset`.
+    fn with_parts<F2, P2>(&self, format: F2, offset: P2) -> LocalTime<F2, P2, C> {
+        LocalTime {
+            format,
+            offset,
+            clock: self.clock.clone(),
+            prefix: self.prefix.clone(),
+            suffix: self.suffix.clone(),
+            width: self.width,
+            skew: self.skew,
+            scale: self.scale,
+            source_tag: self.source_tag,
+            ladder: self.ladder.clone(),
+            monotonic: self.monotonic.clone(),
+            fast_rfc3339: false,
+            auto_offset: self.auto_offset,
+            on_error: self.on_error.clone(),
+            stats: self.stats.clone(),
+            round_to: self.round_to,
+            // Each layer renders its own timestamps, which repeat separately.
+            repeats: self.repeats.as_ref().map(|_| Arc::new(Repeats::default())),
+            ansi_style: self.ansi_style.clone(),
+            numeric_utc: self.numeric_utc,
+            decimal_comma: self.decimal_comma,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{clock::Fixed, testing::render, LocalTime};
+    use time::{macros::datetime, Duration};
+
+    #[test]
+    fn test_writer_timers() {
+        let timer = LocalTime::w3c((8, 0, 0))
+            .with_clock(Fixed(datetime!(2024-05-01 02:00:00.5 UTC)))
+            .with_skew(Duration::minutes(1));
+        let (console, file) = (timer.console_timer(), timer.file_timer());
+
+        assert_eq!(render(&console, console.now()), "10:01:00");
+        assert_eq!(render(&file, file.now()), "2024-05-01T02:01:00.5Z");
+    }
+}